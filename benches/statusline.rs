@@ -0,0 +1,119 @@
+//! Statusline rendering benchmarks
+//!
+//! `build_statusline` runs on every statusline refresh, so it is the hot path.
+//! Each scenario renders a representative session state; run with `cargo bench`.
+
+use std::hint::black_box;
+
+use claude_code_personalities::config::PersonalityPreferences;
+use claude_code_personalities::state::SessionState;
+use claude_code_personalities::statusline::{WorkspaceInfo, build_statusline};
+use claude_code_personalities::types::Activity;
+use criterion::{Criterion, criterion_group, criterion_main};
+
+/// A session that has just started: no git, no job, default personality
+fn minimal_state() -> SessionState {
+    SessionState {
+        session_id: "bench".to_string(),
+        ..Default::default()
+    }
+}
+
+/// A busy session with every state-backed section populated
+fn full_state() -> SessionState {
+    SessionState {
+        session_id: "bench".to_string(),
+        activity: Activity::Editing,
+        current_job: Some("statusline.rs".to_string()),
+        current_file: Some("src/statusline/mod.rs".to_string()),
+        git_branch: Some("main".to_string()),
+        git_dirty: Some(true),
+        git_dirty_count: Some(7),
+        git_stash_count: Some(2),
+        personality: "(ง •̀_•́)ง Borrow Checker Whisperer".to_string(),
+        consecutive_actions: 12,
+        total_actions: 140,
+        error_count: 1,
+        recent_activities: vec![
+            Activity::Reading,
+            Activity::Searching,
+            Activity::Editing,
+            Activity::Testing,
+            Activity::Editing,
+        ],
+        ..Default::default()
+    }
+}
+
+/// A feature branch long enough to need truncating
+fn long_branch_state() -> SessionState {
+    SessionState {
+        git_branch: Some(
+            "feature/JIRA-12345-rework-the-statusline-renderer-for-narrow-terminals".to_string(),
+        ),
+        ..full_state()
+    }
+}
+
+/// A working tree with more dirty files than the count cap shows
+fn max_dirty_state() -> SessionState {
+    SessionState {
+        git_dirty_count: Some(usize::MAX),
+        ..full_state()
+    }
+}
+
+/// Preferences with every optional section that doesn't touch the disk turned on
+fn full_prefs() -> PersonalityPreferences {
+    let mut prefs = PersonalityPreferences {
+        show_action_count: true,
+        show_activity_trail: true,
+        show_context_usage: true,
+        show_git_stash: true,
+        show_error_count: true,
+        show_clock: true,
+        ..Default::default()
+    };
+    prefs.display.show_debug_info = true;
+    prefs
+}
+
+fn workspace() -> WorkspaceInfo {
+    WorkspaceInfo {
+        current_dir: Some("/home/dev/projects/claude-code-personalities/src".to_string()),
+        project_dir: Some("/home/dev/projects/claude-code-personalities".to_string()),
+    }
+}
+
+fn bench_build_statusline(c: &mut Criterion) {
+    let default_prefs = PersonalityPreferences::default();
+    let full_prefs = full_prefs();
+    let workspace = workspace();
+
+    let scenarios = [
+        ("minimal", minimal_state(), &default_prefs),
+        ("full_sections", full_state(), &full_prefs),
+        ("long_git_branch", long_branch_state(), &full_prefs),
+        ("max_dirty_count", max_dirty_state(), &full_prefs),
+    ];
+
+    let mut group = c.benchmark_group("build_statusline");
+    for (name, state, prefs) in &scenarios {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                build_statusline(
+                    black_box(state),
+                    black_box("Opus 4.1"),
+                    black_box(prefs),
+                    Some(&workspace),
+                    Some("9.9.9"),
+                    Some(64),
+                )
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_build_statusline);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+//! Crash-safe replacement of the files this tool keeps on disk
+//!
+//! Preferences, session state, usage stats and the day streak are all written
+//! to a uniquely named temp file beside the target and renamed into place. A
+//! reader never sees a half-written file, and a write that dies midway leaves
+//! the previous contents intact.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Temp file beside `path` that no other writer uses: `<name>.<pid>.<n>.tmp`
+#[must_use]
+pub fn temp_path_for(path: &Path) -> PathBuf {
+    static SAVE_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        SAVE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
+/// Write `content` to a fresh temp file next to `path`, removing it again if
+/// the write fails
+///
+/// # Errors
+///
+/// Returns an error if the temp file cannot be written.
+pub async fn write_temp_file(path: &Path, content: &str) -> std::io::Result<PathBuf> {
+    let temp_path = temp_path_for(path);
+    if let Err(e) = tokio::fs::write(&temp_path, content).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e);
+    }
+    Ok(temp_path)
+}
+
+/// Replace `path` with `content` through a temp file and a rename
+///
+/// # Errors
+///
+/// Returns an error if the temp file cannot be written or renamed into place.
+pub async fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    // Rename onto the real file so a symlinked target stays a symlink
+    let path = tokio::fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf());
+    let temp_path = write_temp_file(&path, content).await?;
+    if let Err(e) = tokio::fs::rename(&temp_path, &path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Blocking version of [`write_atomically`] for callers outside the async runtime
+///
+/// # Errors
+///
+/// Returns an error if the temp file cannot be written or renamed into place.
+pub fn write_atomically_blocking(path: &Path, content: &str) -> std::io::Result<()> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let temp_path = temp_path_for(&path);
+    let result =
+        std::fs::write(&temp_path, content).and_then(|()| std::fs::rename(&temp_path, &path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_paths_are_unique_and_beside_the_target() {
+        let path = Path::new("/tmp/state/session.json");
+        let first = temp_path_for(path);
+        let second = temp_path_for(path);
+
+        assert_ne!(first, second);
+        assert_eq!(first.parent(), path.parent());
+        let name = first.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("session.json."), "{name}");
+        assert!(name.ends_with(".tmp"), "{name}");
+    }
+
+    #[test]
+    fn test_blocking_write_replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stats.json");
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomically_blocking(&path, "new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
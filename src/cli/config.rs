@@ -4,32 +4,74 @@
 //! display preferences, mood settings, and advanced options.
 
 use anyhow::{Context, Result};
+use clap::ArgMatches;
 use cliclack::{confirm, intro, outro, select};
 use colored::Colorize;
 
 use crate::cli::interactive_config;
-use crate::config::PersonalityPreferences;
-use crate::icons::{ICON_CHECK, ICON_ERROR, ICON_INFO, ICON_WARNING};
-use crate::state::SessionState;
+use crate::config::preferences::CONFIG_VERSION;
+use crate::config::{GitElement, PersonalityPreferences, PreferenceGroup, StatuslineSection};
+use crate::icons::{ICON_CHECK, ICON_ERROR, ICON_INFO, ICON_WARNING, resolve_activity_icon};
+use crate::kaomoji::{PACKS, find_pack};
+use crate::state::{PersonalityModifier, SessionState};
 use crate::statusline::{WorkspaceInfo, build_statusline};
 use crate::theme::Theme;
+use crate::theme::colors::validate_theme_file;
 use crate::types::Activity;
 
 /// Handle configuration subcommands
-pub async fn handle_config_command(subcommand: Option<&str>) -> Result<()> {
+pub async fn handle_config_command(subcommand: Option<(&str, &ArgMatches)>) -> Result<()> {
     match subcommand {
-        Some("display") => configure_display().await,
-        Some("theme") => {
+        Some(("display", _)) => configure_display().await,
+        Some(("theme", matches)) if matches.subcommand_matches("set").is_some() => {
+            let name = matches
+                .subcommand_matches("set")
+                .and_then(|set| set.get_one::<String>("name"))
+                .map(String::as_str)
+                .unwrap_or_default();
+            set_theme(name).await
+        }
+        Some(("theme", matches)) if matches.subcommand_matches("validate").is_some() => {
+            let file = matches
+                .subcommand_matches("validate")
+                .and_then(|validate| validate.get_one::<String>("file"))
+                .map(String::as_str)
+                .unwrap_or_default();
+            validate_theme(file).await
+        }
+        Some(("theme", matches)) if matches.get_flag("preview") => {
+            let prefs = PersonalityPreferences::load_or_default()
+                .await
+                .with_context(|| "Failed to load current personality preferences")?;
+            print!("{}", render_theme_gallery(&prefs));
+            Ok(())
+        }
+        Some(("theme", _)) => {
+            let prefs = PersonalityPreferences::load_or_default()
+                .await
+                .with_context(|| "Failed to load current personality preferences")?;
             println!(
-                "{} Theme configuration is temporarily disabled while the new Default theme is being finalized.",
-                ICON_WARNING.yellow()
+                "{} Current theme: {}",
+                ICON_INFO.cyan(),
+                prefs.theme.display_name().bold()
             );
-            println!("The Default theme with context-aware colors is now active by default.");
+            println!("Use 'config theme --preview' to see how every theme looks.");
+            println!("Use 'config theme set <name>' to switch, e.g. 'config theme set dracula'.");
             Ok(())
         }
-        Some("reset") => reset_configuration().await,
+        Some(("icon", matches)) => configure_activity_icon(matches).await,
+        Some(("face", matches)) => configure_mood_face(matches).await,
+        Some(("personality-pack", matches)) => configure_kaomoji_pack(matches).await,
+        Some(("order", matches)) => configure_section_order(matches).await,
+        Some(("diff", _)) => show_config_diff().await,
+        Some(("show", _)) => show_config().await,
+        Some(("migrate", _)) => migrate_config().await,
+        Some(("reset", matches)) => match matches.get_one::<String>("section") {
+            Some(section) => reset_preference_group(section).await,
+            None => reset_configuration().await,
+        },
         None => interactive_config_menu().await,
-        Some(unknown) => {
+        Some((unknown, _)) => {
             println!(
                 "{} Unknown config subcommand: {}",
                 ICON_ERROR.red(),
@@ -66,7 +108,7 @@ async fn interactive_config_menu() -> Result<()> {
             || "Failed to get user selection. Interactive prompt was cancelled or failed.",
         )?;
 
-    match selection.as_ref() {
+    match selection {
         "display" => configure_display().await,
         "theme" => configure_theme().await, // Keep handler in case called directly
         "reset" => reset_configuration().await,
@@ -209,6 +251,503 @@ fn preview_theme(theme: &Theme) {
     println!("    Haiku: {}", theme.apply_model_color("Haiku", "Haiku"));
 }
 
+/// Render a sample statusline in every available theme, one labeled line per theme
+fn render_theme_gallery(prefs: &PersonalityPreferences) -> String {
+    let state = create_preview_state();
+    let workspace = create_preview_workspace();
+    let label_width = Theme::all()
+        .iter()
+        .map(|theme| theme.display_name().len())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for theme in Theme::all() {
+        let label = theme.display_name();
+        let mut theme_prefs = prefs.clone();
+        theme_prefs.theme = theme;
+        theme_prefs.use_colors = true;
+        theme_prefs.show_git = true;
+        theme_prefs.show_activity = true;
+        theme_prefs.show_personality = true;
+        theme_prefs.show_model = true;
+
+        let statusline = build_statusline(
+            &state,
+            "Sonnet",
+            &theme_prefs,
+            Some(&workspace),
+            None,
+            Some(62),
+        );
+        output.push_str(&format!(
+            "  {:<label_width$}  {}\n",
+            label.bold(),
+            statusline
+        ));
+    }
+    output
+}
+
+/// Set the theme by name without the interactive picker
+async fn set_theme(name: &str) -> Result<()> {
+    let mut prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+
+    apply_theme_name(&mut prefs, name)?;
+    prefs
+        .save()
+        .await
+        .with_context(|| "Failed to save theme configuration")?;
+
+    println!(
+        "{} Theme '{}' applied",
+        ICON_CHECK.green(),
+        prefs.theme.display_name()
+    );
+    Ok(())
+}
+
+/// Parse a theme name and assign it, leaving `prefs` untouched on unknown names
+fn apply_theme_name(prefs: &mut PersonalityPreferences, name: &str) -> Result<()> {
+    prefs.theme = name.parse::<Theme>().map_err(|e| anyhow::anyhow!(e))?;
+    Ok(())
+}
+
+/// Validate a theme color file, listing every invalid line
+async fn validate_theme(file: &str) -> Result<()> {
+    let content = tokio::fs::read_to_string(file)
+        .await
+        .with_context(|| format!("Failed to read theme file {file}"))?;
+
+    let problems = validate_theme_file(&content);
+    if problems.is_empty() {
+        println!(
+            "{} {} is a valid theme file",
+            ICON_CHECK.green(),
+            file.bold()
+        );
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("{} {problem}", ICON_ERROR.red());
+    }
+    Err(anyhow::anyhow!(
+        "{file} has {} invalid line(s)",
+        problems.len()
+    ))
+}
+
+/// Set, clear, or show the icon override for a single activity
+async fn configure_activity_icon(matches: &ArgMatches) -> Result<()> {
+    let name = matches
+        .get_one::<String>("activity")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let activity = parse_activity_name(name)?;
+
+    let mut prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+
+    if matches.get_flag("reset") {
+        prefs.activity_icons.remove(&activity);
+    } else if let Some(icon) = matches.get_one::<String>("icon") {
+        prefs.activity_icons.insert(activity.clone(), icon.clone());
+    } else {
+        let icon = resolve_activity_icon(&activity, &prefs.activity_icons, prefs.icon_mode);
+        if icon.is_empty() {
+            println!("{activity}: (no icon)");
+        } else {
+            println!("{activity}: {icon}");
+        }
+        return Ok(());
+    }
+
+    prefs
+        .save()
+        .await
+        .with_context(|| "Failed to save activity icon configuration")?;
+
+    println!(
+        "{} Icon for {} updated",
+        ICON_CHECK.green(),
+        activity.to_string().bold()
+    );
+    Ok(())
+}
+
+/// Set, clear, or show the custom face for a mood
+async fn configure_mood_face(matches: &ArgMatches) -> Result<()> {
+    let mood = matches
+        .get_one::<String>("mood")
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    let mut prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+
+    let slot = match mood {
+        "frustrated" => &mut prefs.frustrated_face,
+        "in-the-zone" => &mut prefs.in_the_zone_face,
+        "normal" => &mut prefs.normal_face,
+        _ => return Err(anyhow::anyhow!("Unknown mood '{mood}'")),
+    };
+
+    if matches.get_flag("reset") {
+        *slot = None;
+    } else if let Some(face) = matches.get_one::<String>("face") {
+        *slot = Some(face.clone());
+    } else {
+        match slot
+            .as_deref()
+            .map(str::trim)
+            .filter(|face| !face.is_empty())
+        {
+            Some(face) => println!("{mood}: {face}"),
+            None => println!("{mood}: (built-in)"),
+        }
+        return Ok(());
+    }
+
+    prefs
+        .save()
+        .await
+        .with_context(|| "Failed to save mood face configuration")?;
+
+    println!("{} Face for {} updated", ICON_CHECK.green(), mood.bold());
+    Ok(())
+}
+
+/// Set or show the selected kaomoji pack
+async fn configure_kaomoji_pack(matches: &ArgMatches) -> Result<()> {
+    let mut prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+
+    let Some(name) = matches.get_one::<String>("pack") else {
+        let current = prefs.kaomoji_pack().map_or("default", |pack| pack.name);
+        println!(
+            "Current pack: {}
+",
+            current.bold()
+        );
+        println!("  {:<8}  Built-in faces", "default");
+        for pack in PACKS {
+            println!("  {:<8}  {}", pack.name, pack.description);
+        }
+        return Ok(());
+    };
+
+    apply_kaomoji_pack(&mut prefs, name)?;
+    prefs
+        .save()
+        .await
+        .with_context(|| "Failed to save kaomoji pack")?;
+
+    println!(
+        "{} Kaomoji pack set to {}",
+        ICON_CHECK.green(),
+        prefs
+            .kaomoji_pack()
+            .map_or("default", |pack| pack.name)
+            .bold()
+    );
+    Ok(())
+}
+
+/// Select a pack by name, or clear it for `default`, leaving `prefs` untouched on unknown names
+fn apply_kaomoji_pack(prefs: &mut PersonalityPreferences, name: &str) -> Result<()> {
+    if name.trim().eq_ignore_ascii_case("default") {
+        prefs.kaomoji_pack = None;
+    } else {
+        let pack = find_pack(name).map_err(|e| anyhow::anyhow!(e))?;
+        prefs.kaomoji_pack = Some(pack.name.to_string());
+    }
+    Ok(())
+}
+
+/// Set or show the order of statusline sections
+async fn configure_section_order(matches: &ArgMatches) -> Result<()> {
+    let mut prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+
+    let Some(sections) = matches.get_one::<String>("sections") else {
+        let names: Vec<&str> = prefs
+            .section_order
+            .iter()
+            .map(StatuslineSection::name)
+            .collect();
+        println!("{}", names.join(","));
+        return Ok(());
+    };
+
+    prefs.section_order = StatuslineSection::parse_order(sections, matches.get_flag("exact"))
+        .map_err(|e| anyhow::anyhow!(e))?;
+    prefs
+        .save()
+        .await
+        .with_context(|| "Failed to save section order")?;
+
+    println!("{} Section order updated", ICON_CHECK.green());
+    Ok(())
+}
+
+/// Print the preferences that differ from the defaults as JSON, e.g. for bug reports
+async fn show_config_diff() -> Result<()> {
+    let prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+    let diff = prefs
+        .non_default_fields()
+        .with_context(|| "Failed to compare preferences with the defaults")?;
+    println!("{}", serde_json::to_string_pretty(&diff)?);
+    Ok(())
+}
+
+/// Upgrade the saved preferences file to the current schema
+async fn migrate_config() -> Result<()> {
+    let saved_version = PersonalityPreferences::migrate_saved()
+        .await
+        .with_context(|| "Failed to migrate personality preferences")?;
+
+    match saved_version {
+        None => println!(
+            "{} No saved configuration yet, nothing to migrate",
+            ICON_INFO.cyan()
+        ),
+        Some(version) if version < CONFIG_VERSION => println!(
+            "{} Migrated configuration from v{version} to v{CONFIG_VERSION}",
+            ICON_CHECK.green()
+        ),
+        Some(_) => println!(
+            "{} Configuration is already at v{CONFIG_VERSION}; rewrote it without unknown fields",
+            ICON_CHECK.green()
+        ),
+    }
+    Ok(())
+}
+
+/// Print every effective preference, grouped for reading
+async fn show_config() -> Result<()> {
+    let prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load current personality preferences")?;
+    print!("{}", render_config_listing(&prefs));
+    Ok(())
+}
+
+/// Render the preferences as labelled groups of `name: value` lines
+fn render_config_listing(prefs: &PersonalityPreferences) -> String {
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" }.to_string();
+    let or_none = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".to_string());
+    let order: Vec<String> = prefs
+        .section_order
+        .iter()
+        .map(|section| section.name().to_string())
+        .collect();
+    let mut momentum_excluded: Vec<String> = prefs
+        .momentum_excluded
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    momentum_excluded.sort();
+    let face = |modifier: &PersonalityModifier| {
+        prefs
+            .mood_face(modifier)
+            .map_or_else(|| "built-in".to_string(), str::to_string)
+    };
+
+    let groups: [(&str, Vec<(&str, String)>); 5] = [
+        (
+            "Sections",
+            vec![
+                ("personality", on_off(prefs.show_personality)),
+                ("activity", on_off(prefs.show_activity)),
+                ("context", on_off(prefs.show_context)),
+                ("git", on_off(prefs.show_git)),
+                ("directory", on_off(prefs.show_current_dir)),
+                ("model", on_off(prefs.show_model)),
+                ("update notice", on_off(prefs.show_update_available)),
+                ("context usage", on_off(prefs.show_context_usage)),
+                ("activity trail", on_off(prefs.show_activity_trail)),
+                ("action count", on_off(prefs.show_action_count)),
+                ("streak milestones", on_off(prefs.show_streak_milestones)),
+                ("clock", on_off(prefs.show_clock)),
+                ("day streak", on_off(prefs.show_day_streak)),
+                ("error count", on_off(prefs.show_error_count)),
+                ("usage stats", on_off(prefs.collect_stats)),
+            ],
+        ),
+        (
+            "Git",
+            vec![
+                ("branch", on_off(prefs.show_git_branch)),
+                ("status", on_off(prefs.show_git_status)),
+                ("stash", on_off(prefs.show_git_stash)),
+                ("detached head", on_off(prefs.show_detached_head)),
+                ("icon", on_off(prefs.show_git_icon)),
+                ("branch suffix", format!("{:?}", prefs.git_branch_suffix)),
+                ("dirty count cap", prefs.git_dirty_count_cap.to_string()),
+                ("branch max width", prefs.git_branch_max_width.to_string()),
+                ("status style", config_name(&prefs.git_status_style)),
+                ("detailed status", on_off(prefs.git_detailed_status)),
+                ("branch type colors", on_off(prefs.color_branch_by_type)),
+                ("run from", config_name(&prefs.git_run_from)),
+                (
+                    "element order",
+                    prefs
+                        .git_elements()
+                        .iter()
+                        .map(GitElement::name)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            ],
+        ),
+        (
+            "Theme",
+            vec![
+                ("theme", prefs.theme.display_name().to_string()),
+                ("colors", on_off(prefs.use_colors)),
+                ("kaomoji pack", or_none(&prefs.kaomoji_pack)),
+                ("default personality", or_none(&prefs.default_personality)),
+            ],
+        ),
+        (
+            "Display",
+            vec![
+                ("icon mode", config_name(&prefs.icon_mode)),
+                ("separators", on_off(prefs.display.show_separators)),
+                (
+                    "separator char",
+                    format!("{:?}", prefs.display.separator_char),
+                ),
+                (
+                    "separator style",
+                    config_name(&prefs.display.separator_style),
+                ),
+                (
+                    "accent separator",
+                    prefs.display.accent_separator_before.as_ref().map_or_else(
+                        || "off".to_string(),
+                        |section| {
+                            format!(
+                                "{:?} before {}",
+                                prefs.display.accent_separator_char,
+                                section.name()
+                            )
+                        },
+                    ),
+                ),
+                ("model name style", config_name(&prefs.model_name_style)),
+                ("model brackets", config_name(&prefs.model_bracket_style)),
+                ("compact activity", on_off(prefs.compact_activity)),
+                ("label style", config_name(&prefs.activity_label_style)),
+                ("filename max width", prefs.filename_max_width.to_string()),
+                ("clock format", format!("{:?}", prefs.clock_format)),
+                (
+                    "quiet hours",
+                    match (prefs.quiet_hours_start, prefs.quiet_hours_end) {
+                        (Some(start), Some(end)) if start != end => {
+                            format!("{start}:00-{end}:00")
+                        }
+                        _ => "off".to_string(),
+                    },
+                ),
+                ("section order", order.join(", ")),
+            ],
+        ),
+        (
+            "Behavior",
+            vec![
+                (
+                    "hyperfocus threshold",
+                    prefs.hyperfocus_threshold.to_string(),
+                ),
+                ("berserker threshold", prefs.berserker_threshold.to_string()),
+                (
+                    "idle timeout",
+                    match prefs.idle_timeout_seconds {
+                        0 => "off".to_string(),
+                        seconds => format!("{seconds}s"),
+                    },
+                ),
+                (
+                    "boot grace",
+                    match prefs.boot_grace_seconds {
+                        0 => "off".to_string(),
+                        seconds => format!("{seconds}s"),
+                    },
+                ),
+                (
+                    "save debounce",
+                    match prefs.save_debounce_ms {
+                        0 => "off".to_string(),
+                        millis => format!("{millis}ms"),
+                    },
+                ),
+                (
+                    "no momentum for",
+                    if momentum_excluded.is_empty() {
+                        "none".to_string()
+                    } else {
+                        momentum_excluded.join(", ")
+                    },
+                ),
+                ("normal face", face(&PersonalityModifier::Normal)),
+                ("in the zone face", face(&PersonalityModifier::InTheZone)),
+                ("frustrated face", face(&PersonalityModifier::Frustrated)),
+            ],
+        ),
+    ];
+
+    let label_width = groups
+        .iter()
+        .flat_map(|(_, entries)| entries.iter().map(|(label, _)| label.len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for (index, (title, entries)) in groups.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!("{}\n", title.bold()));
+        for (label, value) in entries {
+            output.push_str(&format!("  {label:<label_width$}  {value}\n"));
+        }
+    }
+    output
+}
+
+/// How an option value is spelled in the config file, e.g. `project_dir`
+fn config_name(value: &impl serde::Serialize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Parse an activity name strictly, rejecting names that would silently map to Working
+fn parse_activity_name(name: &str) -> Result<Activity> {
+    let activity: Activity = name
+        .parse()
+        .map_err(|()| anyhow::anyhow!("Invalid activity name: {name}"))?;
+    let normalize = |s: &str| s.replace([' ', '-', '_'], "").to_lowercase();
+    if normalize(&activity.to_string()) == normalize(name) {
+        Ok(activity)
+    } else {
+        Err(anyhow::anyhow!(
+            "Unknown activity '{name}'. Use an activity name such as Testing or Editing."
+        ))
+    }
+}
+
 /// Reset all configuration to defaults
 async fn reset_configuration() -> Result<()> {
     intro("Reset Configuration")?;
@@ -245,6 +784,45 @@ async fn reset_configuration() -> Result<()> {
     Ok(())
 }
 
+/// Reset a single group of preferences (e.g. `git`) to defaults
+async fn reset_preference_group(section: &str) -> Result<()> {
+    let group: PreferenceGroup = section.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+
+    intro("Reset Configuration")?;
+
+    let confirmed = confirm(format!(
+        "Reset the '{}' settings to their defaults?",
+        group.name()
+    ))
+    .initial_value(false)
+    .interact()
+    .with_context(|| "Failed to get confirmation")?;
+
+    if confirmed {
+        let mut prefs = PersonalityPreferences::load_or_default()
+            .await
+            .with_context(|| "Failed to load current personality preferences")?;
+        prefs.reset_group(group);
+        prefs
+            .save()
+            .await
+            .with_context(|| format!("Failed to save after resetting '{}'", group.name()))?;
+
+        outro(format!(
+            "{} The '{}' settings have been reset to defaults!",
+            ICON_CHECK.green(),
+            group.name()
+        ))?;
+    } else {
+        outro(format!(
+            "{} Configuration reset cancelled.",
+            ICON_INFO.cyan()
+        ))?;
+    }
+
+    Ok(())
+}
+
 /// Create a sample session state for preview purposes
 fn create_preview_state() -> SessionState {
     SessionState {
@@ -255,10 +833,20 @@ fn create_preview_state() -> SessionState {
         git_branch: Some("main".to_string()),
         git_dirty: Some(true),    // Show dirty state in preview
         git_dirty_count: Some(3), // Show 3 dirty files in preview
+        git_modified_count: Some(2),
+        git_untracked_count: Some(1),
         git_status_checked_at: None,
+        git_stash_count: None,
+        git_stash_checked_at: None,
+        git_detached_head: None,
+        git_operation: None,
         personality: "ლ(╹◡╹ლ) Cowder".to_string(),
         previous_personality: None,
         consecutive_actions: 5,
+        total_actions: 42,
+        last_updated_at: None,
+        session_started_at: None,
+        celebrate_until: None,
         error_count: 1,
         recent_activities: vec![Activity::Editing, Activity::Reading],
         mood: crate::state::MoodState::default(),
@@ -281,7 +869,7 @@ fn show_statusline_preview(prefs: &PersonalityPreferences) {
 
     let state = create_preview_state();
     let workspace = create_preview_workspace();
-    let statusline = build_statusline(&state, "Sonnet", prefs, Some(&workspace), None);
+    let statusline = build_statusline(&state, "Sonnet", prefs, Some(&workspace), None, Some(62));
 
     println!("  {}", statusline);
     println!("{}", "━".repeat(60).bright_black());
@@ -293,8 +881,146 @@ fn print_config_help() {
     println!();
     println!("Subcommands:");
     println!("  display    Configure what appears in the statusline");
-    println!("  theme      Change color theme");
+    println!(
+        "  theme      Change color theme (--preview shows every theme, set NAME applies one, validate FILE checks a color file)"
+    );
+    println!("  icon       Override the icon shown for an activity");
+    println!("  face       Override the face shown for a mood");
+    println!("  personality-pack  Switch to a themed kaomoji pack (cats, bears, robots)");
+    println!("  order      Set the order of statusline sections");
+    println!("  show       List the current value of every setting");
+    println!("  diff       Print only the settings that differ from the defaults");
+    println!("  migrate    Upgrade the saved config to the current format");
     println!("  reset      Reset all settings to defaults");
     println!();
     println!("If no subcommand is provided, an interactive menu will be shown.");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::preferences::GitRunFrom;
+
+    #[test]
+    fn test_theme_gallery_lists_every_theme() {
+        let gallery = render_theme_gallery(&PersonalityPreferences::default());
+
+        assert!(!gallery.is_empty());
+        for theme in Theme::all() {
+            assert!(
+                gallery.contains(theme.display_name()),
+                "Gallery should include {}",
+                theme.display_name()
+            );
+        }
+        assert_eq!(gallery.lines().count(), Theme::all().len());
+    }
+
+    #[test]
+    fn test_config_listing_shows_theme_and_toggles() {
+        let mut prefs = PersonalityPreferences {
+            theme: Theme::Dracula,
+            ..PersonalityPreferences::default()
+        };
+        prefs.show_personality = false;
+
+        let listing = render_config_listing(&prefs);
+        assert!(listing.contains(Theme::Dracula.display_name()));
+        let personality = listing
+            .lines()
+            .find(|line| line.trim_start().starts_with("personality"))
+            .unwrap();
+        assert!(personality.ends_with("off"), "{personality}");
+
+        prefs.show_personality = true;
+        let listing = render_config_listing(&prefs);
+        assert!(listing.contains("Sections"));
+        assert!(listing.contains("Git"));
+        let personality = listing
+            .lines()
+            .find(|line| line.trim_start().starts_with("personality"))
+            .unwrap();
+        assert!(personality.ends_with("on"), "{personality}");
+    }
+
+    #[test]
+    fn test_config_listing_shows_behavior_settings() {
+        let prefs = PersonalityPreferences {
+            hyperfocus_threshold: 7,
+            save_debounce_ms: 250,
+            momentum_excluded: [Activity::Reading].into_iter().collect(),
+            in_the_zone_face: Some("(o_o)".to_string()),
+            ..PersonalityPreferences::default()
+        };
+
+        let listing = render_config_listing(&prefs);
+        let line = |label: &str| {
+            listing
+                .lines()
+                .find(|line| line.trim_start().starts_with(label))
+                .unwrap()
+                .to_string()
+        };
+        assert!(listing.contains("Behavior"));
+        assert!(line("hyperfocus threshold").ends_with(" 7"));
+        assert!(line("save debounce").ends_with("250ms"));
+        assert!(line("boot grace").ends_with("off"));
+        assert!(line("no momentum for").ends_with("Reading"));
+        assert!(line("in the zone face").ends_with("(o_o)"));
+        assert!(line("normal face").ends_with("built-in"));
+
+        // Sections are listed by the names the config file and CLI use
+        let order = line("section order");
+        assert!(
+            order.contains(StatuslineSection::Personality.name()),
+            "{order}"
+        );
+        assert!(!order.contains("Personality"), "{order}");
+    }
+
+    #[test]
+    fn test_config_listing_uses_config_file_names() {
+        let prefs = PersonalityPreferences {
+            git_run_from: GitRunFrom::ProjectDir,
+            ..PersonalityPreferences::default()
+        };
+        let listing = render_config_listing(&prefs);
+        let run_from = listing
+            .lines()
+            .find(|line| line.trim_start().starts_with("run from"))
+            .unwrap();
+        assert!(run_from.ends_with("project_dir"), "{run_from}");
+    }
+
+    #[test]
+    fn test_apply_theme_name() {
+        let mut prefs = PersonalityPreferences::default();
+        apply_theme_name(&mut prefs, "dracula").unwrap();
+        assert_eq!(prefs.theme, Theme::Dracula);
+
+        let error = apply_theme_name(&mut prefs, "neon").unwrap_err();
+        assert!(error.to_string().contains("valid themes"));
+        assert_eq!(prefs.theme, Theme::Dracula);
+    }
+
+    #[test]
+    fn test_apply_kaomoji_pack() {
+        let mut prefs = PersonalityPreferences::default();
+        apply_kaomoji_pack(&mut prefs, "Cats").unwrap();
+        assert_eq!(prefs.kaomoji_pack.as_deref(), Some("cats"));
+
+        let error = apply_kaomoji_pack(&mut prefs, "dogs").unwrap_err();
+        assert!(error.to_string().contains("valid packs"));
+        assert_eq!(prefs.kaomoji_pack.as_deref(), Some("cats"));
+
+        apply_kaomoji_pack(&mut prefs, "default").unwrap();
+        assert_eq!(prefs.kaomoji_pack, None);
+    }
+
+    #[test]
+    fn test_parse_activity_name() {
+        assert_eq!(parse_activity_name("testing").unwrap(), Activity::Testing);
+        assert_eq!(parse_activity_name("Working").unwrap(), Activity::Working);
+        assert!(parse_activity_name("juggling").is_err());
+    }
+}
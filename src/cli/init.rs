@@ -299,7 +299,7 @@ mod tests {
     #[tokio::test]
     #[ignore] // Ignored by default since it modifies system state
     async fn test_init_flow() {
-        let options = InitOptions {
+        let _options = InitOptions {
             non_interactive: true,
             backup: true,
         };
@@ -216,10 +216,19 @@ impl ConfigApp {
                 pref_key: "Git Status",
                 depth: 1,
                 parent: Some("Git"),
-                is_last_child: true,
+                is_last_child: false,
                 enabled: prefs.show_git_status,
                 option_type: OptionType::Toggle,
             },
+            ConfigOption {
+                name: "Stash",
+                pref_key: "Git Stash",
+                depth: 1,
+                parent: Some("Git"),
+                is_last_child: true,
+                enabled: prefs.show_git_stash,
+                option_type: OptionType::Toggle,
+            },
             // Directory section with Move, Icon and Label children
             ConfigOption {
                 name: "Current Directory",
@@ -319,6 +328,69 @@ impl ConfigApp {
                     section: StatuslineSection::UpdateAvailable,
                 },
             },
+            // Activity Trail with Move child
+            ConfigOption {
+                name: "Activity Trail",
+                pref_key: "Activity Trail",
+                depth: 0,
+                parent: None,
+                is_last_child: false,
+                enabled: prefs.show_activity_trail,
+                option_type: OptionType::Toggle,
+            },
+            ConfigOption {
+                name: "Move",
+                pref_key: "Move Activity Trail",
+                depth: 1,
+                parent: Some("Activity Trail"),
+                is_last_child: true,
+                enabled: true,
+                option_type: OptionType::Move {
+                    section: StatuslineSection::ActivityTrail,
+                },
+            },
+            // Action Count with Move child
+            ConfigOption {
+                name: "Action Count",
+                pref_key: "Action Count",
+                depth: 0,
+                parent: None,
+                is_last_child: false,
+                enabled: prefs.show_action_count,
+                option_type: OptionType::Toggle,
+            },
+            ConfigOption {
+                name: "Move",
+                pref_key: "Move Action Count",
+                depth: 1,
+                parent: Some("Action Count"),
+                is_last_child: true,
+                enabled: true,
+                option_type: OptionType::Move {
+                    section: StatuslineSection::ActionCount,
+                },
+            },
+            // Context Usage with Move child
+            ConfigOption {
+                name: "Context Usage",
+                pref_key: "Context Usage",
+                depth: 0,
+                parent: None,
+                is_last_child: false,
+                enabled: prefs.show_context_usage,
+                option_type: OptionType::Toggle,
+            },
+            ConfigOption {
+                name: "Move",
+                pref_key: "Move Context Usage",
+                depth: 1,
+                parent: Some("Context Usage"),
+                is_last_child: true,
+                enabled: true,
+                option_type: OptionType::Move {
+                    section: StatuslineSection::ContextUsage,
+                },
+            },
             // Colors (standalone, not reorderable)
             ConfigOption {
                 name: "Colors",
@@ -525,7 +597,7 @@ impl ConfigApp {
             "Activity Icon" | "Activity Label" | "Activity Context" => {
                 self.prefs.show_activity = true;
             }
-            "Git Icon" | "Git Branch" | "Git Status" => {
+            "Git Icon" | "Git Branch" | "Git Status" | "Git Stash" => {
                 self.prefs.show_git = true;
             }
             "Directory Icon" | "Directory Label" => {
@@ -570,10 +642,20 @@ fn create_preview_state() -> SessionState {
         git_branch: Some("main".to_string()),
         git_dirty: Some(true),    // Show dirty state in preview
         git_dirty_count: Some(3), // Show 3 dirty files in preview
+        git_modified_count: Some(2),
+        git_untracked_count: Some(1),
         git_status_checked_at: None,
+        git_stash_count: None,
+        git_stash_checked_at: None,
+        git_detached_head: None,
+        git_operation: None,
         personality: "ლ(╹◡╹ლ) Cowder".to_string(),
         previous_personality: None,
         consecutive_actions: 5,
+        total_actions: 42,
+        last_updated_at: None,
+        session_started_at: None,
+        celebrate_until: None,
         error_count: 1,
         recent_activities: vec![Activity::Editing, Activity::Reading],
         mood: crate::state::MoodState::default(),
@@ -595,7 +677,7 @@ fn get_section_for_option(opt: &ConfigOption) -> Option<StatuslineSection> {
         "Activity" | "Move Activity" | "Activity Icon" | "Activity Label" | "Activity Context" => {
             Some(StatuslineSection::Activity)
         }
-        "Git" | "Move Git" | "Git Icon" | "Git Branch" | "Git Status" => {
+        "Git" | "Move Git" | "Git Icon" | "Git Branch" | "Git Status" | "Git Stash" => {
             Some(StatuslineSection::Git)
         }
         "Current Directory" | "Move Directory" | "Directory Icon" | "Directory Label" => {
@@ -603,6 +685,9 @@ fn get_section_for_option(opt: &ConfigOption) -> Option<StatuslineSection> {
         }
         "Model" | "Move Model" | "Model Icon" | "Model Label" => Some(StatuslineSection::Model),
         "Update Available" | "Move Update" => Some(StatuslineSection::UpdateAvailable),
+        "Activity Trail" | "Move Activity Trail" => Some(StatuslineSection::ActivityTrail),
+        "Action Count" | "Move Action Count" => Some(StatuslineSection::ActionCount),
+        "Context Usage" | "Move Context Usage" => Some(StatuslineSection::ContextUsage),
         "Debug Info" | "Move Debug" => Some(StatuslineSection::DebugInfo),
         // Colors, Separators don't map to a specific section
         _ => None,
@@ -647,41 +732,40 @@ fn run_app<B: ratatui::backend::Backend>(
         }
 
         // Handle keyboard events
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => {
-                            app.should_quit = true;
-                        }
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            app.should_quit = true;
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            app.move_cursor_up();
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            app.move_cursor_down();
-                        }
-                        KeyCode::Left | KeyCode::Char('h') => {
-                            app.handle_horizontal_key(-1);
-                        }
-                        KeyCode::Right | KeyCode::Char('l') => {
-                            app.handle_horizontal_key(1);
-                        }
-                        KeyCode::Char(' ') => {
-                            app.toggle_current();
-                        }
-                        KeyCode::Enter => {
-                            if app.cursor == app.options.len() {
-                                app.should_quit = true;
-                            } else {
-                                app.toggle_current();
-                            }
-                        }
-                        _ => {}
+        if event::poll(std::time::Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => {
+                    app.should_quit = true;
+                }
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    app.should_quit = true;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    app.move_cursor_up();
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    app.move_cursor_down();
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    app.handle_horizontal_key(-1);
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    app.handle_horizontal_key(1);
+                }
+                KeyCode::Char(' ') => {
+                    app.toggle_current();
+                }
+                KeyCode::Enter => {
+                    if app.cursor == app.options.len() {
+                        app.should_quit = true;
+                    } else {
+                        app.toggle_current();
                     }
                 }
+                _ => {}
             }
         }
     }
@@ -714,8 +798,14 @@ fn ui(f: &mut Frame, app: &ConfigApp) {
 fn render_preview(f: &mut Frame, area: Rect, app: &ConfigApp) {
     let state = create_preview_state();
     let workspace = create_preview_workspace();
-    let (statusline, positions) =
-        build_statusline_with_positions(&state, "Sonnet", &app.prefs, Some(&workspace), None);
+    let (statusline, positions) = build_statusline_with_positions(
+        &state,
+        "Sonnet",
+        &app.prefs,
+        Some(&workspace),
+        None,
+        Some(62),
+    );
 
     // Get the section for the currently selected option
     let highlighted_section = if app.cursor < app.options.len() {
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use colored::Colorize;
 use std::path::PathBuf;
+use unicode_width::UnicodeWidthStr;
 
 use crate::config::PersonalityPreferences;
 use crate::icons::{ICON_CHECK, ICON_ERROR, ICON_INFO, ICON_WARNING};
+use crate::kaomoji::CATEGORIES;
+use crate::stats::UsageStats;
 use crate::version::CURRENT_VERSION;
 
 // Sub-modules
@@ -28,39 +31,48 @@ pub async fn init(non_interactive: bool, backup: bool) -> Result<()> {
     init::init_claude_code(options).await
 }
 
-/// Update Claude Code Personalities to the latest version with default options.
+/// Update Claude Code Personalities to the latest version, optionally installing
+/// a release that publishes no checksum without asking.
 ///
 /// # Errors
 ///
 /// This function will return an error if the update process fails.
 /// See [`update::update_personalities`] for detailed error conditions.
-pub async fn update() -> Result<()> {
-    let options = update::UpdateOptions::default();
+pub async fn update(skip_checksum: bool) -> Result<()> {
+    let options = update::UpdateOptions {
+        skip_checksum,
+        ..Default::default()
+    };
     update::update_personalities(options).await
 }
 
-/// Uninstall Claude Code Personalities with default options.
+/// Uninstall Claude Code Personalities, optionally purging preferences and session state.
 ///
 /// # Errors
 ///
 /// This function will return an error if the uninstallation process fails.
 /// See [`uninstall::uninstall_personalities`] for detailed error conditions.
-pub async fn uninstall() -> Result<()> {
-    let options = uninstall::UninstallOptions::default();
+pub async fn uninstall(purge: bool) -> Result<()> {
+    let options = uninstall::UninstallOptions {
+        purge,
+        ..Default::default()
+    };
     uninstall::uninstall_personalities(options).await
 }
 
 /// Display the current installation and configuration status.
 ///
+/// With `session_id`, the statusline test renders that session's saved state
+/// instead of a canned test session.
+///
 /// # Errors
 ///
 /// This function will return an error if:
 /// - The Claude directory path cannot be determined
 /// - Settings files cannot be read or parsed
 /// - Update checking fails due to network or API errors
-pub async fn status() -> Result<()> {
-    use crate::state::SessionState;
-    use crate::statusline::{ClaudeInput, build_statusline};
+/// - `session_id` names a session with no saved state
+pub async fn status(session_id: Option<&str>) -> Result<()> {
     use anyhow::Context;
 
     println!("{}", "Claude Code Personalities Status".bold().blue());
@@ -113,7 +125,99 @@ pub async fn status() -> Result<()> {
     }
 
     // Test statusline
+    let prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load preferences for status test")?;
+    if let Some(session_id) = session_id {
+        println!(
+            "\n{} Statusline for session {session_id}:",
+            ICON_INFO.cyan()
+        );
+        let statusline = render_session_statusline(session_id, &prefs).await?;
+        println!("  Output: {statusline}");
+        return Ok(());
+    }
+
     println!("\n{} Testing statusline output:", ICON_INFO.cyan());
+    let statusline = render_test_statusline(&prefs).await?;
+    println!("  Output: {statusline}");
+
+    Ok(())
+}
+
+/// Run the tool hook on a synthetic tool use, then print the resulting statusline.
+///
+/// # Errors
+///
+/// This function will return an error if preferences can't be loaded or the
+/// hook left no saved state for `session_id`.
+pub async fn simulate(
+    session_id: &str,
+    tool_name: &str,
+    file_path: Option<&str>,
+    command: Option<&str>,
+) -> Result<()> {
+    use anyhow::Context;
+
+    let prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load preferences for the simulated statusline")?;
+    let statusline =
+        simulated_statusline(session_id, tool_name, file_path, command, &prefs).await?;
+    println!("{statusline}");
+    Ok(())
+}
+
+/// Feed a synthetic tool use through the hook and render the session it updated
+async fn simulated_statusline(
+    session_id: &str,
+    tool_name: &str,
+    file_path: Option<&str>,
+    command: Option<&str>,
+    prefs: &PersonalityPreferences,
+) -> Result<String> {
+    crate::hooks::simulate_tool_hook(session_id, tool_name, file_path, command).await;
+    render_session_statusline(session_id, prefs).await
+}
+
+/// Model shown when rendering a saved session, since session state doesn't record it
+const STATUS_MODEL_NAME: &str = "Opus";
+
+/// Render the statusline for a live session from its saved state
+async fn render_session_statusline(
+    session_id: &str,
+    prefs: &PersonalityPreferences,
+) -> Result<String> {
+    use crate::state::SessionState;
+    use crate::statusline::build_statusline;
+    use anyhow::Context;
+
+    let path = SessionState::active_state_path(session_id);
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "No saved state for session {session_id} (expected {})",
+            path.display()
+        ));
+    }
+    let state = SessionState::load(session_id)
+        .await
+        .with_context(|| format!("Failed to load session state for session {session_id}"))?;
+    Ok(build_statusline(
+        &state,
+        STATUS_MODEL_NAME,
+        prefs,
+        None,
+        None,
+        None,
+    ))
+}
+
+/// Render the statusline for the canned `test` session
+async fn render_test_statusline(prefs: &PersonalityPreferences) -> Result<String> {
+    use crate::state::SessionState;
+    use crate::statusline::{ClaudeInput, build_statusline};
+    use anyhow::Context;
+
     let test_input = r#"{"model":{"display_name":"Opus"},"workspace":{"current_dir":"/test"},"session_id":"test"}"#;
 
     // Simulate statusline output
@@ -130,18 +234,52 @@ pub async fn status() -> Result<()> {
     let state = SessionState::load(&session_id)
         .await
         .with_context(|| format!("Failed to load test session state for session {session_id}"))?;
-    let prefs = PersonalityPreferences::load_or_default()
-        .await
-        .with_context(|| "Failed to load preferences for status test")?;
-    let statusline = build_statusline(&state, &model_name, &prefs, None, None);
-    println!("  Output: {statusline}");
+    Ok(build_statusline(
+        &state,
+        &model_name,
+        prefs,
+        None,
+        None,
+        None,
+    ))
+}
 
-    Ok(())
+/// Result of an update check, reported through the exit code by `check-update --quiet`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateCheckOutcome {
+    UpToDate,
+    UpdateAvailable,
+    Failed,
+}
+
+impl UpdateCheckOutcome {
+    /// Process exit code for scripts: 0 up to date, 10 update available, 1 check failed
+    #[must_use]
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::UpToDate => 0,
+            Self::UpdateAvailable => 10,
+            Self::Failed => 1,
+        }
+    }
+}
+
+/// Run an update check against `source` (which yields the newer release, if any)
+/// without printing anything
+async fn quiet_update_check<T>(
+    source: impl std::future::Future<Output = Result<Option<T>>>,
+) -> UpdateCheckOutcome {
+    match source.await {
+        Ok(None) => UpdateCheckOutcome::UpToDate,
+        Ok(Some(_)) => UpdateCheckOutcome::UpdateAvailable,
+        Err(_) => UpdateCheckOutcome::Failed,
+    }
 }
 
 /// Check for available updates and display version information.
 ///
-/// Always fetches fresh data from GitHub (no caching).
+/// Always fetches fresh data from GitHub (no caching). With `quiet`, nothing is
+/// printed and failures are reported as [`UpdateCheckOutcome::Failed`] instead of an error.
 ///
 /// # Errors
 ///
@@ -150,10 +288,17 @@ pub async fn status() -> Result<()> {
 /// - GitHub API requests fail or return invalid responses
 /// - Network connectivity issues prevent update checking
 /// - Version parsing or comparison fails
-pub async fn check_update() -> Result<()> {
+pub async fn check_update(quiet: bool) -> Result<UpdateCheckOutcome> {
     use crate::version::{VersionManager, format_version_comparison};
     use anyhow::Context;
 
+    if quiet {
+        return Ok(quiet_update_check(async {
+            VersionManager::new()?.check_for_update_force().await
+        })
+        .await);
+    }
+
     println!("{}", "Checking for updates...".bold().blue());
     println!();
 
@@ -188,13 +333,13 @@ pub async fn check_update() -> Result<()> {
             "Run {} to update",
             "claude-code-personalities update".cyan()
         );
+        Ok(UpdateCheckOutcome::UpdateAvailable)
     } else {
         println!();
         println!("{} You are running the latest version!", ICON_CHECK.green());
         println!("Current version: v{CURRENT_VERSION}");
+        Ok(UpdateCheckOutcome::UpToDate)
     }
-
-    Ok(())
 }
 
 /// Display help information and available commands.
@@ -213,19 +358,103 @@ pub fn help() -> Result<()> {
     println!("  init          Initialize Claude Code settings for personalities");
     println!("  config        Customize statusline appearance and colors");
     println!("  status        Check installation and configuration status");
+    println!("  simulate      Run a synthetic tool use through the hooks and print the statusline");
+    println!("  stats         Show local usage counts (when collect_stats is on)");
+    println!("  personalities List every built-in personality (personalities list)");
     println!("  update        Check for and install updates");
     println!("  check-update  Check for available updates");
-    println!("  uninstall     Remove personalities from Claude Code");
+    println!("  uninstall     Remove personalities from Claude Code (--purge removes user data)");
     println!("  help          Show this help message");
     println!();
     println!("Modes (called by Claude Code):");
     println!("  --statusline  Run in statusline mode");
+    println!("  --tmux        tmux-formatted output from saved state (with --statusline)");
+    println!("  --watch       Re-render on state changes (with --statusline, --session-id ID)");
     println!("  --hook TYPE   Run in hook mode (activity, prompt-submit, session-end)");
+    println!("  --verbose     Print hook diagnostics to stderr (with --hook)");
     println!();
 
     Ok(())
 }
 
+/// Print the local usage counters collected with `collect_stats`.
+///
+/// # Errors
+///
+/// This function does not currently return errors.
+pub fn stats() -> Result<()> {
+    let path = crate::stats::stats_path();
+    match path.filter(|path| path.exists()) {
+        Some(path) => print!("{}", render_stats(&UsageStats::load(&path))),
+        None => {
+            print_info("No usage stats yet");
+            println!(
+                "  Set \"collect_stats\": true in the config file to start counting (stored locally only)"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Most frequent entries listed per group
+const STATS_TOP_COUNT: usize = 10;
+
+/// Render the most frequent activities and personalities with their counts
+fn render_stats(stats: &UsageStats) -> String {
+    let mut output = String::new();
+    for (index, (title, counts)) in [
+        ("Activities", &stats.activities),
+        ("Personalities", &stats.personalities),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if index > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!("{}\n", title.bold()));
+        for (name, count) in UsageStats::ranked(counts).into_iter().take(STATS_TOP_COUNT) {
+            output.push_str(&format!("  {count:>6}  {name}\n"));
+        }
+    }
+    output
+}
+
+/// Print every built-in personality with its face, grouped by category.
+///
+/// # Errors
+///
+/// This function does not currently return errors.
+pub fn list_personalities() -> Result<()> {
+    print!("{}", render_personality_list());
+    Ok(())
+}
+
+/// Render each category's faces and descriptions, with the descriptions aligned
+fn render_personality_list() -> String {
+    let face_width = CATEGORIES
+        .iter()
+        .flat_map(|(_, kaomoji)| kaomoji.iter().map(|kaomoji| kaomoji.face.width()))
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for (index, (category, kaomoji)) in CATEGORIES.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!("{}\n", category.bold()));
+        for kaomoji in *kaomoji {
+            let padding = " ".repeat(face_width.saturating_sub(kaomoji.face.width()));
+            output.push_str(&format!(
+                "  {}{padding}  {}\n",
+                kaomoji.face, kaomoji.description
+            ));
+        }
+    }
+    output
+}
+
 fn get_claude_dir() -> Result<PathBuf> {
     settings::get_claude_dir()
 }
@@ -234,3 +463,101 @@ fn get_claude_dir() -> Result<PathBuf> {
 fn print_info(message: &str) {
     println!("  {} {}", ICON_INFO.cyan(), message);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SessionState;
+
+    #[tokio::test]
+    async fn test_status_renders_saved_session() {
+        let session_id = format!("status_test_{}", std::process::id());
+        let state = SessionState {
+            session_id: session_id.clone(),
+            personality: "(=^･ω･^=) Code Kitty".to_string(),
+            ..Default::default()
+        };
+        state.save().await.unwrap();
+
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        let statusline = render_session_statusline(&session_id, &prefs)
+            .await
+            .unwrap();
+        assert!(statusline.contains("(=^･ω･^=) Code Kitty"), "{statusline}");
+
+        SessionState::cleanup(&session_id).await.unwrap();
+        let missing = render_session_statusline(&session_id, &prefs).await;
+        assert!(missing.unwrap_err().to_string().contains("No saved state"));
+    }
+
+    #[tokio::test]
+    async fn test_simulated_edit_shows_coding() {
+        let session_id = format!("simulate_test_{}", std::process::id());
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+
+        let statusline = simulated_statusline(&session_id, "Edit", Some("main.rs"), None, &prefs)
+            .await
+            .unwrap();
+        assert!(statusline.contains("Coding"), "{statusline}");
+        assert!(statusline.contains("main.rs"), "{statusline}");
+
+        SessionState::cleanup(&session_id).await.unwrap();
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let mut stats = UsageStats::default();
+        stats.record(&crate::types::Activity::Coding, "ლ(╹◡╹ლ) Cowder");
+        stats.record(&crate::types::Activity::Coding, "ლ(╹◡╹ლ) Cowder");
+
+        let rendered = render_stats(&stats);
+        assert!(rendered.contains("Activities"));
+        assert!(
+            rendered.lines().any(|line| line.trim() == "2  Coding"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("ლ(╹◡╹ლ) Cowder"));
+    }
+
+    #[test]
+    fn test_personality_list() {
+        use crate::kaomoji::{CODE_WIZARD, TOOL_KAOMOJI};
+
+        let list = render_personality_list();
+        assert!(!list.is_empty());
+        assert!(list.contains(CODE_WIZARD.description), "{list}");
+        assert!(list.contains(CODE_WIZARD.face), "{list}");
+        for (category, _) in CATEGORIES {
+            assert!(list.contains(category), "{category}");
+        }
+        let total: usize = CATEGORIES.iter().map(|(_, kaomoji)| kaomoji.len()).sum();
+        assert!(total > TOOL_KAOMOJI.len());
+        assert_eq!(
+            list.lines().filter(|line| line.starts_with("  ")).count(),
+            total
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quiet_update_check_outcomes() {
+        let up_to_date = quiet_update_check(async { Ok(None::<String>) }).await;
+        assert_eq!(up_to_date, UpdateCheckOutcome::UpToDate);
+        assert_eq!(up_to_date.exit_code(), 0);
+
+        let available = quiet_update_check(async { Ok(Some("v9.9.9".to_string())) }).await;
+        assert_eq!(available, UpdateCheckOutcome::UpdateAvailable);
+        assert_eq!(available.exit_code(), 10);
+
+        let failed =
+            quiet_update_check(async { Err::<Option<String>, _>(anyhow::anyhow!("offline")) })
+                .await;
+        assert_eq!(failed, UpdateCheckOutcome::Failed);
+        assert_eq!(failed.exit_code(), 1);
+    }
+}
@@ -292,10 +292,10 @@ impl ClaudeSettings {
                         if let Some(Value::Array(entry_hooks)) = hook_entry.get_mut("hooks") {
                             // Remove only personality commands from this entry's hooks array
                             entry_hooks.retain(|hook| {
-                                if let Some(command) = hook.get("command") {
-                                    if let Some(cmd_str) = command.as_str() {
-                                        return !cmd_str.contains("claude-code-personalities");
-                                    }
+                                if let Some(command) = hook.get("command")
+                                    && let Some(cmd_str) = command.as_str()
+                                {
+                                    return !cmd_str.contains("claude-code-personalities");
                                 }
                                 true
                             });
@@ -335,14 +335,12 @@ impl ClaudeSettings {
     #[must_use]
     pub fn is_personality_configured(&self) -> bool {
         // Check statusline
-        if let Some(statusline) = self.content.get("statusLine") {
-            if let Some(command) = statusline.get("command") {
-                if let Some(cmd_str) = command.as_str() {
-                    if cmd_str.contains("claude-code-personalities") {
-                        return true;
-                    }
-                }
-            }
+        if let Some(statusline) = self.content.get("statusLine")
+            && let Some(command) = statusline.get("command")
+            && let Some(cmd_str) = command.as_str()
+            && cmd_str.contains("claude-code-personalities")
+        {
+            return true;
         }
 
         // Check hooks
@@ -400,17 +398,17 @@ fn hook_contains_personality_command(hook_value: &Value) -> bool {
         Value::Object(obj) => {
             if let Some(Value::Array(hooks)) = obj.get("hooks") {
                 return hooks.iter().any(|hook| {
-                    if let Some(command) = hook.get("command") {
-                        if let Some(cmd_str) = command.as_str() {
-                            return cmd_str.contains("claude-code-personalities");
-                        }
+                    if let Some(command) = hook.get("command")
+                        && let Some(cmd_str) = command.as_str()
+                    {
+                        return cmd_str.contains("claude-code-personalities");
                     }
                     false
                 });
-            } else if let Some(command) = obj.get("command") {
-                if let Some(cmd_str) = command.as_str() {
-                    return cmd_str.contains("claude-code-personalities");
-                }
+            } else if let Some(command) = obj.get("command")
+                && let Some(cmd_str) = command.as_str()
+            {
+                return cmd_str.contains("claude-code-personalities");
             }
             false
         }
@@ -886,7 +884,7 @@ mod tests {
     async fn test_remove_personality_config_mixed_commands_in_entry() {
         let temp_dir = TempDir::new().unwrap();
         let settings_path = temp_dir.path().join("settings.json");
-        let binary_path = PathBuf::from("/path/to/claude-code-personalities");
+        let _binary_path = PathBuf::from("/path/to/claude-code-personalities");
 
         // Create a scenario where personality and non-personality commands exist in the same hook entry
         let mixed_config = serde_json::json!({
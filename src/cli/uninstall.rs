@@ -5,11 +5,24 @@ use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use crate::cli::settings::{ClaudeSettings, get_claude_dir};
+use crate::config::PersonalityPreferences;
 use crate::icons::{ICON_CHECK, ICON_INFO, ICON_WARNING};
+use crate::platform;
+use crate::stats::STATS_FILE_NAME;
+use crate::streak::STREAK_FILE_NAME;
 
 #[derive(Default)]
 pub struct UninstallOptions {
     pub force: bool,
+    /// Also remove user preferences and session state files
+    pub purge: bool,
+}
+
+/// User data removed by a purge
+#[derive(Debug, Default, PartialEq)]
+struct UserDataRemoval {
+    session_files: u32,
+    preferences: bool,
 }
 
 /// Uninstall Claude Code Personalities and remove all configuration.
@@ -17,8 +30,8 @@ pub struct UninstallOptions {
 /// This function performs a complete cleanup including:
 /// - Removal of the binary from ~/.claude/
 /// - Cleanup of Claude Code settings.json configuration
-/// - Removal of temporary state files
-/// - Optional removal of backup files and user preferences
+/// - Removal of backup files
+/// - With `purge`, removal of user preferences and session state files
 ///
 /// # Errors
 ///
@@ -58,10 +71,16 @@ pub async fn uninstall_personalities(options: UninstallOptions) -> Result<()> {
     println!("{}", "This will remove:".bold().yellow());
     println!("  • Claude Code Personalities binary");
     println!("  • Statusline and hook configuration from settings.json");
-    println!("  • Temporary state files");
     println!("  • All backup files");
-    println!("  • User preferences file");
+    if options.purge {
+        println!("  • Session state files");
+        println!("  • User preferences file");
+    }
     println!();
+    if !options.purge {
+        print_info("User preferences and session state are kept (use --purge to remove them).");
+        println!();
+    }
 
     // Step 3: Load Claude settings
     print_info("Loading Claude settings...");
@@ -149,27 +168,27 @@ pub async fn uninstall_personalities(options: UninstallOptions) -> Result<()> {
         print_info("No backup files found");
     }
 
-    // Step 10: Clean up temporary state files
-    print_info("Cleaning up temporary files...");
-    let temp_files_removed = cleanup_temp_files()
-        .await
-        .with_context(|| "Failed to clean up temporary files")?;
-    if temp_files_removed > 0 {
-        print_success(&format!("Removed {temp_files_removed} temporary files"));
+    // Step 10: Remove session state and user preferences (only when purging)
+    if options.purge {
+        print_info("Removing session state and user preferences...");
     }
-
-    // Step 11: Remove user preferences (always for complete cleanup)
-    print_info("Removing user preferences...");
-    let prefs_removed = cleanup_user_preferences()
+    let prefs_path = PersonalityPreferences::get_preferences_path()?;
+    let removal = cleanup_user_data(&options, &platform::state_dir(), &prefs_path)
         .await
-        .with_context(|| "Failed to clean up user preferences")?;
-    if prefs_removed {
+        .with_context(|| "Failed to clean up user data")?;
+    if removal.session_files > 0 {
+        print_success(&format!(
+            "Removed {} session state files",
+            removal.session_files
+        ));
+    }
+    if removal.preferences {
         print_success("User preferences removed");
     }
 
-    // Step 12: Show completion message
+    // Step 11: Show completion message
     println!();
-    print_uninstall_success(false, false, backup_path.as_ref())?;
+    print_uninstall_success(!options.purge, false, backup_path.as_ref())?;
 
     Ok(())
 }
@@ -213,35 +232,57 @@ async fn cleanup_backup_files(claude_dir: &PathBuf) -> Result<u32> {
     Ok(removed_count)
 }
 
-/// Clean up temporary state files from /tmp
-async fn cleanup_temp_files() -> Result<u32> {
+/// Remove user preferences and session state files when `options.purge` is set.
+/// A regular uninstall leaves user data untouched.
+async fn cleanup_user_data(
+    options: &UninstallOptions,
+    state_dir: &Path,
+    prefs_file: &Path,
+) -> Result<UserDataRemoval> {
+    if !options.purge {
+        return Ok(UserDataRemoval::default());
+    }
+
+    Ok(UserDataRemoval {
+        session_files: cleanup_session_artifacts(state_dir).await?,
+        preferences: cleanup_user_preferences(prefs_file).await?
+            | cleanup_user_preferences(&prefs_file.with_file_name(STREAK_FILE_NAME)).await?
+            | cleanup_user_preferences(&prefs_file.with_file_name(STATS_FILE_NAME)).await?,
+    })
+}
+
+/// Check whether a file name belongs to a session state or error file
+/// (including names used by older releases)
+fn is_session_artifact(filename: &str) -> bool {
+    filename.starts_with("claude_code_personalities_")
+        || filename.starts_with("claude_activity_")
+        || filename.starts_with("claude_personalities_")
+}
+
+/// Remove all session state and error files from the state directory
+async fn cleanup_session_artifacts(state_dir: &Path) -> Result<u32> {
     let mut removed_count = 0;
-    let tmp_dir = PathBuf::from("/tmp");
 
-    if !tmp_dir.exists() {
+    if !state_dir.exists() {
         return Ok(0);
     }
 
-    let mut entries = fs::read_dir(&tmp_dir)
+    let mut entries = fs::read_dir(state_dir)
         .await
-        .with_context(|| "Failed to read /tmp directory")?;
+        .with_context(|| format!("Failed to read state directory: {}", state_dir.display()))?;
 
     while let Some(entry) = entries
         .next_entry()
         .await
-        .with_context(|| "Failed to read /tmp entry")?
+        .with_context(|| "Failed to read state directory entry")?
     {
         let filename = entry.file_name();
-        let filename_str = filename.to_string_lossy();
 
-        // Remove personality-related temp files
-        if (filename_str.starts_with("claude_activity_")
-            || filename_str.starts_with("claude_code_personalities_errors_")
-            || filename_str.starts_with("claude_personalities_"))
+        if is_session_artifact(&filename.to_string_lossy())
             && fs::remove_file(entry.path()).await.is_ok()
         {
             removed_count += 1;
-            // Don't warn about temp files - they might be in use
+            // Don't warn about state files - they might be in use
         }
     }
 
@@ -249,12 +290,9 @@ async fn cleanup_temp_files() -> Result<u32> {
 }
 
 /// Clean up user preferences file
-async fn cleanup_user_preferences() -> Result<bool> {
-    let claude_dir = get_claude_dir()?;
-    let prefs_file = claude_dir.join("personalities_config.json");
-
+async fn cleanup_user_preferences(prefs_file: &Path) -> Result<bool> {
     if prefs_file.exists() {
-        fs::remove_file(&prefs_file)
+        fs::remove_file(prefs_file)
             .await
             .with_context(|| format!("Failed to remove preferences: {}", prefs_file.display()))?;
         Ok(true)
@@ -279,7 +317,9 @@ fn print_uninstall_success(
     println!("{}", "What was removed:".bold().cyan());
     println!("  {} Claude Code Personalities binary", ICON_CHECK.green());
     println!("  {} Statusline and hook configuration", ICON_CHECK.green());
-    println!("  {} Temporary state files", ICON_CHECK.green());
+    if !kept_preferences {
+        println!("  {} Session state files", ICON_CHECK.green());
+    }
     if !kept_backups {
         println!("  {} All backup files", ICON_CHECK.green());
     }
@@ -292,7 +332,7 @@ fn print_uninstall_success(
         println!("{}", "What was preserved:".bold().yellow());
         if kept_preferences {
             println!(
-                "  {} User preferences (can be removed manually)",
+                "  {} User preferences and session state (remove with --purge)",
                 ICON_INFO.yellow()
             );
         }
@@ -381,24 +421,24 @@ async fn find_all_binary_locations(claude_dir: &Path) -> Result<Vec<PathBuf>> {
         .arg("claude-code-personalities")
         .output()
         .await
+        && output.status.success()
     {
-        if output.status.success() {
-            let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path_str.is_empty() {
-                let path_binary = PathBuf::from(path_str);
-                if path_binary.exists() {
-                    locations.push(path_binary);
-                }
+        let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !path_str.is_empty() {
+            let path_binary = PathBuf::from(path_str);
+            if path_binary.exists() {
+                locations.push(path_binary);
             }
         }
     }
 
     // 2. Check ~/.local/bin/claude-code-personalities
     let local_bin = dirs::home_dir().map(|home| home.join(".local/bin/claude-code-personalities"));
-    if let Some(local_bin) = local_bin {
-        if local_bin.exists() && !locations.contains(&local_bin) {
-            locations.push(local_bin);
-        }
+    if let Some(local_bin) = local_bin
+        && local_bin.exists()
+        && !locations.contains(&local_bin)
+    {
+        locations.push(local_bin);
     }
 
     // 3. Check ~/.claude/claude-code-personalities
@@ -464,39 +504,102 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_cleanup_temp_files() {
-        // This test is challenging since we can't easily create files in /tmp
-        // So we'll just test that the function runs without error
-        let result = cleanup_temp_files().await;
-        assert!(result.is_ok());
+    async fn test_cleanup_session_artifacts() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let files = [
+            "claude_code_personalities_activity_abc.json",
+            "claude_code_personalities_errors_abc.count",
+            "claude_activity_legacy.json",
+            "unrelated.txt", // Should not be removed
+        ];
+        for filename in &files {
+            fs::write(temp_dir.path().join(filename), b"{}")
+                .await
+                .unwrap();
+        }
+
+        let removed = cleanup_session_artifacts(temp_dir.path()).await.unwrap();
+        assert_eq!(removed, 3);
+        assert!(temp_dir.path().join("unrelated.txt").exists());
     }
 
     #[tokio::test]
     async fn test_cleanup_user_preferences() {
         let temp_dir = TempDir::new().unwrap();
-
-        // Mock the get_claude_dir function by creating a preferences file
         let prefs_file = temp_dir.path().join("personalities_config.json");
         fs::write(&prefs_file, b"{}").await.unwrap();
 
-        // We can't easily test this function directly due to get_claude_dir dependency
-        // But we can test the file operations
+        assert!(cleanup_user_preferences(&prefs_file).await.unwrap());
+        assert!(!prefs_file.exists());
+        // Second run has nothing to remove
+        assert!(!cleanup_user_preferences(&prefs_file).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_purge_removes_user_data_only_when_requested() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir
+            .path()
+            .join("claude_code_personalities_activity_purge.json");
+        let error_file = temp_dir
+            .path()
+            .join("claude_code_personalities_errors_purge.count");
+        let prefs_file = temp_dir.path().join("personalities_config.json");
+        let streak_file = temp_dir.path().join(STREAK_FILE_NAME);
+        let stats_file = temp_dir.path().join(STATS_FILE_NAME);
+        for path in [
+            &state_file,
+            &error_file,
+            &prefs_file,
+            &streak_file,
+            &stats_file,
+        ] {
+            fs::write(path, b"{}").await.unwrap();
+        }
+
+        // Regular uninstall keeps user data
+        let removal = cleanup_user_data(&UninstallOptions::default(), temp_dir.path(), &prefs_file)
+            .await
+            .unwrap();
+        assert_eq!(removal, UserDataRemoval::default());
+        assert!(state_file.exists());
+        assert!(error_file.exists());
         assert!(prefs_file.exists());
-        fs::remove_file(&prefs_file).await.unwrap();
+        assert!(streak_file.exists());
+
+        // Purge removes it
+        let options = UninstallOptions {
+            purge: true,
+            ..Default::default()
+        };
+        let removal = cleanup_user_data(&options, temp_dir.path(), &prefs_file)
+            .await
+            .unwrap();
+        assert_eq!(removal.session_files, 2);
+        assert!(removal.preferences);
+        assert!(!state_file.exists());
+        assert!(!error_file.exists());
         assert!(!prefs_file.exists());
+        assert!(!streak_file.exists());
+        assert!(!stats_file.exists());
     }
 
     #[test]
     fn test_uninstall_options_default() {
         let options = UninstallOptions::default();
         assert!(!options.force);
+        assert!(!options.purge);
     }
 
     // Integration test for uninstall flow (requires manual verification)
     #[tokio::test]
     #[ignore] // Ignored by default since it would modify system state
     async fn test_uninstall_flow() {
-        let _options = UninstallOptions { force: true };
+        let _options = UninstallOptions {
+            force: true,
+            purge: false,
+        };
 
         // This test would modify the actual Claude settings
         // In a real scenario, we'd use a temporary directory
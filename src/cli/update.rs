@@ -1,9 +1,11 @@
 use anyhow::{Context, Result, anyhow};
 use cliclack::{confirm, intro, outro};
+use colored::Colorize;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use crate::cli::settings::get_claude_dir;
+use crate::icons::ICON_WARNING;
 use crate::platform::Platform;
 use crate::version::{
     CURRENT_VERSION, VersionManager, format_changelog, format_commits_as_changelog,
@@ -14,6 +16,8 @@ pub struct UpdateOptions {
     pub force: bool,
     pub interactive: bool,
     pub include_prereleases: bool,
+    /// Install a release that publishes no checksum without asking
+    pub skip_checksum: bool,
 }
 
 impl Default for UpdateOptions {
@@ -22,6 +26,7 @@ impl Default for UpdateOptions {
             force: false,
             interactive: true,
             include_prereleases: false,
+            skip_checksum: false,
         }
     }
 }
@@ -43,6 +48,7 @@ impl Default for UpdateOptions {
 /// - Binary download fails or file verification fails
 /// - File system operations fail (backup, replace, permissions)
 /// - User cancels update when in interactive mode
+/// - The release publishes no checksum and installing unverified is not confirmed
 /// - Binary verification fails after installation
 pub async fn update_personalities(options: UpdateOptions) -> Result<()> {
     intro("Checking for updates")?;
@@ -61,7 +67,7 @@ pub async fn update_personalities(options: UpdateOptions) -> Result<()> {
         return Ok(());
     }
 
-    perform_update(&version_manager, &latest_release).await?;
+    perform_update(&version_manager, &latest_release, &options).await?;
 
     Ok(())
 }
@@ -152,6 +158,7 @@ async fn show_update_info_and_confirm(
 async fn perform_update(
     version_manager: &VersionManager,
     latest_release: &crate::version::GitHubRelease,
+    options: &UpdateOptions,
 ) -> Result<()> {
     let platform = Platform::detect().with_context(|| "Failed to detect current platform")?;
 
@@ -168,10 +175,21 @@ async fn perform_update(
             )
         })?;
 
-    let paths = setup_update_paths().await?;
+    let paths = setup_update_paths()?;
 
     println!();
-    download_and_verify_binary(version_manager, asset, &paths.temp_binary).await?;
+    if let Err(error) = download_and_verify_binary(
+        version_manager,
+        latest_release,
+        asset,
+        &paths.temp_binary,
+        options,
+    )
+    .await
+    {
+        let _ = fs::remove_file(&paths.temp_binary).await;
+        return Err(error);
+    }
     backup_and_replace_binary(&paths).await?;
 
     let latest_version = latest_release
@@ -190,67 +208,35 @@ struct UpdatePaths {
     claude_dir: PathBuf,
 }
 
-/// Find existing claude-code-personalities binary installation.
+/// Work out where the update is downloaded, installed and backed up.
 ///
-/// This function searches for an existing binary installation in order of preference:
-/// 1. PATH (using `which` command) - highest priority since it's accessible
-/// 2. ~/.local/bin/claude-code-personalities - standard user binary location
-/// 3. ~/.claude/claude-code-personalities - legacy installation location
-///
-/// # Returns
-/// `Some(PathBuf)` if an existing binary is found, `None` if no binary exists.
+/// The binary replaced is the one running now (with symlinks resolved), and the
+/// download goes next to it so the final rename stays on one filesystem.
 ///
 /// # Errors
-/// Returns an error if command execution fails or directory traversal fails
-async fn find_existing_binary() -> Result<Option<PathBuf>> {
-    // 1. Check PATH using `which` command (highest priority)
-    if let Ok(output) = tokio::process::Command::new("which")
-        .arg("claude-code-personalities")
-        .output()
-        .await
-        && output.status.success()
-    {
-        let path_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if !path_str.is_empty() {
-            let path_binary = PathBuf::from(path_str);
-            if path_binary.exists() {
-                return Ok(Some(path_binary));
-            }
-        }
-    }
-
-    // 2. Check ~/.local/bin/claude-code-personalities
-    if let Some(home_dir) = dirs::home_dir() {
-        let local_bin = home_dir.join(".local/bin/claude-code-personalities");
-        if local_bin.exists() {
-            return Ok(Some(local_bin));
-        }
-    }
-
-    // 3. Check ~/.claude/claude-code-personalities (legacy location)
-    let claude_dir = get_claude_dir()?;
-    let claude_binary = claude_dir.join("claude-code-personalities");
-    if claude_binary.exists() {
-        return Ok(Some(claude_binary));
-    }
-
-    Ok(None)
-}
-
-async fn setup_update_paths() -> Result<UpdatePaths> {
+///
+/// Returns an error, and nothing is downloaded, if the running binary's path
+/// can't be determined.
+fn setup_update_paths() -> Result<UpdatePaths> {
     let claude_dir = get_claude_dir().with_context(|| "Failed to determine Claude directory")?;
 
-    // Find the existing binary location
-    let current_binary = find_existing_binary().await?
-        .ok_or_else(|| anyhow!(
-            "No claude-code-personalities binary found in PATH, ~/.local/bin, or ~/.claude/. \
-            Please install the binary first using the install.sh script or download from GitHub releases."
-        ))?;
+    let current_binary = std::env::current_exe()
+        .and_then(std::fs::canonicalize)
+        .with_context(|| {
+            "Could not determine the path of the running binary; refusing to update. \
+            Reinstall with the install.sh script or download the binary from GitHub releases."
+        })?;
+    let install_dir = current_binary.parent().ok_or_else(|| {
+        anyhow!(
+            "Could not determine the directory of {}; refusing to update",
+            current_binary.display()
+        )
+    })?;
 
     let backup_binary = claude_dir.join(format!(
         "claude-code-personalities.backup.{CURRENT_VERSION}"
     ));
-    let temp_binary = claude_dir.join("claude-code-personalities.tmp");
+    let temp_binary = install_dir.join(".claude-code-personalities.update.tmp");
 
     Ok(UpdatePaths {
         current_binary,
@@ -262,8 +248,10 @@ async fn setup_update_paths() -> Result<UpdatePaths> {
 
 async fn download_and_verify_binary(
     version_manager: &VersionManager,
+    release: &crate::version::GitHubRelease,
     asset: &crate::version::GitHubAsset,
     temp_binary: &Path,
+    options: &UpdateOptions,
 ) -> Result<()> {
     print_status("Downloading...");
     version_manager
@@ -272,10 +260,18 @@ async fn download_and_verify_binary(
         .with_context(|| "Failed to download update")?;
 
     print_status("Verifying...");
-    verify_binary_sha256(asset, temp_binary)
+    verify_binary_sha256(release, asset, temp_binary, options)
         .await
         .with_context(|| "SHA256 verification failed")?;
 
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(temp_binary, std::fs::Permissions::from_mode(0o755))
+            .await
+            .with_context(|| "Failed to set executable permissions on downloaded binary")?;
+    }
+
     Ok(())
 }
 
@@ -285,21 +281,16 @@ async fn backup_and_replace_binary(paths: &UpdatePaths) -> Result<()> {
         .await
         .with_context(|| format!("Failed to create backup: {}", paths.backup_binary.display()))?;
 
-    fs::rename(&paths.temp_binary, &paths.current_binary)
-        .await
-        .with_context(|| "Failed to replace current binary with updated version")?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut permissions = fs::metadata(&paths.current_binary)
-            .await
-            .with_context(|| "Failed to get metadata for updated binary")?
-            .permissions();
-        permissions.set_mode(0o755);
-        fs::set_permissions(&paths.current_binary, permissions)
-            .await
-            .with_context(|| "Failed to set executable permissions on updated binary")?;
+    // The rename swaps the directory entry in one step. On Unix the running
+    // process keeps the old file open by inode, so replacing ourselves is safe.
+    if let Err(error) = fs::rename(&paths.temp_binary, &paths.current_binary).await {
+        let _ = fs::remove_file(&paths.temp_binary).await;
+        return Err(error).with_context(|| {
+            format!(
+                "Failed to replace {} with the updated version",
+                paths.current_binary.display()
+            )
+        });
     }
 
     Ok(())
@@ -329,18 +320,55 @@ async fn verify_installation_and_cleanup(paths: &UpdatePaths, latest_version: &s
     Ok(())
 }
 
-/// Verify binary integrity using SHA256 checksum
+/// Name of the checksum file published beside a release asset
+fn checksum_asset_name(asset_name: &str) -> String {
+    format!("{asset_name}.sha256")
+}
+
+/// Verify binary integrity against the release's SHA256 checksum.
+///
+/// A release without a checksum is only installed after a warning, and then only
+/// with `--skip-checksum` or the user's confirmation.
 async fn verify_binary_sha256(
+    release: &crate::version::GitHubRelease,
     asset: &crate::version::GitHubAsset,
     binary_path: &Path,
+    options: &UpdateOptions,
 ) -> Result<()> {
-    use sha2::{Digest, Sha256};
+    let checksum_name = checksum_asset_name(&asset.name);
+    let Some(checksum_asset) = release
+        .assets
+        .iter()
+        .find(|candidate| candidate.name == checksum_name)
+    else {
+        print_warning(&format!(
+            "This release publishes no {checksum_name}, so the download can't be verified"
+        ));
+        if options.skip_checksum {
+            return Ok(());
+        }
+        let install_unverified = options.interactive
+            && confirm("Install it without checksum verification?")
+                .initial_value(false)
+                .interact()
+                .with_context(|| "Failed to get user confirmation for unverified update")?;
+        if install_unverified {
+            return Ok(());
+        }
+        return Err(anyhow!(
+            "No checksum published for {}; rerun with --skip-checksum to install it unverified",
+            asset.name
+        ));
+    };
 
-    // Download the checksum file
-    let checksum_url = format!("{}.sha256", asset.browser_download_url);
-    let response = reqwest::get(&checksum_url)
+    let response = reqwest::get(&checksum_asset.browser_download_url)
         .await
-        .with_context(|| format!("Failed to download checksum from {}", checksum_url))?;
+        .with_context(|| {
+            format!(
+                "Failed to download checksum from {}",
+                checksum_asset.browser_download_url
+            )
+        })?;
 
     if !response.status().is_success() {
         return Err(anyhow!(
@@ -354,23 +382,26 @@ async fn verify_binary_sha256(
         .await
         .with_context(|| "Failed to read checksum content")?;
 
-    // Parse expected hash (format: "hash  filename" or just "hash")
+    let binary_content = fs::read(binary_path)
+        .await
+        .with_context(|| format!("Failed to read binary: {}", binary_path.display()))?;
+
+    verify_sha256(&binary_content, &checksum_content)
+}
+
+/// Check `content` against a `sha256sum`-style checksum file (`hash  filename` or just `hash`)
+fn verify_sha256(content: &[u8], checksum_content: &str) -> Result<()> {
+    use sha2::{Digest, Sha256};
+
     let expected_hash = checksum_content
         .split_whitespace()
         .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
         .ok_or_else(|| anyhow!("Invalid checksum file format"))?
         .to_lowercase();
 
-    // Compute actual hash of downloaded binary
-    let binary_content = fs::read(binary_path)
-        .await
-        .with_context(|| format!("Failed to read binary: {}", binary_path.display()))?;
-
-    let mut hasher = Sha256::new();
-    hasher.update(&binary_content);
-    let actual_hash = format!("{:x}", hasher.finalize());
+    let actual_hash = format!("{:x}", Sha256::digest(content));
 
-    // Compare hashes
     if actual_hash != expected_hash {
         return Err(anyhow!(
             "Checksum mismatch!\nExpected: {}\nActual:   {}",
@@ -421,20 +452,20 @@ async fn cleanup_old_backups(claude_dir: &PathBuf) -> Result<()> {
         let filename = entry.file_name();
         let filename_str = filename.to_string_lossy();
 
-        if filename_str.starts_with("claude-code-personalities.backup.") {
-            if let Ok(metadata) = entry.metadata().await {
-                backup_files.push((
-                    entry.path(),
-                    metadata
-                        .modified()
-                        .unwrap_or(std::time::SystemTime::UNIX_EPOCH),
-                ));
-            }
+        if filename_str.starts_with("claude-code-personalities.backup.")
+            && let Ok(metadata) = entry.metadata().await
+        {
+            backup_files.push((
+                entry.path(),
+                metadata
+                    .modified()
+                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH),
+            ));
         }
     }
 
     // Sort by modification time (newest first)
-    backup_files.sort_by(|a, b| b.1.cmp(&a.1));
+    backup_files.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     // Remove all but the newest 3 backups
     for (path, _) in backup_files.iter().skip(3) {
@@ -454,6 +485,10 @@ fn print_status(message: &str) {
     println!("  {message}");
 }
 
+fn print_warning(message: &str) {
+    println!("  {} {}", ICON_WARNING.yellow(), message);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -502,11 +537,39 @@ mod tests {
         assert_eq!(other_files, 1, "Should not touch non-backup files");
     }
 
+    #[test]
+    fn test_verify_sha256() {
+        let content = b"binary contents";
+        let actual = {
+            use sha2::{Digest, Sha256};
+            format!("{:x}", Sha256::digest(content))
+        };
+
+        // `sha256sum` output, a bare hash, and upper case all verify
+        assert!(verify_sha256(content, &format!("{actual}  claude-code-personalities\n")).is_ok());
+        assert!(verify_sha256(content, &actual).is_ok());
+        assert!(verify_sha256(content, &actual.to_uppercase()).is_ok());
+
+        let error = verify_sha256(content, &"0".repeat(64)).unwrap_err();
+        assert!(error.to_string().contains("Checksum mismatch"), "{error}");
+        assert!(verify_sha256(content, "").is_err());
+        assert!(verify_sha256(content, "not-a-hash  file").is_err());
+    }
+
+    #[test]
+    fn test_checksum_asset_name() {
+        assert_eq!(
+            checksum_asset_name("claude-code-personalities-linux-x86_64"),
+            "claude-code-personalities-linux-x86_64.sha256"
+        );
+    }
+
     #[test]
     fn test_update_options_default() {
         let options = UpdateOptions::default();
         assert!(!options.force);
         assert!(options.interactive);
         assert!(!options.include_prereleases);
+        assert!(!options.skip_checksum);
     }
 }
@@ -1,3 +1,6 @@
 pub mod preferences;
 
-pub use preferences::{PersonalityPreferences, StatuslineSection};
+pub use preferences::{
+    GitElement, GitRunFrom, GitStatusStyle, ModelNameStyle, PersonalityPreferences,
+    PreferenceGroup, SeparatorStyle, StatuslineSection,
+};
@@ -1,12 +1,178 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
+use crate::atomic_write::write_atomically;
 use crate::error::PersonalityError;
+use crate::icons::{ICON_UPDATE, IconMode};
+use crate::kaomoji::{KaomojiPack, find_pack};
+use crate::state::PersonalityModifier;
 use crate::theme::Theme;
+use crate::types::Activity;
 
 type Result<T> = std::result::Result<T, PersonalityError>;
 
+/// How separators between statusline sections are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SeparatorStyle {
+    /// The configured `separator_char` between spaces
+    #[default]
+    #[serde(alias = "Plain")]
+    Plain,
+    /// Sections on alternating theme backgrounds joined by Powerline arrow
+    /// glyphs (requires a Powerline-patched font); just the glyph without colors
+    #[serde(alias = "Powerline")]
+    Powerline,
+}
+
+/// How the model name is shown in the model section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelNameStyle {
+    /// The full display name, e.g. `Claude 3.5 Sonnet`
+    #[default]
+    #[serde(alias = "Full")]
+    Full,
+    /// Just the model family, e.g. `Sonnet`
+    #[serde(alias = "Short")]
+    Short,
+    /// The family's initial, e.g. `S`
+    #[serde(alias = "Initials")]
+    Initials,
+}
+
+/// Brackets drawn around the model section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelBracketStyle {
+    /// No brackets, e.g. `Sonnet`
+    #[default]
+    #[serde(alias = "None")]
+    None,
+    /// `[Sonnet]`
+    #[serde(alias = "Square")]
+    Square,
+    /// `(Sonnet)`
+    #[serde(alias = "Round")]
+    Round,
+    /// `<Sonnet>`
+    #[serde(alias = "Angle")]
+    Angle,
+}
+
+impl ModelBracketStyle {
+    /// Wrap `text` in this style's brackets
+    #[must_use]
+    pub fn wrap(self, text: &str) -> String {
+        match self {
+            Self::None => text.to_string(),
+            Self::Square => format!("[{text}]"),
+            Self::Round => format!("({text})"),
+            Self::Angle => format!("<{text}>"),
+        }
+    }
+}
+
+/// How the activity label is worded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityLabelStyle {
+    /// The activity name, e.g. `Testing`
+    #[default]
+    #[serde(alias = "TitleCase")]
+    TitleCase,
+    /// The activity name in lowercase, e.g. `testing`
+    #[serde(alias = "Lowercase")]
+    Lowercase,
+    /// A short phrase, e.g. `Running tests`
+    #[serde(alias = "Verbose")]
+    Verbose,
+}
+
+impl ActivityLabelStyle {
+    /// Label for `activity` in this style
+    #[must_use]
+    pub fn label(self, activity: &Activity) -> String {
+        match self {
+            Self::TitleCase => activity.to_string(),
+            Self::Lowercase => activity.to_string().to_lowercase(),
+            Self::Verbose => activity.phrase().to_string(),
+        }
+    }
+}
+
+/// How the git section shows whether the working tree is clean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GitStatusStyle {
+    /// Colored glyphs, e.g. a green `\u{2713}` or an orange `\u{00b1}3`
+    #[default]
+    #[serde(alias = "ColorGlyph")]
+    ColorGlyph,
+    /// Words that don't depend on color, e.g. `clean` or `dirty(3)`
+    #[serde(alias = "TextLabel")]
+    TextLabel,
+}
+
+/// Which workspace directory git commands run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GitRunFrom {
+    /// The directory Claude is currently working in
+    #[default]
+    #[serde(alias = "CurrentDir")]
+    CurrentDir,
+    /// The project root, so a session deep in a subdirectory still reports the root repository
+    #[serde(alias = "ProjectDir")]
+    ProjectDir,
+}
+
+/// A piece of the git section that `git_element_order` can place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitElement {
+    Icon,
+    Branch,
+    Status,
+}
+
+impl GitElement {
+    /// The default arrangement: icon, branch, then status
+    #[must_use]
+    pub fn default_order() -> Vec<GitElement> {
+        vec![Self::Icon, Self::Branch, Self::Status]
+    }
+
+    /// Name used in the config file
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Icon => "icon",
+            Self::Branch => "branch",
+            Self::Status => "status",
+        }
+    }
+
+    /// Parse a list of element names, or `None` if any name is unknown or
+    /// repeated, or the list is empty
+    #[must_use]
+    pub fn parse_order<S: AsRef<str>>(names: &[S]) -> Option<Vec<GitElement>> {
+        let mut order = Vec::new();
+        for name in names {
+            let name = name.as_ref().trim();
+            let element = Self::default_order()
+                .into_iter()
+                .find(|element| element.name().eq_ignore_ascii_case(name))?;
+            if order.contains(&element) {
+                return None;
+            }
+            order.push(element);
+        }
+        (!order.is_empty()).then_some(order)
+    }
+}
+
 /// Advanced display configuration options
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
@@ -17,21 +183,64 @@ pub struct DisplayConfig {
     /// The separator character to use between elements
     #[serde(default = "default_separator")]
     pub separator_char: String,
+    /// Plain separator character or Powerline arrows
+    #[serde(default)]
+    pub separator_style: SeparatorStyle,
+    /// Section that gets the accent separator in front of it instead of the normal one
+    /// (e.g. `model`, to set it apart from the workflow sections). With Powerline
+    /// segments, the arrow into the section is drawn in the accent color instead.
+    #[serde(default)]
+    pub accent_separator_before: Option<StatuslineSection>,
+    /// The accent separator character, drawn in the theme's info color
+    #[serde(default = "default_accent_separator")]
+    pub accent_separator_char: String,
     /// Show debugging info (error counts, session info)
     #[serde(default)]
     pub show_debug_info: bool,
+    /// Glyph shown after the branch when the working tree is clean
+    #[serde(default = "default_git_clean_glyph")]
+    pub git_clean_glyph: String,
+    /// Glyph shown before the dirty file count
+    #[serde(default = "default_git_dirty_glyph")]
+    pub git_dirty_glyph: String,
+    /// End colored output with an ANSI reset so colors don't bleed into the prompt
+    #[serde(default = "default_true")]
+    pub reset_at_end: bool,
+    /// Drop sections that render as only whitespace, so they can't leave doubled,
+    /// leading, or trailing separators behind
+    #[serde(default = "default_true")]
+    pub collapse_separators: bool,
 }
 
 fn default_separator() -> String {
     "\u{2022}".to_string() // • bullet
 }
 
+fn default_accent_separator() -> String {
+    "\u{2503}".to_string() // heavy vertical bar
+}
+
+fn default_git_clean_glyph() -> String {
+    "\u{2713}".to_string() // ✓ check mark
+}
+
+fn default_git_dirty_glyph() -> String {
+    "\u{00b1}".to_string() // ± plus-minus
+}
+
 impl Default for DisplayConfig {
     fn default() -> Self {
         Self {
             show_separators: true,
             separator_char: default_separator(),
+            separator_style: SeparatorStyle::default(),
+            accent_separator_before: None,
+            accent_separator_char: default_accent_separator(),
             show_debug_info: false,
+            git_clean_glyph: default_git_clean_glyph(),
+            git_dirty_glyph: default_git_dirty_glyph(),
+            reset_at_end: true,
+            collapse_separators: true,
         }
     }
 }
@@ -44,9 +253,18 @@ pub enum StatuslineSection {
     Directory,
     Git,
     Activity,
+    ActivityTrail,
     Model,
     UpdateAvailable,
+    ActionCount,
+    ContextUsage,
     DebugInfo,
+    /// Local time of day; opt-in, so not part of the default order
+    Clock,
+    /// Days in a row with activity; opt-in, so not part of the default order
+    DayStreak,
+    /// Recent tool errors, e.g. `\u{f071}3`; opt-in, so not part of the default order
+    Errors,
 }
 
 impl StatuslineSection {
@@ -58,17 +276,216 @@ impl StatuslineSection {
             Self::Directory,
             Self::Git,
             Self::Activity,
+            Self::ActivityTrail,
             Self::Model,
             Self::UpdateAvailable,
+            Self::ActionCount,
+            Self::ContextUsage,
             Self::DebugInfo,
         ]
     }
+
+    /// Name used on the command line and in the config file
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Personality => "personality",
+            Self::Directory => "directory",
+            Self::Git => "git",
+            Self::Activity => "activity",
+            Self::ActivityTrail => "activity_trail",
+            Self::Model => "model",
+            Self::UpdateAvailable => "update_available",
+            Self::ActionCount => "action_count",
+            Self::ContextUsage => "context_usage",
+            Self::DebugInfo => "debug_info",
+            Self::Clock => "clock",
+            Self::DayStreak => "day_streak",
+            Self::Errors => "errors",
+        }
+    }
+
+    /// Every section, including opt-in ones left out of the default order
+    #[must_use]
+    pub fn all() -> Vec<StatuslineSection> {
+        let mut sections = Self::default_order();
+        sections.extend([Self::Clock, Self::DayStreak, Self::Errors]);
+        sections
+    }
+
+    /// Parse a comma-separated list of section names into a section order.
+    ///
+    /// Unknown and duplicate names are rejected. Sections not listed are appended
+    /// in default order unless `exact` is set, in which case they are dropped.
+    pub fn parse_order(list: &str, exact: bool) -> std::result::Result<Vec<Self>, String> {
+        let mut order: Vec<Self> = Vec::new();
+        for name in list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let section: Self = name.parse()?;
+            if order.contains(&section) {
+                return Err(format!("Section '{name}' is listed more than once"));
+            }
+            order.push(section);
+        }
+
+        if order.is_empty() {
+            return Err("No sections given".to_string());
+        }
+
+        if !exact {
+            for section in Self::default_order() {
+                if !order.contains(&section) {
+                    order.push(section);
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+impl std::str::FromStr for StatuslineSection {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let normalized = s.trim().replace('-', "_").to_lowercase();
+        Self::all()
+            .into_iter()
+            .find(|section| section.name() == normalized)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::all().iter().map(StatuslineSection::name).collect();
+                format!(
+                    "Unknown section '{s}'. Valid sections: {}",
+                    valid.join(", ")
+                )
+            })
+    }
+}
+
+/// Groups of related preferences that can be reset independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreferenceGroup {
+    Git,
+    Activity,
+    Directory,
+    Model,
+    Display,
+    Theme,
+}
+
+impl PreferenceGroup {
+    /// Get all preference groups
+    #[must_use]
+    pub fn all() -> Vec<PreferenceGroup> {
+        vec![
+            Self::Git,
+            Self::Activity,
+            Self::Directory,
+            Self::Model,
+            Self::Display,
+            Self::Theme,
+        ]
+    }
+
+    /// Name used on the command line
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Git => "git",
+            Self::Activity => "activity",
+            Self::Directory => "directory",
+            Self::Model => "model",
+            Self::Display => "display",
+            Self::Theme => "theme",
+        }
+    }
+}
+
+impl std::str::FromStr for PreferenceGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|group| group.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::all().iter().map(PreferenceGroup::name).collect();
+                format!(
+                    "Unknown section '{s}'. Valid sections: {}",
+                    valid.join(", ")
+                )
+            })
+    }
+}
+
+/// Per-project preferences override, looked up in the workspace directories
+pub const PROJECT_CONFIG_FILE: &str = ".claude-code-personalities.toml";
+
+/// Recursively merge `overrides` into `base`: objects merge key by key, anything
+/// else replaces the base value
+fn merge_json(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge_json(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// The parts of `value` that differ from `default`: objects are compared key by
+/// key, anything else is kept whole when it differs. `None` means no difference.
+fn diff_json(value: serde_json::Value, default: &serde_json::Value) -> Option<serde_json::Value> {
+    match (value, default) {
+        (serde_json::Value::Object(value), serde_json::Value::Object(default)) => {
+            let changed: serde_json::Map<String, serde_json::Value> = value
+                .into_iter()
+                .filter_map(|(key, value)| match default.get(&key) {
+                    Some(default) => diff_json(value, default).map(|diff| (key, diff)),
+                    None => Some((key, value)),
+                })
+                .collect();
+            (!changed.is_empty()).then_some(serde_json::Value::Object(changed))
+        }
+        (value, default) => (value != *default).then_some(value),
+    }
 }
 
-/// Current config version. Increment when making breaking changes.
+/// Current config version. Increment when making breaking changes, and add a
+/// step to [`MIGRATIONS`] that upgrades the previous version.
 /// v1: Initial version (pre-0.4.0, had compact_mode)
 /// v2: Removed compact_mode from DisplayConfig (0.4.0+)
-pub const CONFIG_VERSION: u32 = 2;
+/// v3: Folded show_current_job and show_current_file into show_context
+pub const CONFIG_VERSION: u32 = 3;
+
+type ConfigObject = serde_json::Map<String, serde_json::Value>;
+
+/// Schema migrations, applied in order to the raw JSON. Entry `i` upgrades a
+/// config from version `i + 1` to `i + 2`.
+const MIGRATIONS: [fn(&mut ConfigObject); 2] = [migrate_v1_to_v2, migrate_v2_to_v3];
+
+fn migrate_v1_to_v2(config: &mut ConfigObject) {
+    if let Some(serde_json::Value::Object(display)) = config.get_mut("display") {
+        display.remove("compact_mode");
+    }
+}
+
+fn migrate_v2_to_v3(config: &mut ConfigObject) {
+    // An explicit show_context already says what the user wants
+    let mut wanted_context = false;
+    for old in ["show_current_job", "show_current_file"] {
+        wanted_context |= config
+            .remove(old)
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+    }
+    if wanted_context && !config.contains_key("show_context") {
+        config.insert("show_context".to_string(), serde_json::Value::Bool(true));
+    }
+}
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -82,16 +499,10 @@ pub struct PersonalityPreferences {
     pub show_personality: bool,
     pub show_activity: bool,
 
-    // Unified context field (replaces show_current_job + show_current_file)
+    // Unified context field (replaced show_current_job + show_current_file in v3)
     #[serde(default = "default_true")]
     pub show_context: bool,
 
-    // Deprecated fields (kept for backward compatibility, not shown in UI)
-    #[serde(default)]
-    pub show_current_job: bool,
-    #[serde(default)]
-    pub show_current_file: bool,
-
     // Git master toggle (parent of show_git_branch, show_git_status)
     #[serde(default = "default_true")]
     pub show_git: bool,
@@ -99,11 +510,85 @@ pub struct PersonalityPreferences {
     pub show_git_branch: bool,
     #[serde(default = "default_true")]
     pub show_git_status: bool,
+    /// Show the number of stash entries, e.g. `\u{2691}3`
+    #[serde(default)]
+    pub show_git_stash: bool,
+    /// Show `@ <hash>` for a detached HEAD and e.g. `(rebasing)` while an operation is in progress
+    #[serde(default)]
+    pub show_detached_head: bool,
+    /// Word appended to plain branch names, e.g. `main branch` (empty to disable)
+    #[serde(default = "default_git_branch_suffix")]
+    pub git_branch_suffix: String,
+    /// Dirty counts above this render as e.g. `±99+` (0 shows the raw count)
+    #[serde(default = "default_git_dirty_count_cap")]
+    pub git_dirty_count_cap: usize,
+    /// Longest branch name (in characters) before its middle is replaced by `\u{2026}` (0 disables)
+    #[serde(default)]
+    pub git_branch_max_width: usize,
+    /// Colored glyphs or plain `clean`/`dirty(N)` words for the git status
+    #[serde(default)]
+    pub git_status_style: GitStatusStyle,
+    /// Split the dirty count into tracked and untracked files, e.g. `~3 +2`
+    #[serde(default)]
+    pub git_detailed_status: bool,
+    /// Color the branch by its conventional prefix: `feat/` success, `fix/` warning, `hotfix/` error
+    #[serde(default)]
+    pub color_branch_by_type: bool,
+    /// Which git pieces to show and in what order, e.g. `["status", "branch"]`
+    /// (unknown or repeated names fall back to `icon`, `branch`, `status`)
+    #[serde(default = "default_git_element_order")]
+    pub git_element_order: Vec<String>,
+    /// Run git in the current directory or the project directory (falls back
+    /// to the current directory when Claude doesn't report a project)
+    #[serde(default)]
+    pub git_run_from: GitRunFrom,
     pub show_current_dir: bool,
     pub show_model: bool,
     #[serde(default = "default_true")]
     pub show_update_available: bool,
+    /// Icon shown before the update notice (empty to hide it)
+    #[serde(default = "default_update_icon")]
+    pub update_icon: String,
+    /// Template for the update notice; `{version}` is replaced (default `v{version}`)
+    #[serde(default)]
+    pub update_format: Option<String>,
     pub use_colors: bool,
+    /// Local hour (0-23) at which quiet hours begin, turning colors off until `quiet_hours_end`
+    #[serde(default)]
+    pub quiet_hours_start: Option<u32>,
+    /// Local hour (0-23) at which quiet hours end; may be earlier than the start for
+    /// an overnight window like 22 to 6
+    #[serde(default)]
+    pub quiet_hours_end: Option<u32>,
+    /// Nerd Font glyphs, Unicode or ASCII stand-ins, or no icons at all
+    #[serde(default)]
+    pub icon_mode: IconMode,
+    #[serde(default)]
+    pub show_action_count: bool,
+    /// Append e.g. `+ 20 streak!` to the personality when the action streak hits a milestone
+    #[serde(default)]
+    pub show_streak_milestones: bool,
+    #[serde(default)]
+    pub show_activity_trail: bool,
+    #[serde(default)]
+    pub show_context_usage: bool,
+    /// Show the local time, at the end of the statusline unless `clock` is in `section_order`
+    #[serde(default)]
+    pub show_clock: bool,
+    /// `strftime`-style format for the clock, e.g. `%H:%M` or `%a %l:%M %p`
+    #[serde(default = "default_clock_format")]
+    pub clock_format: String,
+    /// Track days in a row with activity and show the streak, at the end of the
+    /// statusline unless `day_streak` is in `section_order`
+    #[serde(default)]
+    pub show_day_streak: bool,
+    /// Show a warning badge with the recent error count, e.g. `\u{f071}3`, at the end
+    /// of the statusline unless `errors` is in `section_order`
+    #[serde(default)]
+    pub show_error_count: bool,
+    /// Tally shown activities and personalities in a local stats file (never sent anywhere)
+    #[serde(default)]
+    pub collect_stats: bool,
 
     // Per-section icon toggles
     #[serde(default = "default_true")]
@@ -118,10 +603,71 @@ pub struct PersonalityPreferences {
     // Per-section label toggles
     #[serde(default = "default_true")]
     pub show_activity_label: bool,
+    /// Show only the activity icon when there is one, dropping the label
+    #[serde(default)]
+    pub compact_activity: bool,
+    /// Wording of the activity label: `Testing`, `testing`, or `Running tests`
+    #[serde(default)]
+    pub activity_label_style: ActivityLabelStyle,
     #[serde(default = "default_true")]
     pub show_directory_label: bool,
+    /// Hide the directory when it is the repository root and the git branch is shown
+    #[serde(default)]
+    pub hide_dir_when_repo_root: bool,
     #[serde(default = "default_true")]
     pub show_model_label: bool,
+    #[serde(default)]
+    pub model_name_style: ModelNameStyle,
+    /// Brackets around the model icon and name, e.g. `[Sonnet]`
+    #[serde(default)]
+    pub model_bracket_style: ModelBracketStyle,
+
+    // Longest file name (in characters) shown in the activity context
+    #[serde(default = "default_filename_max_width")]
+    pub filename_max_width: usize,
+
+    // Per-activity icon overrides (empty string hides the icon)
+    #[serde(default)]
+    pub activity_icons: HashMap<Activity, String>,
+
+    // Consecutive-action counts above which pattern personalities kick in
+    #[serde(default = "default_hyperfocus_threshold")]
+    pub hyperfocus_threshold: u32,
+    #[serde(default = "default_berserker_threshold")]
+    pub berserker_threshold: u32,
+
+    // Activities whose successes never build momentum toward InTheZone (any
+    // activity not listed, including ones added in later versions, does)
+    #[serde(default, serialize_with = "serialize_sorted_activities")]
+    pub momentum_excluded: HashSet<Activity>,
+
+    // Seconds without activity before showing the resting face (0 disables)
+    #[serde(default)]
+    pub idle_timeout_seconds: u64,
+
+    // Seconds after a session starts during which an idle session keeps the booting-up face (0 disables)
+    #[serde(default)]
+    pub boot_grace_seconds: u64,
+
+    // Skip state writes within this many milliseconds of the last one when only
+    // display details changed; counters are always written (0 saves on every hook)
+    #[serde(default)]
+    pub save_debounce_ms: u64,
+
+    // Custom faces for the mood personalities (empty or absent uses the built-in face)
+    #[serde(default)]
+    pub frustrated_face: Option<String>,
+    #[serde(default)]
+    pub in_the_zone_face: Option<String>,
+    #[serde(default)]
+    pub normal_face: Option<String>,
+    /// Themed kaomoji pack (e.g. `cats`) replacing the mood and default faces
+    #[serde(default)]
+    pub kaomoji_pack: Option<String>,
+    /// Personality shown when nothing more specific applies, e.g. `(-_-) Zen Mode`
+    /// (empty or absent uses the built-in `( ˘ ³˘) Chillin`)
+    #[serde(default)]
+    pub default_personality: Option<String>,
 
     // Advanced configurations
     #[serde(default)]
@@ -134,12 +680,64 @@ pub struct PersonalityPreferences {
     // Section ordering for statusline
     #[serde(default = "StatuslineSection::default_order")]
     pub section_order: Vec<StatuslineSection>,
+
+    // Sections pushed to the right edge of the terminal (kept in section order)
+    #[serde(default)]
+    pub right_align_sections: Vec<StatuslineSection>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_filename_max_width() -> usize {
+    20
+}
+
+fn default_git_branch_suffix() -> String {
+    "branch".to_string()
+}
+
+fn default_git_element_order() -> Vec<String> {
+    GitElement::default_order()
+        .iter()
+        .map(|element| element.name().to_string())
+        .collect()
+}
+
+fn default_update_icon() -> String {
+    ICON_UPDATE.to_string()
+}
+
+fn default_git_dirty_count_cap() -> usize {
+    99
+}
+
+/// Format used for the clock when none is configured or the configured one is invalid
+pub const DEFAULT_CLOCK_FORMAT: &str = "%H:%M";
+
+fn default_clock_format() -> String {
+    DEFAULT_CLOCK_FORMAT.to_string()
+}
+
+fn default_hyperfocus_threshold() -> u32 {
+    10
+}
+
+fn default_berserker_threshold() -> u32 {
+    20
+}
+
+/// Write a set of activities in a stable order so saved configs don't churn
+fn serialize_sorted_activities<S: serde::Serializer>(
+    activities: &HashSet<Activity>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&Activity> = activities.iter().collect();
+    sorted.sort_by_key(|activity| activity.to_string());
+    serializer.collect_seq(sorted)
+}
+
 fn default_config_version() -> u32 {
     // Default to 1 for old configs without version field
     // This triggers migration on first load
@@ -153,15 +751,37 @@ impl Default for PersonalityPreferences {
             show_personality: true,
             show_activity: true,
             show_context: true,
-            show_current_job: false,  // Deprecated
-            show_current_file: false, // Deprecated
-            show_git: true,           // Git master toggle
+            show_git: true, // Git master toggle
             show_git_branch: true,
-            show_git_status: true,   // Enabled by default
+            show_git_status: true, // Enabled by default
+            show_git_stash: false,
+            show_detached_head: false,
+            git_branch_suffix: default_git_branch_suffix(),
+            git_dirty_count_cap: default_git_dirty_count_cap(),
+            git_branch_max_width: 0,
+            git_status_style: GitStatusStyle::default(),
+            git_detailed_status: false,
+            color_branch_by_type: false,
+            git_element_order: default_git_element_order(),
+            git_run_from: GitRunFrom::default(),
             show_current_dir: false, // Hidden by default per user request
             show_model: true,
             show_update_available: true, // Show update indicator by default
+            update_icon: default_update_icon(),
+            update_format: None,
             use_colors: true,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            icon_mode: IconMode::NerdFont,
+            show_action_count: false,
+            show_streak_milestones: false,
+            show_activity_trail: false,
+            show_context_usage: false,
+            show_clock: false,
+            clock_format: default_clock_format(),
+            show_day_streak: false,
+            show_error_count: false,
+            collect_stats: false,
             // Per-section icon toggles (all enabled by default)
             show_activity_icon: true,
             show_git_icon: true,
@@ -169,11 +789,30 @@ impl Default for PersonalityPreferences {
             show_model_icon: true,
             // Per-section label toggles (all enabled by default)
             show_activity_label: true,
+            compact_activity: false,
+            activity_label_style: ActivityLabelStyle::default(),
             show_directory_label: true,
+            hide_dir_when_repo_root: false,
             show_model_label: true,
+            model_name_style: ModelNameStyle::default(),
+            model_bracket_style: ModelBracketStyle::default(),
+            filename_max_width: default_filename_max_width(),
+            activity_icons: HashMap::new(),
+            hyperfocus_threshold: default_hyperfocus_threshold(),
+            berserker_threshold: default_berserker_threshold(),
+            momentum_excluded: HashSet::new(),
+            idle_timeout_seconds: 0,
+            boot_grace_seconds: 0,
+            save_debounce_ms: 0,
+            frustrated_face: None,
+            in_the_zone_face: None,
+            normal_face: None,
+            kaomoji_pack: None,
+            default_personality: None,
             display: DisplayConfig::default(),
             theme: Theme::default(),
             section_order: StatuslineSection::default_order(),
+            right_align_sections: Vec::new(),
         }
     }
 }
@@ -189,7 +828,6 @@ impl PersonalityPreferences {
     /// This function will return an error if:
     /// - The home directory cannot be determined
     /// - The HOME environment variable is not set
-    #[must_use]
     pub fn get_preferences_path() -> Result<PathBuf> {
         // Allow override via environment variable (useful for testing)
         if let Ok(path) = std::env::var("CLAUDE_PERSONALITIES_CONFIG") {
@@ -223,18 +861,19 @@ impl PersonalityPreferences {
                     source: e,
                     suggestion: Some("Check file permissions".to_string()),
                 })?;
-            let mut prefs: PersonalityPreferences =
-                serde_json::from_str(&content).map_err(|e| PersonalityError::Parsing {
-                    context: "personality preferences file".to_string(),
-                    input_preview: Some(content.chars().take(100).collect()),
-                    source: e,
-                    suggestion: Some("Check JSON syntax in preferences file".to_string()),
-                })?;
+            let (mut prefs, saved_version) = Self::from_json_migrated(&content)?;
+
+            // Sections added after the config was written still need a slot
+            prefs.ensure_all_sections();
 
-            // Migrate old config versions
-            if prefs.config_version < CONFIG_VERSION {
-                prefs.migrate();
-                // Save migrated config so it's compatible with current version
+            // Warn once: the clamped thresholds are saved with the migrated config
+            let clamped = prefs.clamp_pattern_thresholds();
+            if let Some(warning) = &clamped {
+                eprintln!("Warning: {warning}");
+            }
+
+            // Save migrated config so it's compatible with current version
+            if saved_version < CONFIG_VERSION || clamped.is_some() {
                 prefs.save().await?;
             }
 
@@ -244,15 +883,322 @@ impl PersonalityPreferences {
         }
     }
 
-    /// Migrate config from older versions to current version.
-    /// Called automatically when loading a config with older version.
-    fn migrate(&mut self) {
-        // v1 -> v2: compact_mode was removed from DisplayConfig
-        // No action needed - the field is simply ignored if present in JSON
-        // and won't be written on save
+    /// Load global preferences with a project-local override layered on top.
+    ///
+    /// The first of `dirs` containing [`PROJECT_CONFIG_FILE`] wins. Keys set there
+    /// replace the global values (nested tables merge key by key); everything else
+    /// is inherited. Without a project config this is the same as [`Self::load_or_default`].
+    pub async fn load_or_default_for_workspace(dirs: &[&str]) -> Result<Self> {
+        let global = Self::load_or_default().await?;
+
+        let Some(path) = dirs
+            .iter()
+            .map(|dir| Path::new(dir).join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+        else {
+            return Ok(global);
+        };
+
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| PersonalityError::IO {
+                operation: "read project preferences".to_string(),
+                path: Some(path.display().to_string()),
+                source: e,
+                suggestion: Some("Check file permissions".to_string()),
+            })?;
+        global.with_project_overrides(&content)
+    }
+
+    /// Apply the settings from a project config (TOML) on top of these preferences
+    pub fn with_project_overrides(&self, project_toml: &str) -> Result<Self> {
+        let overrides: toml::Value =
+            toml::from_str(project_toml).map_err(|e| PersonalityError::System {
+                message: format!("Invalid project config {PROJECT_CONFIG_FILE}: {e}"),
+                suggestion: Some("Check TOML syntax in the project config".to_string()),
+            })?;
+
+        let to_parsing_error = |e: serde_json::Error| PersonalityError::Parsing {
+            context: format!("project config {PROJECT_CONFIG_FILE}"),
+            input_preview: Some(project_toml.chars().take(100).collect()),
+            source: e,
+            suggestion: Some("Use the same keys and values as the global config".to_string()),
+        };
+        let mut merged = serde_json::to_value(self).map_err(to_parsing_error)?;
+        merge_json(
+            &mut merged,
+            serde_json::to_value(overrides).map_err(to_parsing_error)?,
+        );
+
+        let mut prefs: Self = serde_json::from_value(merged).map_err(to_parsing_error)?;
+        prefs.ensure_all_sections();
+        // The project config is never rewritten, so warning here would repeat on
+        // every load; clamp quietly instead
+        let _ = prefs.clamp_pattern_thresholds();
+        Ok(prefs)
+    }
+
+    /// Parse a preferences file, upgrading older schemas to [`CONFIG_VERSION`]
+    /// one step at a time.
+    ///
+    /// Returns the preferences along with the version the file was written as.
+    /// Fields missing from the file take their defaults, and fields that no
+    /// longer exist are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is not valid preferences JSON.
+    pub fn from_json_migrated(content: &str) -> Result<(Self, u32)> {
+        let parse_error = |e| PersonalityError::Parsing {
+            context: "personality preferences file".to_string(),
+            input_preview: Some(content.chars().take(100).collect()),
+            source: e,
+            suggestion: Some("Check JSON syntax in preferences file".to_string()),
+        };
+
+        let mut value: serde_json::Value = serde_json::from_str(content).map_err(parse_error)?;
+        let saved_version = value
+            .get("config_version")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or_else(default_config_version);
+
+        if let Some(config) = value.as_object_mut()
+            && saved_version < CONFIG_VERSION
+        {
+            let first_step = saved_version.max(1) as usize - 1;
+            for migration in &MIGRATIONS[first_step..] {
+                migration(config);
+            }
+            config.insert(
+                "config_version".to_string(),
+                serde_json::Value::from(CONFIG_VERSION),
+            );
+        }
+
+        let prefs = serde_json::from_value(value).map_err(parse_error)?;
+        Ok((prefs, saved_version))
+    }
+
+    /// Upgrade the saved preferences file to the current schema and rewrite it,
+    /// dropping any fields the current version no longer knows about.
+    ///
+    /// Returns the version the file was written as, or `None` if there is no
+    /// saved preferences file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, parsed, or written back.
+    pub async fn migrate_saved() -> Result<Option<u32>> {
+        let path = Self::get_preferences_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| PersonalityError::IO {
+                operation: "read personality preferences".to_string(),
+                path: Some(path.display().to_string()),
+                source: e,
+                suggestion: Some("Check file permissions".to_string()),
+            })?;
+        let (prefs, saved_version) = Self::from_json_migrated(&content)?;
+        prefs.save().await?;
+        Ok(Some(saved_version))
+    }
+
+    /// Git pieces to render, in order, falling back to the default order when
+    /// `git_element_order` is invalid
+    #[must_use]
+    pub fn git_elements(&self) -> Vec<GitElement> {
+        GitElement::parse_order(&self.git_element_order).unwrap_or_else(GitElement::default_order)
+    }
+
+    /// Sections to render, in order: `section_order`, with opt-in sections (the
+    /// clock, day streak and error count) appended when enabled but not placed anywhere
+    #[must_use]
+    pub fn display_order(&self) -> Vec<StatuslineSection> {
+        let mut order = self.section_order.clone();
+        for (enabled, section) in [
+            (self.show_clock, StatuslineSection::Clock),
+            (self.show_day_streak, StatuslineSection::DayStreak),
+            (self.show_error_count, StatuslineSection::Errors),
+        ] {
+            if enabled && !order.contains(&section) {
+                order.push(section);
+            }
+        }
+        order
+    }
+
+    /// Append any sections missing from `section_order` (e.g. sections introduced
+    /// after the config file was written) in their default relative position.
+    pub fn ensure_all_sections(&mut self) {
+        for (index, section) in StatuslineSection::default_order().into_iter().enumerate() {
+            if !self.section_order.contains(&section) {
+                let position = index.min(self.section_order.len());
+                self.section_order.insert(position, section);
+            }
+        }
+    }
+
+    /// Only the preferences that differ from the defaults, as a JSON object.
+    ///
+    /// Nested settings are trimmed to their changed keys, so the result is the
+    /// smallest config that reproduces this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preferences cannot be serialized to JSON
+    pub fn non_default_fields(&self) -> Result<serde_json::Value> {
+        let to_json = |prefs: &Self| {
+            serde_json::to_value(prefs).map_err(|e| PersonalityError::Parsing {
+                context: "serializing personality preferences to JSON".to_string(),
+                input_preview: None,
+                source: e,
+                suggestion: Some("Check data validity".to_string()),
+            })
+        };
+        let defaults = to_json(&Self::default())?;
+        Ok(diff_json(to_json(self)?, &defaults)
+            .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new())))
+    }
+
+    /// Selected kaomoji pack, ignoring names that no longer match a pack
+    pub fn kaomoji_pack(&self) -> Option<&'static KaomojiPack> {
+        self.kaomoji_pack
+            .as_deref()
+            .and_then(|name| find_pack(name).ok())
+    }
+
+    /// Whether `hour` (0-23, local time) falls within quiet hours.
+    ///
+    /// The window includes the start hour and excludes the end hour. It is off
+    /// unless both ends are set to valid, different hours.
+    #[must_use]
+    pub fn in_quiet_hours(&self, hour: u32) -> bool {
+        match (self.quiet_hours_start, self.quiet_hours_end) {
+            (Some(start), Some(end)) if start < 24 && end < 24 && start != end => {
+                if start < end {
+                    (start..end).contains(&hour)
+                } else {
+                    // Overnight window, e.g. 22 to 6
+                    hour >= start || hour < end
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Custom fallback personality, ignoring empty or whitespace-only values
+    pub fn default_personality(&self) -> Option<&str> {
+        self.default_personality
+            .as_deref()
+            .map(str::trim)
+            .filter(|personality| !personality.is_empty())
+    }
+
+    /// Custom face configured for a mood, ignoring empty or whitespace-only values
+    pub fn mood_face(&self, modifier: &PersonalityModifier) -> Option<&str> {
+        let face = match modifier {
+            PersonalityModifier::Frustrated => &self.frustrated_face,
+            PersonalityModifier::InTheZone => &self.in_the_zone_face,
+            PersonalityModifier::Normal => &self.normal_face,
+        };
+        face.as_deref()
+            .map(str::trim)
+            .filter(|face| !face.is_empty())
+    }
+
+    /// Keep the berserker threshold above the hyperfocus threshold so both
+    /// personalities stay reachable. Returns a warning when clamping was needed.
+    pub fn clamp_pattern_thresholds(&mut self) -> Option<String> {
+        if self.berserker_threshold > self.hyperfocus_threshold {
+            return None;
+        }
+
+        let clamped = self.hyperfocus_threshold.saturating_add(1);
+        let warning = format!(
+            "berserker_threshold ({}) must be greater than hyperfocus_threshold ({}); using {clamped}",
+            self.berserker_threshold, self.hyperfocus_threshold
+        );
+        self.berserker_threshold = clamped;
+        Some(warning)
+    }
+
+    /// Whether successes at `activity` build momentum toward InTheZone
+    #[must_use]
+    pub fn builds_momentum(&self, activity: &Activity) -> bool {
+        !self.momentum_excluded.contains(activity)
+    }
+
+    /// Reset only the preferences belonging to `group`, leaving everything else untouched.
+    pub fn reset_group(&mut self, group: PreferenceGroup) {
+        match group {
+            PreferenceGroup::Git => self.reset_git(),
+            PreferenceGroup::Activity => self.reset_activity(),
+            PreferenceGroup::Directory => self.reset_directory(),
+            PreferenceGroup::Model => self.reset_model(),
+            PreferenceGroup::Display => {
+                self.display = DisplayConfig::default();
+                self.icon_mode = IconMode::default();
+                self.show_clock = false;
+                self.clock_format = default_clock_format();
+            }
+            PreferenceGroup::Theme => self.theme = Theme::default(),
+        }
+    }
+
+    /// Reset git section toggles and formatting
+    pub fn reset_git(&mut self) {
+        let defaults = Self::default();
+        self.show_git = defaults.show_git;
+        self.show_git_branch = defaults.show_git_branch;
+        self.show_git_status = defaults.show_git_status;
+        self.show_git_stash = defaults.show_git_stash;
+        self.show_detached_head = defaults.show_detached_head;
+        self.show_git_icon = defaults.show_git_icon;
+        self.git_branch_suffix = defaults.git_branch_suffix;
+        self.git_dirty_count_cap = defaults.git_dirty_count_cap;
+        self.git_branch_max_width = defaults.git_branch_max_width;
+        self.git_status_style = defaults.git_status_style;
+        self.git_detailed_status = defaults.git_detailed_status;
+        self.color_branch_by_type = defaults.color_branch_by_type;
+        self.git_element_order = defaults.git_element_order;
+        self.git_run_from = defaults.git_run_from;
+    }
 
-        // Update to current version
-        self.config_version = CONFIG_VERSION;
+    /// Reset activity section toggles and icon overrides
+    pub fn reset_activity(&mut self) {
+        let defaults = Self::default();
+        self.show_activity = defaults.show_activity;
+        self.show_context = defaults.show_context;
+        self.show_activity_icon = defaults.show_activity_icon;
+        self.show_activity_label = defaults.show_activity_label;
+        self.compact_activity = defaults.compact_activity;
+        self.activity_label_style = defaults.activity_label_style;
+        self.filename_max_width = defaults.filename_max_width;
+        self.activity_icons = defaults.activity_icons;
+    }
+
+    /// Reset directory section toggles
+    pub fn reset_directory(&mut self) {
+        let defaults = Self::default();
+        self.show_current_dir = defaults.show_current_dir;
+        self.show_directory_icon = defaults.show_directory_icon;
+        self.show_directory_label = defaults.show_directory_label;
+        self.hide_dir_when_repo_root = defaults.hide_dir_when_repo_root;
+    }
+
+    /// Reset model section toggles
+    pub fn reset_model(&mut self) {
+        let defaults = Self::default();
+        self.show_model = defaults.show_model;
+        self.show_model_icon = defaults.show_model_icon;
+        self.model_name_style = defaults.model_name_style;
+        self.model_bracket_style = defaults.model_bracket_style;
+        self.show_model_label = defaults.show_model_label;
     }
 
     /// Save preferences to file.
@@ -286,7 +1232,7 @@ impl PersonalityPreferences {
                 source: e,
                 suggestion: Some("Check data validity".to_string()),
             })?;
-        fs::write(&path, content)
+        write_atomically(&path, &content)
             .await
             .map_err(|e| PersonalityError::IO {
                 operation: "write personality preferences".to_string(),
@@ -297,24 +1243,6 @@ impl PersonalityPreferences {
         Ok(())
     }
 
-    /// Get a list of all basic display preference options with their current values
-    #[must_use]
-    pub fn get_display_options(&self) -> Vec<(&'static str, bool)> {
-        vec![
-            ("Personality", self.show_personality),
-            ("Activity", self.show_activity),
-            ("Activity Context", self.show_context), // Unified: shows files OR commands depending on activity
-            ("Git Branch", self.show_git_branch),
-            ("Git Status", self.show_git_status),
-            ("Current Directory", self.show_current_dir),
-            ("Model", self.show_model),
-            ("Update Available", self.show_update_available),
-            ("Colors", self.use_colors),
-            ("Separators", self.display.show_separators),
-            ("Debug Info", self.display.show_debug_info),
-        ]
-    }
-
     /// Update preferences from a list of selected option names
     pub fn update_from_selections(&mut self, selections: &[&str]) {
         // Reset all to false first
@@ -324,10 +1252,14 @@ impl PersonalityPreferences {
         self.show_git = false;
         self.show_git_branch = false;
         self.show_git_status = false;
+        self.show_git_stash = false;
         self.show_current_dir = false;
         self.show_model = false;
         self.show_update_available = false;
         self.use_colors = false;
+        self.show_action_count = false;
+        self.show_activity_trail = false;
+        self.show_context_usage = false;
         self.show_activity_icon = false;
         self.show_git_icon = false;
         self.show_directory_icon = false;
@@ -347,10 +1279,14 @@ impl PersonalityPreferences {
                 "Git" => self.show_git = true,
                 "Git Branch" => self.show_git_branch = true,
                 "Git Status" => self.show_git_status = true,
+                "Git Stash" => self.show_git_stash = true,
                 "Current Directory" => self.show_current_dir = true,
                 "Model" => self.show_model = true,
                 "Update Available" => self.show_update_available = true,
                 "Colors" => self.use_colors = true,
+                "Action Count" => self.show_action_count = true,
+                "Activity Trail" => self.show_activity_trail = true,
+                "Context Usage" => self.show_context_usage = true,
                 "Activity Icon" => self.show_activity_icon = true,
                 "Git Icon" => self.show_git_icon = true,
                 "Directory Icon" => self.show_directory_icon = true,
@@ -369,6 +1305,7 @@ impl PersonalityPreferences {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::atomic_write::write_temp_file;
     use tempfile::tempdir;
 
     #[test]
@@ -394,29 +1331,13 @@ mod tests {
         assert!(prefs.show_directory_label);
         assert!(prefs.show_model_label);
         // Section order
-        assert_eq!(prefs.section_order.len(), 7);
+        assert!(!prefs.show_action_count);
+        assert!(!prefs.show_context_usage);
+        assert_eq!(prefs.idle_timeout_seconds, 0); // Resting face is opt-in
+        assert_eq!(prefs.section_order.len(), 10);
         assert_eq!(prefs.section_order[0], StatuslineSection::Personality);
     }
 
-    #[test]
-    fn test_get_display_options() {
-        let prefs = PersonalityPreferences::default();
-        let options = prefs.get_display_options();
-
-        assert_eq!(options.len(), 11); // Removed Icons (now per-section)
-        assert!(options.iter().any(|(name, _)| *name == "Personality"));
-        assert!(options.iter().any(|(name, _)| *name == "Activity"));
-        assert!(options.iter().any(|(name, _)| *name == "Activity Context")); // Unified context
-        assert!(options.iter().any(|(name, _)| *name == "Git Branch"));
-        assert!(options.iter().any(|(name, _)| *name == "Git Status"));
-        assert!(options.iter().any(|(name, _)| *name == "Current Directory"));
-        assert!(options.iter().any(|(name, _)| *name == "Model"));
-        assert!(options.iter().any(|(name, _)| *name == "Update Available"));
-        assert!(options.iter().any(|(name, _)| *name == "Colors"));
-        assert!(options.iter().any(|(name, _)| *name == "Separators"));
-        assert!(options.iter().any(|(name, _)| *name == "Debug Info"));
-    }
-
     #[test]
     fn test_update_from_selections() {
         let mut prefs = PersonalityPreferences::default();
@@ -469,6 +1390,126 @@ mod tests {
         assert_eq!(loaded_prefs.show_personality, prefs.show_personality);
     }
 
+    #[test]
+    fn test_migrate_oldest_config_to_current() {
+        assert_eq!(MIGRATIONS.len() + 1, CONFIG_VERSION as usize);
+
+        // An unversioned (v1) config: no show_model, and two fields that have since gone
+        let old = r#"{
+            "show_personality": false,
+            "show_context": false,
+            "show_current_file": true,
+            "display": { "compact_mode": true, "show_debug_info": true }
+        }"#;
+
+        let (prefs, saved_version) = PersonalityPreferences::from_json_migrated(old).unwrap();
+        assert_eq!(saved_version, 1);
+        assert_eq!(prefs.config_version, CONFIG_VERSION);
+
+        // Settings carry over, missing ones take defaults
+        assert!(!prefs.show_personality);
+        assert!(prefs.display.show_debug_info);
+        assert!(prefs.show_model);
+        assert_eq!(prefs.section_order, StatuslineSection::default_order());
+
+        // An explicit context toggle wins over the deprecated file toggle
+        assert!(!prefs.show_context);
+
+        // Dead fields are not written back
+        let rewritten = serde_json::to_value(&prefs).unwrap();
+        assert!(rewritten.get("show_current_file").is_none());
+        assert!(rewritten.get("show_current_job").is_none());
+        assert!(rewritten["display"].get("compact_mode").is_none());
+
+        // Without one, the deprecated toggle lives on as the context toggle
+        let (prefs, _) = PersonalityPreferences::from_json_migrated(
+            r#"{ "config_version": 2, "show_current_job": true }"#,
+        )
+        .unwrap();
+        assert!(prefs.show_context);
+    }
+
+    #[test]
+    fn test_current_config_is_not_migrated() {
+        let current = serde_json::to_string(&PersonalityPreferences {
+            show_context: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let (prefs, saved_version) = PersonalityPreferences::from_json_migrated(&current).unwrap();
+        assert_eq!(saved_version, CONFIG_VERSION);
+        assert!(!prefs.show_context);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_save_leaves_original_intact() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("personalities_config.json");
+
+        let original = PersonalityPreferences {
+            show_current_dir: true,
+            ..Default::default()
+        };
+        let content = serde_json::to_string_pretty(&original).unwrap();
+        write_atomically(&path, &content).await.unwrap();
+
+        // A save that dies mid-write only ever touches its temp file
+        let changed = serde_json::to_string_pretty(&PersonalityPreferences {
+            show_current_dir: false,
+            ..Default::default()
+        })
+        .unwrap();
+        write_temp_file(&path, &changed[..changed.len() / 2])
+            .await
+            .unwrap();
+
+        let loaded: PersonalityPreferences =
+            serde_json::from_str(&fs::read_to_string(&path).await.unwrap()).unwrap();
+        assert!(loaded.show_current_dir);
+
+        // A completed save replaces the file and leaves no temp file of its own
+        write_atomically(&path, &changed).await.unwrap();
+        let loaded: PersonalityPreferences =
+            serde_json::from_str(&fs::read_to_string(&path).await.unwrap()).unwrap();
+        assert!(!loaded.show_current_dir);
+        let temp_files = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(
+            temp_files, 1,
+            "only the interrupted write's temp file remains"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_save_through_symlink_keeps_link() {
+        let temp_dir = tempdir().unwrap();
+        let target = temp_dir.path().join("dotfiles_config.json");
+        let link = temp_dir.path().join("personalities_config.json");
+        std::fs::write(&target, "{}").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomically(&link, "{\"show_model\": false}")
+            .await
+            .unwrap();
+
+        assert!(std::fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "{\"show_model\": false}"
+        );
+    }
+
     #[test]
     fn test_reset_to_defaults() {
         // Start with non-default values
@@ -517,6 +1558,121 @@ mod tests {
         assert!(prefs.use_colors); // default is true
     }
 
+    #[test]
+    fn test_ensure_all_sections_backfills_new_sections() {
+        // A config saved before ActionCount existed
+        let old_config = r#"{
+            "section_order": ["model", "personality", "git", "activity", "directory", "update_available", "debug_info"]
+        }"#;
+
+        let mut prefs: PersonalityPreferences = serde_json::from_str(old_config).unwrap();
+        assert!(
+            !prefs
+                .section_order
+                .contains(&StatuslineSection::ActionCount)
+        );
+
+        prefs.ensure_all_sections();
+        assert_eq!(prefs.section_order.len(), 10);
+        assert!(
+            prefs
+                .section_order
+                .contains(&StatuslineSection::ActionCount)
+        );
+        // Existing custom order is preserved
+        assert_eq!(prefs.section_order[0], StatuslineSection::Model);
+        assert_eq!(prefs.section_order[1], StatuslineSection::Personality);
+    }
+
+    #[test]
+    fn test_quiet_hours_window() {
+        let quiet = |start, end| PersonalityPreferences {
+            quiet_hours_start: start,
+            quiet_hours_end: end,
+            ..Default::default()
+        };
+
+        // Same-day window: start included, end excluded
+        let afternoon = quiet(Some(13), Some(15));
+        assert!(!afternoon.in_quiet_hours(12));
+        assert!(afternoon.in_quiet_hours(13));
+        assert!(afternoon.in_quiet_hours(14));
+        assert!(!afternoon.in_quiet_hours(15));
+
+        // Overnight window wraps around midnight
+        let overnight = quiet(Some(22), Some(6));
+        for hour in [22, 23, 0, 3, 5] {
+            assert!(overnight.in_quiet_hours(hour), "{hour}");
+        }
+        for hour in [6, 12, 21] {
+            assert!(!overnight.in_quiet_hours(hour), "{hour}");
+        }
+
+        // Off unless both ends are valid, different hours
+        for prefs in [
+            PersonalityPreferences::default(),
+            quiet(Some(22), None),
+            quiet(None, Some(6)),
+            quiet(Some(8), Some(8)),
+            quiet(Some(22), Some(30)),
+        ] {
+            assert!((0..24).all(|hour| !prefs.in_quiet_hours(hour)));
+        }
+    }
+
+    #[test]
+    fn test_clock_is_opt_in() {
+        let mut prefs = PersonalityPreferences::default();
+        assert!(!prefs.section_order.contains(&StatuslineSection::Clock));
+        assert!(!prefs.display_order().contains(&StatuslineSection::Clock));
+        assert_eq!(
+            "clock".parse::<StatuslineSection>(),
+            Ok(StatuslineSection::Clock)
+        );
+
+        // Enabled but unplaced, the clock goes last
+        prefs.show_clock = true;
+        assert_eq!(
+            prefs.display_order().last(),
+            Some(&StatuslineSection::Clock)
+        );
+
+        // A placed clock keeps its position
+        prefs.section_order = StatuslineSection::parse_order("clock,personality", false).unwrap();
+        let order = prefs.display_order();
+        assert_eq!(order[0], StatuslineSection::Clock);
+        assert_eq!(
+            order
+                .iter()
+                .filter(|s| **s == StatuslineSection::Clock)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_activity_icons_deserialize() {
+        let config = r#"{
+            "activity_icons": { "Testing": "\uf0c3", "Executing": "" }
+        }"#;
+
+        let prefs: PersonalityPreferences = serde_json::from_str(config).unwrap();
+        assert_eq!(
+            prefs
+                .activity_icons
+                .get(&Activity::Testing)
+                .map(String::as_str),
+            Some("\u{f0c3}")
+        );
+        assert_eq!(
+            prefs
+                .activity_icons
+                .get(&Activity::Executing)
+                .map(String::as_str),
+            Some("")
+        );
+    }
+
     #[test]
     fn test_forward_compatibility_extra_fields() {
         // Simulate a new config file with fields this binary doesn't know about
@@ -530,4 +1686,224 @@ mod tests {
         let prefs: PersonalityPreferences = serde_json::from_str(future_config).unwrap();
         assert!(!prefs.show_personality);
     }
+
+    #[test]
+    fn test_pattern_thresholds_default_and_clamp() {
+        let mut prefs = PersonalityPreferences::default();
+        assert_eq!(prefs.hyperfocus_threshold, 10);
+        assert_eq!(prefs.berserker_threshold, 20);
+        assert!(prefs.clamp_pattern_thresholds().is_none());
+
+        let mut prefs: PersonalityPreferences =
+            serde_json::from_str(r#"{"hyperfocus_threshold": 15, "berserker_threshold": 8}"#)
+                .unwrap();
+        assert!(prefs.clamp_pattern_thresholds().is_some());
+        assert_eq!(prefs.hyperfocus_threshold, 15);
+        assert_eq!(prefs.berserker_threshold, 16);
+    }
+
+    #[test]
+    fn test_momentum_activities_filter_mood() {
+        use crate::state::{MoodState, PersonalityModifier};
+
+        let prefs = PersonalityPreferences {
+            momentum_excluded: HashSet::from([Activity::Reading]),
+            ..Default::default()
+        };
+
+        let mut reading = MoodState::default();
+        for _ in 0..20 {
+            reading.update_with_momentum(false, prefs.builds_momentum(&Activity::Reading));
+        }
+        assert_eq!(reading.momentum, 0);
+        assert!(!matches!(
+            reading.get_personality_modifier(),
+            PersonalityModifier::InTheZone
+        ));
+
+        let mut coding = MoodState::default();
+        for _ in 0..20 {
+            coding.update_with_momentum(false, prefs.builds_momentum(&Activity::Coding));
+        }
+        assert!(matches!(
+            coding.get_personality_modifier(),
+            PersonalityModifier::InTheZone
+        ));
+
+        // Defaults exclude nothing, so every activity builds momentum
+        let defaults = PersonalityPreferences::default();
+        assert!(Activity::all().iter().all(|a| defaults.builds_momentum(a)));
+    }
+
+    #[test]
+    fn test_style_options_use_snake_case_names() {
+        let prefs = PersonalityPreferences {
+            activity_label_style: ActivityLabelStyle::TitleCase,
+            git_status_style: GitStatusStyle::TextLabel,
+            icon_mode: IconMode::NerdFont,
+            ..PersonalityPreferences::default()
+        };
+        let json = serde_json::to_value(&prefs).unwrap();
+        assert_eq!(json["activity_label_style"], "title_case");
+        assert_eq!(json["git_status_style"], "text_label");
+        assert_eq!(json["icon_mode"], "nerd_font");
+        assert_eq!(json["display"]["separator_style"], "plain");
+
+        // The PascalCase names earlier configs were saved with still load
+        let prefs: PersonalityPreferences = serde_json::from_str(
+            r#"{ "activity_label_style": "Verbose", "model_name_style": "Short",
+                 "model_bracket_style": "Square", "icon_mode": "Ascii",
+                 "display": { "separator_style": "Powerline" } }"#,
+        )
+        .unwrap();
+        assert_eq!(prefs.activity_label_style, ActivityLabelStyle::Verbose);
+        assert_eq!(prefs.model_name_style, ModelNameStyle::Short);
+        assert_eq!(prefs.model_bracket_style, ModelBracketStyle::Square);
+        assert_eq!(prefs.icon_mode, IconMode::Ascii);
+        assert_eq!(prefs.display.separator_style, SeparatorStyle::Powerline);
+    }
+
+    #[test]
+    fn test_reset_group_only_touches_that_group() {
+        let mut prefs = PersonalityPreferences {
+            theme: Theme::Nord,
+            show_git_status: false,
+            show_git_icon: false,
+            git_branch_suffix: String::new(),
+            show_model: false,
+            ..Default::default()
+        };
+
+        prefs.reset_group(PreferenceGroup::Git);
+        assert!(prefs.show_git_status);
+        assert!(prefs.show_git_icon);
+        assert_eq!(prefs.git_branch_suffix, "branch");
+        assert_eq!(prefs.theme, Theme::Nord);
+        assert!(!prefs.show_model);
+
+        prefs.reset_group(PreferenceGroup::Theme);
+        assert_eq!(prefs.theme, Theme::Default);
+    }
+
+    #[test]
+    fn test_project_config_overrides_global() {
+        let global = PersonalityPreferences {
+            show_model: false,
+            git_branch_suffix: "br".to_string(),
+            display: DisplayConfig {
+                separator_char: "|".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let project = r#"
+            show_model = true
+            hyperfocus_threshold = 4
+
+            [display]
+            show_debug_info = true
+        "#;
+        let merged = global.with_project_overrides(project).unwrap();
+
+        // Keys set by the project win
+        assert!(merged.show_model);
+        assert_eq!(merged.hyperfocus_threshold, 4);
+        assert!(merged.display.show_debug_info);
+        // Everything else is inherited from the global config, including nested keys
+        assert_eq!(merged.git_branch_suffix, "br");
+        assert_eq!(merged.display.separator_char, "|");
+        assert_eq!(merged.berserker_threshold, global.berserker_threshold);
+    }
+
+    #[test]
+    fn test_invalid_project_config_is_rejected() {
+        let global = PersonalityPreferences::default();
+        assert!(global.with_project_overrides("show_model = ").is_err());
+        assert!(
+            global
+                .with_project_overrides("show_model = \"yes\"")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_non_default_fields() {
+        assert_eq!(
+            PersonalityPreferences::default()
+                .non_default_fields()
+                .unwrap(),
+            serde_json::json!({})
+        );
+
+        let prefs = PersonalityPreferences {
+            theme: Theme::Dracula,
+            show_git: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            prefs.non_default_fields().unwrap(),
+            serde_json::json!({ "theme": "Dracula", "show_git": false })
+        );
+
+        // Nested settings keep only their changed keys
+        let mut prefs = PersonalityPreferences::default();
+        prefs.display.separator_char = "|".to_string();
+        assert_eq!(
+            prefs.non_default_fields().unwrap(),
+            serde_json::json!({ "display": { "separator_char": "|" } })
+        );
+    }
+
+    #[test]
+    fn test_parse_section_order() {
+        let order =
+            StatuslineSection::parse_order("personality, git,activity,model", false).unwrap();
+        assert_eq!(
+            &order[..4],
+            &[
+                StatuslineSection::Personality,
+                StatuslineSection::Git,
+                StatuslineSection::Activity,
+                StatuslineSection::Model,
+            ]
+        );
+        // Unlisted sections follow in default order
+        assert_eq!(order.len(), StatuslineSection::default_order().len());
+        assert_eq!(order[4], StatuslineSection::Directory);
+
+        let exact = StatuslineSection::parse_order("model,activity-trail", true).unwrap();
+        assert_eq!(
+            exact,
+            vec![StatuslineSection::Model, StatuslineSection::ActivityTrail]
+        );
+    }
+
+    #[test]
+    fn test_parse_section_order_rejects_duplicates() {
+        let err = StatuslineSection::parse_order("git,model,git", false).unwrap_err();
+        assert!(err.contains("more than once"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_section_order_rejects_unknown_names() {
+        let err = StatuslineSection::parse_order("git,weather", false).unwrap_err();
+        assert!(err.contains("weather"), "{err}");
+        assert!(err.contains("activity_trail"), "{err}");
+    }
+
+    #[test]
+    fn test_preference_group_parsing() {
+        assert_eq!("git".parse::<PreferenceGroup>(), Ok(PreferenceGroup::Git));
+        assert_eq!(
+            "Display".parse::<PreferenceGroup>(),
+            Ok(PreferenceGroup::Display)
+        );
+
+        let err = "colors".parse::<PreferenceGroup>().unwrap_err();
+        assert!(err.contains("colors"));
+        for group in PreferenceGroup::all() {
+            assert!(err.contains(group.name()));
+        }
+    }
 }
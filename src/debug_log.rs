@@ -0,0 +1,113 @@
+//! Opt-in debug log for diagnosing hooks in real sessions
+//!
+//! Claude Code swallows stderr from hooks and the statusline, so `--verbose` is
+//! no help there. When `CCP_DEBUG_LOG` names a file, every hook and statusline
+//! invocation appends one JSON record per line to it. Logging is best effort and
+//! never fails the caller.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the debug log file
+pub const DEBUG_LOG_VAR: &str = "CCP_DEBUG_LOG";
+
+/// One logged invocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebugRecord {
+    /// Local time in RFC 3339 format
+    pub timestamp: String,
+    /// Hook type (e.g. `post-tool`) or `statusline`
+    pub event: String,
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub personality: Option<String>,
+}
+
+impl DebugRecord {
+    /// Create a record for `event` in `session_id`, timestamped now
+    pub fn new(event: &str, session_id: &str) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            event: event.to_string(),
+            session_id: session_id.to_string(),
+            tool: None,
+            activity: None,
+            personality: None,
+        }
+    }
+}
+
+/// Append `record` to the file named by `CCP_DEBUG_LOG`, if set.
+///
+/// Does nothing when the variable is unset or empty; write failures are ignored.
+pub fn log(record: &DebugRecord) {
+    if let Some(path) = log_path(std::env::var_os(DEBUG_LOG_VAR)) {
+        let _ = append_record(&path, record);
+    }
+}
+
+/// The log file named by a `CCP_DEBUG_LOG` value, treating empty as unset
+fn log_path(value: Option<OsString>) -> Option<PathBuf> {
+    value.filter(|path| !path.is_empty()).map(PathBuf::from)
+}
+
+/// Append `record` as a single JSON line to `path`, creating the file if needed
+fn append_record(path: &Path, record: &DebugRecord) -> std::io::Result<()> {
+    let line = serde_json::to_string(record)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("debug.log");
+
+        let record = DebugRecord {
+            tool: Some("Edit".to_string()),
+            activity: Some("Editing".to_string()),
+            personality: Some("ʕ•ᴥ•ʔ Code Wizard".to_string()),
+            ..DebugRecord::new("post-tool", "abc123")
+        };
+        append_record(&path, &record).unwrap();
+        append_record(&path, &DebugRecord::new("statusline", "abc123")).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<DebugRecord> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], record);
+        assert_eq!(records[1].event, "statusline");
+        assert!(chrono::DateTime::parse_from_rfc3339(&records[1].timestamp).is_ok());
+    }
+
+    #[test]
+    fn test_log_path_follows_the_variable() {
+        assert_eq!(
+            log_path(Some(OsString::from("/tmp/ccp.log"))),
+            Some(PathBuf::from("/tmp/ccp.log"))
+        );
+        assert_eq!(log_path(Some(OsString::new())), None);
+        assert_eq!(log_path(None), None);
+    }
+
+    #[test]
+    fn test_unwritable_log_is_an_error_not_a_panic() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory can't be opened for appending
+        assert!(append_record(dir.path(), &DebugRecord::new("statusline", "abc")).is_err());
+    }
+}
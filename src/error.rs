@@ -119,8 +119,11 @@ impl fmt::Display for PersonalityError {
                 } else {
                     writeln!(
                         f,
-                        "\n{} Try removing /tmp/claude_code_personalities_activity_*.json files",
-                        ICON_LIGHTBULB.yellow()
+                        "\n{} Try removing {} files",
+                        ICON_LIGHTBULB.yellow(),
+                        crate::platform::state_dir()
+                            .join("claude_code_personalities_activity_*.json")
+                            .display()
                     )?;
                 }
             }
@@ -50,3 +50,15 @@ pub const ICON_DOCUMENTING: &str = "\u{f02d}"; //
 
 /// Deploying applications - Cloud icon
 pub const ICON_DEPLOYING: &str = "\u{f0c2}"; //
+
+/// Running linters and formatters - Check circle icon
+pub const ICON_QUALITY_CHECKING: &str = "\u{f058}"; // 
+
+/// Fetching web pages - Globe icon
+pub const ICON_BROWSING: &str = "\u{f0ac}"; // 
+
+/// Downloading - Cloud download icon
+pub const ICON_FETCHING: &str = "\u{f0ed}"; // 
+
+/// Planning work - Clipboard icon
+pub const ICON_PLANNING: &str = "\u{f0ea}"; // 
@@ -3,6 +3,9 @@
 //! This module provides a single source of truth for all Nerd Font icons used
 //! throughout the application, organized by category and purpose.
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
 use crate::types::Activity;
 
 // Re-export all icon categories
@@ -17,27 +20,213 @@ pub use models::*;
 pub use status::*;
 pub use ui::*;
 
-/// Get the appropriate icon for an activity (only for Executing, Reading, and Idle)
+/// How icons are drawn, for terminals without a Nerd Font installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IconMode {
+    /// Nerd Font glyphs (requires a patched font)
+    #[default]
+    #[serde(alias = "NerdFont")]
+    NerdFont,
+    /// Color emoji from the system emoji font
+    #[serde(alias = "Emoji")]
+    Emoji,
+    /// Plain Unicode symbols that most fonts can draw
+    #[serde(alias = "Unicode")]
+    Unicode,
+    /// Short bracketed ASCII labels such as `[read]`
+    #[serde(alias = "Ascii")]
+    Ascii,
+    /// No icons at all
+    #[serde(alias = "None")]
+    None,
+}
+
+impl IconMode {
+    /// Pick the form of an icon that matches this mode
+    fn pick(self, nerd_font: &'static str, fallbacks: Fallbacks) -> &'static str {
+        let (emoji, unicode, ascii) = fallbacks;
+        match self {
+            IconMode::NerdFont => nerd_font,
+            IconMode::Emoji => emoji,
+            IconMode::Unicode => unicode,
+            IconMode::Ascii => ascii,
+            IconMode::None => "",
+        }
+    }
+}
+
+/// Emoji, Unicode and ASCII stand-ins for a Nerd Font icon
+type Fallbacks = (&'static str, &'static str, &'static str);
+
+/// Emoji, Unicode and ASCII stand-ins for an activity's Nerd Font icon
+fn activity_fallbacks(activity: &Activity) -> Fallbacks {
+    match activity {
+        Activity::Editing => ("\u{270f}\u{fe0f}", "\u{270e}", "[edit]"),
+        Activity::Coding => ("\u{1f4bb}", "\u{2039}\u{203a}", "[code]"),
+        Activity::Configuring => ("\u{2699}\u{fe0f}", "\u{2261}", "[config]"),
+        Activity::Navigating => ("\u{1f4c1}", "\u{2302}", "[dir]"),
+        Activity::Writing => ("\u{1f4dd}", "\u{00b6}", "[write]"),
+        Activity::Executing => ("\u{1f3c3}", "\u{00bb}", "[run]"),
+        Activity::Reading => ("\u{1f4d6}", "\u{25ce}", "[read]"),
+        Activity::Searching => ("\u{1f50d}", "\u{2315}", "[search]"),
+        Activity::Debugging => ("\u{1f41b}", "\u{2717}", "[debug]"),
+        Activity::Testing => ("\u{1f9ea}", "\u{2713}", "[test]"),
+        Activity::Reviewing => ("\u{1f440}", "\u{25c9}", "[review]"),
+        Activity::Thinking => ("\u{1f4ad}", "\u{2026}", "[think]"),
+        Activity::Building => ("\u{1f528}", "\u{25b2}", "[build]"),
+        Activity::Installing => ("\u{1f4e6}", "\u{2193}", "[install]"),
+        Activity::Idle => ("\u{1f4a4}", "\u{25cc}", "[idle]"),
+        Activity::Working => ("\u{1f6e0}\u{fe0f}", "\u{25cf}", "[work]"),
+        Activity::Refactoring => ("\u{267b}\u{fe0f}", "\u{27f2}", "[refactor]"),
+        Activity::Documenting => ("\u{1f4da}", "\u{00a7}", "[docs]"),
+        Activity::Deploying => ("\u{1f680}", "\u{21e1}", "[deploy]"),
+        Activity::Committing => ("\u{1f4be}", "\u{2387}", "[commit]"),
+        Activity::QualityChecking => ("\u{2705}", "\u{25c6}", "[lint]"),
+        Activity::Browsing => ("\u{1f310}", "\u{2641}", "[web]"),
+        Activity::Fetching => ("\u{1f4e5}", "\u{21e3}", "[fetch]"),
+        Activity::Planning => ("\u{1f4cb}", "\u{2630}", "[plan]"),
+    }
+}
+
+/// Emoji, Unicode and ASCII stand-ins for the UI icons shown in the statusline
+const UI_ICON_FALLBACKS: &[(&str, Fallbacks)] = &[
+    (ICON_FOLDER, ("\u{1f4c2}", "\u{2302}", "[dir]")),
+    (ICON_GIT_BRANCH, ("\u{1f33f}", "\u{2387}", "[git]")),
+    (ICON_BOLT, ("\u{26a1}", "\u{03df}", "[actions]")),
+    (ICON_UPDATE, ("\u{2b06}\u{fe0f}", "\u{2191}", "[update]")),
+    (ICON_CLOCK, ("\u{1f552}", "\u{25f7}", "[time]")),
+    (ICON_CALENDAR_CHECK, ("\u{1f4c5}", "\u{2605}", "[days]")),
+    (ICON_WARNING, ("\u{26a0}\u{fe0f}", "\u{26a0}", "!")),
+];
+
+/// Get the appropriate icon for an activity (only for Executing, Reading, Browsing, Fetching,
+/// Planning, and Idle)
 pub fn get_activity_icon(activity: &Activity) -> &'static str {
     match activity {
         Activity::Executing => ICON_EXECUTING,
         Activity::Reading => ICON_READING,
+        Activity::Browsing => ICON_BROWSING,
+        Activity::Fetching => ICON_FETCHING,
+        Activity::Planning => ICON_PLANNING,
         Activity::Idle => ICON_IDLE,
         // All other activities show no icon to reduce visual clutter
         _ => "",
     }
 }
 
+/// Get an icon for every activity, used where activities are shown as icons only
+pub fn get_activity_trail_icon(activity: &Activity) -> &'static str {
+    match activity {
+        Activity::Editing => ICON_EDITING,
+        Activity::Coding => ICON_CODE,
+        Activity::Configuring => ICON_GEAR,
+        Activity::Navigating => ICON_FOLDER,
+        Activity::Writing => ICON_WRITING,
+        Activity::Executing => ICON_EXECUTING,
+        Activity::Reading => ICON_READING,
+        Activity::Searching => ICON_SEARCHING,
+        Activity::Debugging => ICON_DEBUGGING,
+        Activity::Testing => ICON_TESTING,
+        Activity::Reviewing => ICON_REVIEWING,
+        Activity::Thinking => ICON_THINKING,
+        Activity::Building => ICON_BUILDING,
+        Activity::Installing => ICON_INSTALLING,
+        Activity::Idle => ICON_IDLE,
+        Activity::Working => ICON_WORKING,
+        Activity::Refactoring => ICON_REFACTORING,
+        Activity::Documenting => ICON_DOCUMENTING,
+        Activity::Deploying => ICON_DEPLOYING,
+        Activity::Committing => ICON_GIT_BRANCH,
+        Activity::QualityChecking => ICON_QUALITY_CHECKING,
+        Activity::Browsing => ICON_BROWSING,
+        Activity::Fetching => ICON_FETCHING,
+        Activity::Planning => ICON_PLANNING,
+    }
+}
+
+/// Get the icon for an activity (only for Executing, Reading, Browsing, Fetching, Planning,
+/// and Idle) in `mode`
+pub fn get_activity_icon_for_mode(activity: &Activity, mode: IconMode) -> &'static str {
+    let icon = get_activity_icon(activity);
+    if icon.is_empty() {
+        return "";
+    }
+    mode.pick(icon, activity_fallbacks(activity))
+}
+
+/// Get the trail icon for an activity in `mode`
+pub fn get_activity_trail_icon_for_mode(activity: &Activity, mode: IconMode) -> &'static str {
+    mode.pick(
+        get_activity_trail_icon(activity),
+        activity_fallbacks(activity),
+    )
+}
+
+/// Get the icon for an activity, preferring a user override when one is configured.
+/// An empty override suppresses the icon entirely. Overrides are shown as-is in
+/// every mode except `IconMode::None`, which hides all icons.
+pub fn resolve_activity_icon<'a>(
+    activity: &Activity,
+    overrides: &'a HashMap<Activity, String>,
+    mode: IconMode,
+) -> &'a str {
+    if mode == IconMode::None {
+        return "";
+    }
+    overrides.get(activity).map_or_else(
+        || get_activity_icon_for_mode(activity, mode),
+        String::as_str,
+    )
+}
+
+/// Get a UI icon (folder, git branch, action counter, update arrow) in `mode`.
+/// Icons without a known stand-in, such as user-configured ones, pass through.
+pub fn get_ui_icon(icon: &str, mode: IconMode) -> &str {
+    match UI_ICON_FALLBACKS
+        .iter()
+        .find(|(nerd_font, _)| *nerd_font == icon)
+    {
+        Some(&(nerd_font, fallbacks)) => mode.pick(nerd_font, fallbacks),
+        None if mode == IconMode::None => "",
+        None => icon,
+    }
+}
+
 /// Get the appropriate icon for a model
 pub fn get_model_icon(model_name: &str) -> &'static str {
-    match model_name.to_lowercase().as_str() {
-        name if name.contains("opus") => ICON_OPUS,
-        name if name.contains("sonnet") => ICON_SONNET,
-        name if name.contains("haiku") => ICON_HAIKU,
+    match model_family(model_name) {
+        Some("Opus") => ICON_OPUS,
+        Some("Sonnet") => ICON_SONNET,
+        Some("Haiku") => ICON_HAIKU,
         _ => ICON_CLAUDE_DEFAULT,
     }
 }
 
+/// Get the icon for a model in `mode`
+pub fn get_model_icon_for_mode(model_name: &str, mode: IconMode) -> &'static str {
+    let fallbacks = match model_family(model_name) {
+        Some("Opus") => ("\u{1f3ad}", "\u{25c6}", "[opus]"),
+        Some("Sonnet") => ("\u{1f4dc}", "\u{25c7}", "[sonnet]"),
+        Some("Haiku") => ("\u{1f343}", "\u{25cb}", "[haiku]"),
+        _ => ("\u{1f916}", "\u{25e6}", "[claude]"),
+    };
+    mode.pick(get_model_icon(model_name), fallbacks)
+}
+
+/// Model family (`Opus`, `Sonnet`, or `Haiku`) named in a model's display name.
+///
+/// Matches anywhere in the name, so dated (`claude-opus-4-20250101`) and compact
+/// (`opus4.5`) forms work. When several families appear, the last one wins.
+pub fn model_family(model_name: &str) -> Option<&'static str> {
+    let name = model_name.to_lowercase();
+    [("opus", "Opus"), ("sonnet", "Sonnet"), ("haiku", "Haiku")]
+        .into_iter()
+        .filter_map(|(keyword, family)| name.rfind(keyword).map(|position| (position, family)))
+        .max_by_key(|(position, _)| *position)
+        .map(|(_, family)| family)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,6 +237,9 @@ mod tests {
         assert_eq!(get_activity_icon(&Activity::Executing), ICON_EXECUTING);
         assert_eq!(get_activity_icon(&Activity::Reading), ICON_READING);
         assert_eq!(get_activity_icon(&Activity::Idle), ICON_IDLE);
+        assert_eq!(get_activity_icon(&Activity::Browsing), ICON_BROWSING);
+        assert_eq!(get_activity_icon(&Activity::Planning), ICON_PLANNING);
+        assert_eq!(get_activity_icon(&Activity::Fetching), ICON_FETCHING);
 
         // Activities that should have no icon (empty string)
         assert_eq!(get_activity_icon(&Activity::Editing), "");
@@ -66,6 +258,110 @@ mod tests {
         assert_eq!(get_activity_icon(&Activity::Refactoring), "");
         assert_eq!(get_activity_icon(&Activity::Documenting), "");
         assert_eq!(get_activity_icon(&Activity::Deploying), "");
+        assert_eq!(get_activity_icon(&Activity::QualityChecking), "");
+    }
+
+    #[test]
+    fn test_resolve_activity_icon_overrides() {
+        let mut overrides = HashMap::new();
+        overrides.insert(Activity::Testing, "\u{f0c3}".to_string());
+        overrides.insert(Activity::Executing, String::new());
+
+        assert_eq!(
+            resolve_activity_icon(&Activity::Testing, &overrides, IconMode::NerdFont),
+            "\u{f0c3}"
+        );
+        assert_eq!(
+            resolve_activity_icon(&Activity::Executing, &overrides, IconMode::NerdFont),
+            ""
+        );
+        // Activities without an override keep their default
+        assert_eq!(
+            resolve_activity_icon(&Activity::Reading, &overrides, IconMode::NerdFont),
+            ICON_READING
+        );
+        // Overrides survive fallback modes but not `None`
+        assert_eq!(
+            resolve_activity_icon(&Activity::Testing, &overrides, IconMode::Ascii),
+            "\u{f0c3}"
+        );
+        assert_eq!(
+            resolve_activity_icon(&Activity::Testing, &overrides, IconMode::None),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_reading_icon_in_every_mode() {
+        let reading = &Activity::Reading;
+        assert_eq!(
+            get_activity_icon_for_mode(reading, IconMode::NerdFont),
+            ICON_READING
+        );
+        assert_eq!(
+            get_activity_icon_for_mode(reading, IconMode::Emoji),
+            "\u{1f4d6}"
+        );
+        assert_eq!(
+            get_activity_icon_for_mode(reading, IconMode::Unicode),
+            "\u{25ce}"
+        );
+        assert_eq!(
+            get_activity_icon_for_mode(reading, IconMode::Ascii),
+            "[read]"
+        );
+        assert_eq!(get_activity_icon_for_mode(reading, IconMode::None), "");
+
+        // Activities without a default icon stay empty in every mode
+        assert_eq!(
+            get_activity_icon_for_mode(&Activity::Editing, IconMode::Ascii),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_fallback_icons_are_not_nerd_font_glyphs() {
+        // Nerd Font glyphs live in the Private Use Areas
+        let is_private_use = |c: char| matches!(c as u32, 0xe000..=0xf8ff | 0xf0000..);
+        let activities = [
+            Activity::Editing,
+            Activity::Reading,
+            Activity::Committing,
+            Activity::QualityChecking,
+        ];
+        for mode in [IconMode::Emoji, IconMode::Unicode, IconMode::Ascii] {
+            let mut icons: Vec<&str> = activities
+                .iter()
+                .map(|activity| get_activity_trail_icon_for_mode(activity, mode))
+                .collect();
+            icons.push(get_model_icon_for_mode("Opus", mode));
+            icons.push(get_ui_icon(ICON_FOLDER, mode));
+            icons.push(get_ui_icon(ICON_GIT_BRANCH, mode));
+            for icon in icons {
+                assert!(!icon.is_empty());
+                assert!(!icon.chars().any(is_private_use), "{mode:?}: {icon}");
+                if mode == IconMode::Ascii {
+                    assert!(icon.is_ascii(), "{icon}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ui_icon_passes_custom_icons_through() {
+        assert_eq!(get_ui_icon(ICON_UPDATE, IconMode::Ascii), "[update]");
+        assert_eq!(get_ui_icon("^", IconMode::Ascii), "^");
+        assert_eq!(get_ui_icon("^", IconMode::None), "");
+    }
+
+    #[test]
+    fn test_icon_mode_names() {
+        let mode: IconMode = serde_json::from_str("\"Emoji\"").unwrap();
+        assert_eq!(mode, IconMode::Emoji);
+        let mode: IconMode = serde_json::from_str("\"Unicode\"").unwrap();
+        assert_eq!(mode, IconMode::Unicode);
+        let mode: IconMode = serde_json::from_str("\"Ascii\"").unwrap();
+        assert_eq!(mode, IconMode::Ascii);
     }
 
     #[test]
@@ -85,4 +381,22 @@ mod tests {
         assert_eq!(get_model_icon("Unknown"), ICON_CLAUDE_DEFAULT);
         assert_eq!(get_model_icon("GPT-4"), ICON_CLAUDE_DEFAULT);
     }
+
+    #[test]
+    fn test_model_family_formats() {
+        for (name, family) in [
+            ("Claude 3.5 Sonnet", Some("Sonnet")),
+            ("claude-opus-4-20250101", Some("Opus")),
+            ("claude-4-opus-thinking", Some("Opus")),
+            ("opus4.5", Some("Opus")),
+            ("Sonnet4", Some("Sonnet")),
+            ("claude-haiku-4-5", Some("Haiku")),
+            ("Opus 4.1 (sonnet fallback)", Some("Sonnet")),
+            ("sonnet-distilled-haiku", Some("Haiku")),
+            ("Claude", None),
+            ("", None),
+        ] {
+            assert_eq!(model_family(name), family, "{name}");
+        }
+    }
 }
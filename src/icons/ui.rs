@@ -14,3 +14,15 @@ pub const ICON_GEAR: &str = "\u{f013}"; // ⚙
 
 /// Git branch icon
 pub const ICON_GIT_BRANCH: &str = "\u{e725}"; //
+
+/// Lightning bolt icon for the session action counter
+pub const ICON_BOLT: &str = "\u{f0e7}"; //
+
+/// Arrow icon shown before the update notice
+pub const ICON_UPDATE: &str = "\u{f062}"; //
+
+/// Clock icon for the time-of-day section
+pub const ICON_CLOCK: &str = "\u{f017}";
+
+/// Calendar icon for the day streak section
+pub const ICON_CALENDAR_CHECK: &str = "\u{f274}";
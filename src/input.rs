@@ -0,0 +1,140 @@
+//! Bounded reading of the JSON Claude Code sends on stdin
+//!
+//! The statusline and hooks read their whole input before parsing it. A
+//! misbehaving integration could send an endless or enormous payload, so input
+//! is capped (1 MiB unless `CCP_MAX_INPUT_BYTES` says otherwise) and anything
+//! larger is rejected instead of being buffered. Text from tool input that ends
+//! up in the statusline goes through [`strip_control_chars`] first, so escape
+//! sequences in a filename or command can't recolor or corrupt the line.
+
+use anyhow::{Result, bail};
+use std::io::Read;
+
+/// Environment variable overriding the input size limit in bytes
+pub const MAX_INPUT_VAR: &str = "CCP_MAX_INPUT_BYTES";
+
+/// Input size limit when `CCP_MAX_INPUT_BYTES` is unset or invalid
+pub const DEFAULT_MAX_INPUT_BYTES: u64 = 1024 * 1024;
+
+/// The input size limit, from `CCP_MAX_INPUT_BYTES` or the default
+#[must_use]
+pub fn max_input_bytes() -> u64 {
+    std::env::var(MAX_INPUT_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|&limit| limit > 0)
+        .unwrap_or(DEFAULT_MAX_INPUT_BYTES)
+}
+
+/// Read all of stdin as UTF-8, failing once it exceeds the input size limit.
+///
+/// # Errors
+///
+/// Returns an error if stdin can't be read, is larger than the limit, or isn't UTF-8.
+pub fn read_stdin() -> Result<String> {
+    read_limited(std::io::stdin().lock(), max_input_bytes())
+}
+
+/// Remove terminal escape sequences and control characters from `text`.
+///
+/// CSI (`ESC [ ... m`) and OSC (`ESC ] ... BEL`) sequences are dropped whole,
+/// line breaks and tabs become spaces, and any other control character is removed.
+#[must_use]
+pub fn strip_control_chars(text: &str) -> String {
+    let mut clean = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.peek() {
+                // CSI: parameters and intermediates up to a final byte in @..=~
+                Some('[') => {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or the ST terminator (ESC \)
+                Some(']') => {
+                    chars.next();
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\n' | '\r' | '\t' => clean.push(' '),
+            c if c.is_control() => {}
+            c => clean.push(c),
+        }
+    }
+    clean
+}
+
+/// Read `reader` to the end as UTF-8, failing once more than `limit` bytes arrive
+fn read_limited<R: Read>(reader: R, limit: u64) -> Result<String> {
+    let mut bytes = Vec::new();
+    // One byte past the limit is enough to tell an oversized input apart
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        bail!("Input is larger than the {limit} byte limit (set {MAX_INPUT_VAR} to raise it)");
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_within_limit_is_read() {
+        let input = r#"{"session_id":"abc"}"#;
+        assert_eq!(read_limited(input.as_bytes(), 64).unwrap(), input);
+        assert_eq!(
+            read_limited(input.as_bytes(), input.len() as u64).unwrap(),
+            input
+        );
+    }
+
+    #[test]
+    fn test_oversized_input_is_rejected() {
+        let input = "x".repeat(2048);
+        let error = read_limited(input.as_bytes(), 1024).unwrap_err();
+        assert!(error.to_string().contains("1024 byte limit"), "{error}");
+
+        // An endless stream stops at the limit instead of growing forever
+        let error = read_limited(std::io::repeat(b'{'), 1024).unwrap_err();
+        assert!(error.to_string().contains("byte limit"), "{error}");
+    }
+
+    #[test]
+    fn test_strip_control_chars() {
+        assert_eq!(strip_control_chars("src/main.rs"), "src/main.rs");
+        assert_eq!(strip_control_chars("\u{1b}[31mred\u{1b}[0m.rs"), "red.rs");
+        assert_eq!(strip_control_chars("a\u{1b}[1;38;5;196mb"), "ab");
+        assert_eq!(
+            strip_control_chars("\u{1b}]0;title\u{7}x \u{1b}]8;;http://x\u{1b}\\y"),
+            "x y"
+        );
+        assert_eq!(
+            strip_control_chars("make\ttest\nnow\u{7}\u{0}"),
+            "make test now"
+        );
+        // A C1 CSI and a lone ESC are dropped too
+        assert_eq!(strip_control_chars("a\u{9b}b\u{1b}"), "ab");
+        assert_eq!(
+            strip_control_chars("caf\u{e9} (\u{256f}\u{b0}\u{25a1}\u{b0})"),
+            "caf\u{e9} (\u{256f}\u{b0}\u{25a1}\u{b0})"
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_an_error() {
+        assert!(read_limited(&[0xff, 0xfe][..], 64).is_err());
+    }
+}
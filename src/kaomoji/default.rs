@@ -8,9 +8,23 @@ use super::Kaomoji;
 // Initial state
 pub const BOOTING_UP: Kaomoji = Kaomoji::new("( ˘ ³˘)", "Chillin");
 
+// Shown when the session has been idle for a while
+pub const RESTING: Kaomoji = Kaomoji::new("(－_－) zzZ", "Resting");
+
 // Basic tool operations
 pub const CODE_WIZARD: Kaomoji = Kaomoji::new("ლ(╹◡╹ლ)", "Cowder");
 pub const CODE_WIZARD_ALT: Kaomoji = Kaomoji::new("ლ(╹◡╹ლ)", "Cowder");
 pub const GENTLE_REFACTORER: Kaomoji = Kaomoji::new("(• ε •)", "Gentle Refactorer");
 pub const CODE_JANITOR: Kaomoji = Kaomoji::new("(ง'̀-'́)ง", "Code Janitor");
 pub const CASUAL_CODE_REVIEWER: Kaomoji = Kaomoji::new("¯\\_(ツ)_/¯", "Casual Code Reviewer");
+
+/// Every default face, listed for `personalities list`
+pub const DEFAULT_KAOMOJI: &[&Kaomoji] = &[
+    &BOOTING_UP,
+    &RESTING,
+    &CODE_WIZARD,
+    &CODE_WIZARD_ALT,
+    &GENTLE_REFACTORER,
+    &CODE_JANITOR,
+    &CASUAL_CODE_REVIEWER,
+];
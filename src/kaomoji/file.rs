@@ -19,6 +19,24 @@ pub const MARKUP_WIZARD: Kaomoji = Kaomoji::new("<(￣︶￣)>", "Markup Wizard"
 
 // Programming Languages
 pub const JS_MASTER: Kaomoji = Kaomoji::new("(▀̿Ĺ̯▀̿ ̿)", "JS Master");
+pub const BORROW_CHECKER_WHISPERER: Kaomoji = Kaomoji::new("(ง •̀_•́)ง", "Borrow Checker Whisperer");
+pub const GOPHER: Kaomoji = Kaomoji::new("ʕ◔ϖ◔ʔ", "Gopher");
+pub const SNAKE_CHARMER: Kaomoji = Kaomoji::new("∼(ᵔ◡ᵔ)∼", "Snake Charmer");
 
 // Configuration and Settings
 pub const CONFIG_HELPER: Kaomoji = Kaomoji::new("(๑>؂•̀๑)", "Config Helper");
+
+/// Every file-type face, listed for `personalities list`
+pub const FILE_KAOMOJI: &[&Kaomoji] = &[
+    &SECURITY_ANALYST,
+    &PERFORMANCE_TUNER,
+    &DOCUMENTATION_WRITER,
+    &UI_DEVELOPER,
+    &STYLE_ARTIST,
+    &MARKUP_WIZARD,
+    &JS_MASTER,
+    &BORROW_CHECKER_WHISPERER,
+    &GOPHER,
+    &SNAKE_CHARMER,
+    &CONFIG_HELPER,
+];
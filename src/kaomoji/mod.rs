@@ -5,12 +5,14 @@
 
 use std::fmt;
 
+use crate::hooks;
 use crate::state::PersonalityModifier;
 
 // Re-export all kaomoji categories
 pub mod default;
 pub mod file;
 pub mod mood;
+pub mod packs;
 pub mod time;
 pub mod tool;
 
@@ -18,6 +20,7 @@ pub mod tool;
 pub use default::*;
 pub use file::*;
 pub use mood::*;
+pub use packs::{KaomojiPack, PACKS, find_pack};
 pub use time::*;
 pub use tool::*;
 
@@ -46,6 +49,15 @@ impl fmt::Display for Kaomoji {
     }
 }
 
+/// Every built-in kaomoji, grouped by category name
+pub const CATEGORIES: &[(&str, &[&Kaomoji])] = &[
+    ("Default", DEFAULT_KAOMOJI),
+    ("Mood", MOOD_KAOMOJI),
+    ("File", FILE_KAOMOJI),
+    ("Tool", TOOL_KAOMOJI),
+    ("Time", TIME_KAOMOJI),
+];
+
 /// Get mood-based kaomoji based on personality modifier and frustration level
 pub fn get_mood_kaomoji(modifier: &PersonalityModifier, frustration_level: u8) -> &'static Kaomoji {
     match modifier {
@@ -66,13 +78,22 @@ pub fn get_tool_kaomoji(tool_name: &str, command: Option<&str>) -> Option<&'stat
     match tool_name {
         "Bash" => get_bash_kaomoji(command?),
         "Grep" => Some(&BUG_HUNTER),
+        "WebFetch" => Some(&WEB_SURFER),
+        "TodoWrite" => Some(&TASK_ORCHESTRATOR),
+        // Multi-file edits are refactors, whatever the file type
+        "MultiEdit" => Some(&REFACTOR_SURGEON),
         _ => None,
     }
 }
 
 /// Get bash command-specific kaomoji
 pub fn get_bash_kaomoji(command: &str) -> Option<&'static Kaomoji> {
-    // Git operations (highest priority)
+    // Downloads, including `git clone`, before other git operations
+    if hooks::is_fetch_command(command) {
+        return Some(&NET_RETRIEVER);
+    }
+
+    // Git operations
     if is_git_command(command) {
         return Some(&GIT_MANAGER);
     }
@@ -82,6 +103,16 @@ pub fn get_bash_kaomoji(command: &str) -> Option<&'static Kaomoji> {
         return Some(&TEST_TASKMASTER);
     }
 
+    // Linters and formatters
+    if hooks::quality_tool(command).is_some() {
+        return Some(&QUALITY_AUDITOR);
+    }
+
+    // Infrastructure dry runs (plan/diff) before the real thing
+    if hooks::is_infra_plan_command(command) {
+        return Some(&INFRA_PLANNER);
+    }
+
     // Deployment/Infrastructure
     if is_deploy_command(command) {
         return Some(&DEPLOYMENT_GUARD);
@@ -212,14 +243,19 @@ pub fn get_file_kaomoji(file_path: &str) -> Option<&'static Kaomoji> {
         return Some(&CONFIG_HELPER);
     }
 
-    None
+    // Language families (lowest priority - only when nothing more specific matched)
+    language_kaomoji(file_path)
 }
 
 /// Get pattern-based kaomoji for consecutive actions
-pub fn get_pattern_kaomoji(consecutive_actions: u32) -> Option<&'static Kaomoji> {
-    if consecutive_actions > 20 {
+pub fn get_pattern_kaomoji(
+    consecutive_actions: u32,
+    hyperfocus_threshold: u32,
+    berserker_threshold: u32,
+) -> Option<&'static Kaomoji> {
+    if consecutive_actions > berserker_threshold {
         Some(&CODE_BERSERKER)
-    } else if consecutive_actions > 10 {
+    } else if consecutive_actions > hyperfocus_threshold {
         Some(&HYPERFOCUSED)
     } else {
         None
@@ -415,6 +451,71 @@ fn is_config_file(file: &str) -> bool {
         || file_lower.ends_with(".toml")
 }
 
+/// Language-family face for a source file, picked by extension
+fn language_kaomoji(file: &str) -> Option<&'static Kaomoji> {
+    const LANGUAGE_GROUPS: &[(&[&str], &Kaomoji)] = &[
+        (&["rs"], &BORROW_CHECKER_WHISPERER),
+        (&["go"], &GOPHER),
+        (&["py", "pyi", "pyw"], &SNAKE_CHARMER),
+    ];
+
+    let extension = std::path::Path::new(file)
+        .extension()?
+        .to_str()?
+        .to_lowercase();
+    LANGUAGE_GROUPS
+        .iter()
+        .find(|(extensions, _)| extensions.contains(&extension.as_str()))
+        .map(|(_, kaomoji)| *kaomoji)
+}
+
 fn is_js_file(file: &str) -> bool {
     file.ends_with(".js") || file.ends_with(".ts") || file.ends_with(".mjs")
 }
+
+#[cfg(test)]
+mod tests {
+    /// Names of the `pub const ...: Kaomoji` faces declared in a source file
+    fn declared_faces(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub const "))
+            .filter_map(|rest| rest.split_once(": Kaomoji "))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Names listed in the `&[&Kaomoji]` registry of a source file
+    fn registered_faces(source: &str) -> Vec<&str> {
+        let start = source.find(": &[&Kaomoji] = &[").expect("registry list");
+        let list = &source[start..];
+        let list = &list[..list.find("];").expect("end of registry list")];
+        list.split('&')
+            .skip(4)
+            .map(|name| name.trim().trim_end_matches(',').trim())
+            .collect()
+    }
+
+    #[test]
+    fn test_every_face_is_registered() {
+        let sources = [
+            ("default.rs", include_str!("default.rs")),
+            ("file.rs", include_str!("file.rs")),
+            ("mood.rs", include_str!("mood.rs")),
+            ("tool.rs", include_str!("tool.rs")),
+            ("time.rs", include_str!("time.rs")),
+        ];
+        for (file, source) in sources {
+            let declared = declared_faces(source);
+            let registered = registered_faces(source);
+            assert!(!declared.is_empty(), "{file}");
+            for name in &declared {
+                assert!(
+                    registered.contains(name),
+                    "{name} in {file} is not registered"
+                );
+            }
+            assert_eq!(declared.len(), registered.len(), "{file}");
+        }
+    }
+}
@@ -11,8 +11,20 @@ pub const FRUSTRATED_HIGH: Kaomoji = Kaomoji::new("(╯°□°)╯︵ ┻━┻"
 /// Mid-level frustration - some errors encountered
 pub const FRUSTRATED_MID: Kaomoji = Kaomoji::new("(ノಠ益ಠ)ノ", "Error Warrior");
 
+/// Shown briefly after recovering from a frustrating error streak
+pub const CELEBRATING: Kaomoji = Kaomoji::new("\\(^o^)/", "Back on Track");
+
 /// Highly focused state - good momentum
 pub const HYPERFOCUSED: Kaomoji = Kaomoji::new("┌༼◉ل͟◉༽┐", "Hyperfocused Coder");
 
 /// Extreme focus state - very high consecutive actions
 pub const CODE_BERSERKER: Kaomoji = Kaomoji::new("【╯°□°】╯︵ ┻━┻", "Code Berserker");
+
+/// Every mood face, listed for `personalities list`
+pub const MOOD_KAOMOJI: &[&Kaomoji] = &[
+    &FRUSTRATED_HIGH,
+    &FRUSTRATED_MID,
+    &CELEBRATING,
+    &HYPERFOCUSED,
+    &CODE_BERSERKER,
+];
@@ -0,0 +1,162 @@
+//! Themed kaomoji packs
+//!
+//! A pack swaps the mood and default tool faces for a themed set. Tool- and
+//! file-specific personalities (Git Manager, JS Master, ...) are left alone so
+//! the statusline still says what Claude is doing.
+
+use super::{
+    BOOTING_UP, CASUAL_CODE_REVIEWER, CODE_BERSERKER, CODE_JANITOR, CODE_WIZARD, FRUSTRATED_HIGH,
+    FRUSTRATED_MID, GENTLE_REFACTORER, HYPERFOCUSED, Kaomoji, RESEARCH_KING, SEARCH_MAESTRO,
+};
+
+/// A named set of replacements for the built-in mood and default faces
+#[derive(Debug)]
+pub struct KaomojiPack {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Built-in kaomoji and the pack's replacement for it
+    faces: &'static [(Kaomoji, Kaomoji)],
+}
+
+impl KaomojiPack {
+    /// The pack's replacement for a built-in kaomoji, or the kaomoji itself
+    pub fn remap(&self, kaomoji: &'static Kaomoji) -> &'static Kaomoji {
+        self.faces
+            .iter()
+            .find(|(original, _)| original == kaomoji)
+            .map_or(kaomoji, |(_, replacement)| replacement)
+    }
+}
+
+pub const CATS: KaomojiPack = KaomojiPack {
+    name: "cats",
+    description: "Feline faces for every mood",
+    faces: &[
+        (
+            FRUSTRATED_HIGH,
+            Kaomoji::new("(=ↀДↀ=)ﾉ︵ ┻━┻", "Table-Flipping Tabby"),
+        ),
+        (FRUSTRATED_MID, Kaomoji::new("(=`ω´=)", "Hissing Kitten")),
+        (HYPERFOCUSED, Kaomoji::new("(=◉ω◉=)", "Laser-Pointer Focus")),
+        (CODE_BERSERKER, Kaomoji::new("ฅ(=ↀωↀ=)ฅ", "Zoomies")),
+        (CODE_WIZARD, Kaomoji::new("(=^･ω･^=)", "Code Kitty")),
+        (GENTLE_REFACTORER, Kaomoji::new("(=^‥^=)", "Tidy Tabby")),
+        (CODE_JANITOR, Kaomoji::new("ฅ^•ﻌ•^ฅ", "Litter Sweeper")),
+        (
+            CASUAL_CODE_REVIEWER,
+            Kaomoji::new("(=ｘェｘ=)", "Judgy Cat"),
+        ),
+        (RESEARCH_KING, Kaomoji::new("(=･ｪ･=?", "Curious Cat")),
+        (SEARCH_MAESTRO, Kaomoji::new("(=ↀωↀ=)", "Mouse Hunter")),
+        (BOOTING_UP, Kaomoji::new("(=-ω-=)", "Catnapper")),
+    ],
+};
+
+pub const BEARS: KaomojiPack = KaomojiPack {
+    name: "bears",
+    description: "Bears from cub to grizzly",
+    faces: &[
+        (
+            FRUSTRATED_HIGH,
+            Kaomoji::new("ʕノ•ᴥ•ʔノ ︵ ┻━┻", "Grizzly Flipper"),
+        ),
+        (FRUSTRATED_MID, Kaomoji::new("ʕ •̀ o •́ ʔ", "Grumpy Bear")),
+        (HYPERFOCUSED, Kaomoji::new("ʕ◉ᴥ◉ʔ", "Focused Bear")),
+        (CODE_BERSERKER, Kaomoji::new("ʕ╬ಠᴥಠʔ", "Bear-serker")),
+        (CODE_WIZARD, Kaomoji::new("ʕ•ᴥ•ʔ", "Code Bear")),
+        (GENTLE_REFACTORER, Kaomoji::new("ʕ￫ᴥ￩ʔ", "Gentle Bear")),
+        (CODE_JANITOR, Kaomoji::new("ʕง•ᴥ•ʔง", "Cleanup Cub")),
+        (
+            CASUAL_CODE_REVIEWER,
+            Kaomoji::new("ʕ ˵• ₒ •˵ ʔ", "Reviewing Bear"),
+        ),
+        (RESEARCH_KING, Kaomoji::new("ʕ •ᴥ•ʔ゛", "Reading Bear")),
+        (SEARCH_MAESTRO, Kaomoji::new("ʕ·ᴥ·　ʔ", "Honey Hunter")),
+        (BOOTING_UP, Kaomoji::new("ʕ -ᴥ-ʔ", "Hibernating")),
+    ],
+};
+
+pub const ROBOTS: KaomojiPack = KaomojiPack {
+    name: "robots",
+    description: "Bracketed bots with blinking eyes",
+    faces: &[
+        (
+            FRUSTRATED_HIGH,
+            Kaomoji::new("[╯°□°]╯︵ ┻━┻", "Malfunction"),
+        ),
+        (FRUSTRATED_MID, Kaomoji::new("[ x_x ]", "Error Loop")),
+        (HYPERFOCUSED, Kaomoji::new("[ ◉_◉ ]", "Overclocked")),
+        (CODE_BERSERKER, Kaomoji::new("d[ o_0 ]b", "Full Throttle")),
+        (CODE_WIZARD, Kaomoji::new("[ ^_^ ]", "Code Bot")),
+        (GENTLE_REFACTORER, Kaomoji::new("[ ･_･ ]", "Refactor Unit")),
+        (CODE_JANITOR, Kaomoji::new("[ •_•]o", "Cleanup Drone")),
+        (CASUAL_CODE_REVIEWER, Kaomoji::new("[ ¬_¬ ]", "Review Bot")),
+        (RESEARCH_KING, Kaomoji::new("[ o_o ]", "Scanner")),
+        (SEARCH_MAESTRO, Kaomoji::new("[ ⊙_⊙ ]", "Deep Scanner")),
+        (BOOTING_UP, Kaomoji::new("[ -_- ]", "Standby")),
+    ],
+};
+
+/// Every built-in pack
+pub const PACKS: &[KaomojiPack] = &[CATS, BEARS, ROBOTS];
+
+/// Look up a pack by name (case-insensitive), listing the valid names on failure
+pub fn find_pack(name: &str) -> Result<&'static KaomojiPack, String> {
+    PACKS
+        .iter()
+        .find(|pack| pack.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| {
+            let valid: Vec<&str> = PACKS.iter().map(|pack| pack.name).collect();
+            format!(
+                "Unknown kaomoji pack: {name} (valid packs: {})",
+                valid.join(", ")
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kaomoji::{CODE_WIZARD_ALT, GIT_MANAGER, get_mood_kaomoji};
+    use crate::state::PersonalityModifier;
+
+    #[test]
+    fn test_find_pack() {
+        assert_eq!(find_pack("cats").unwrap().name, "cats");
+        assert_eq!(find_pack("Robots").unwrap().name, "robots");
+
+        let error = find_pack("dogs").unwrap_err();
+        assert!(error.contains("Unknown kaomoji pack: dogs"));
+        assert!(error.contains("cats, bears, robots"));
+    }
+
+    #[test]
+    fn test_cats_pack_changes_mood_faces() {
+        let cats = find_pack("cats").unwrap();
+        for modifier in [PersonalityModifier::Normal, PersonalityModifier::InTheZone] {
+            let built_in = get_mood_kaomoji(&modifier, 0);
+            assert_ne!(cats.remap(built_in).face, built_in.face, "{modifier:?}");
+        }
+        assert_eq!(cats.remap(&CODE_WIZARD).description, "Code Kitty");
+        assert_eq!(cats.remap(&HYPERFOCUSED).description, "Laser-Pointer Focus");
+        // The Edit default shares the normal face
+        assert_eq!(cats.remap(&CODE_WIZARD_ALT).description, "Code Kitty");
+    }
+
+    #[test]
+    fn test_packs_leave_tool_faces_alone() {
+        for pack in PACKS {
+            assert_eq!(pack.remap(&GIT_MANAGER), &GIT_MANAGER);
+        }
+    }
+
+    #[test]
+    fn test_packs_cover_the_same_faces() {
+        for pack in PACKS {
+            let originals: Vec<&Kaomoji> =
+                pack.faces.iter().map(|(original, _)| original).collect();
+            let cats: Vec<&Kaomoji> = CATS.faces.iter().map(|(original, _)| original).collect();
+            assert_eq!(originals, cats, "{}", pack.name);
+        }
+    }
+}
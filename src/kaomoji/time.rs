@@ -10,10 +10,8 @@ pub const CAFFEINATED: Kaomoji = Kaomoji::new("( -_-)旦~", "Caffeinated");
 // TGIFFFFF (Friday > 5 PM)
 pub const TGIFFFFF: Kaomoji = Kaomoji::new("ヽ(⌐■_■)ノ♪♬", "TGIFFFFF");
 
-/// Get time-based kaomoji if applicable
-pub fn get_time_kaomoji() -> Option<&'static Kaomoji> {
-    get_time_kaomoji_for(Local::now())
-}
+/// Every time-of-day face, listed for `personalities list`
+pub const TIME_KAOMOJI: &[&Kaomoji] = &[&NIGHT_OWL, &CAFFEINATED, &TGIFFFFF];
 
 /// Get time-based kaomoji for a specific time (used for testing)
 pub fn get_time_kaomoji_for(now: DateTime<Local>) -> Option<&'static Kaomoji> {
@@ -31,7 +29,7 @@ pub fn get_time_kaomoji_for(now: DateTime<Local>) -> Option<&'static Kaomoji> {
     }
 
     // Early morning (5 AM - 8 AM)
-    if hour >= 5 && hour < 8 {
+    if (5..8).contains(&hour) {
         return Some(&CAFFEINATED);
     }
 
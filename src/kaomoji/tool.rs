@@ -15,11 +15,14 @@ pub const BUG_HUNTER: Kaomoji = Kaomoji::new("(つ◉益◉)つ", "Bug Hunter");
 pub const QUALITY_AUDITOR: Kaomoji = Kaomoji::new("৻( •̀ ᗜ •́ ৻)", "Quality Auditor");
 
 // Development and Build
+pub const REFACTOR_SURGEON: Kaomoji = Kaomoji::new("(•̀ᴗ•́)ノ✄", "Refactor Surgeon");
 pub const COMPILATION_WARRIOR: Kaomoji = Kaomoji::new("ᕦ(ò_óˇ)ᕤ", "Compilation Warrior");
 pub const DEPENDENCY_WRANGLER: Kaomoji = Kaomoji::new("^⎚-⎚^", "Dependency Wrangler");
 
 // System Operations
 pub const DEPLOYMENT_GUARD: Kaomoji = Kaomoji::new("( ͡ _ ͡°)ﾉ⚲", "Deployment Guard");
+pub const INFRA_PLANNER: Kaomoji = Kaomoji::new("(￣ー￣)φ", "Cautious Planner");
+pub const BACKGROUND_RUNNER: Kaomoji = Kaomoji::new("ε=ε=┌( ˘▾˘)┘", "Background Runner");
 pub const TASK_ASSASSIN: Kaomoji = Kaomoji::new("(╬ ಠ益ಠ)", "Task Assassin");
 pub const NETWORK_SENTINEL: Kaomoji = Kaomoji::new("(╭ರ_ಠ)", "Network Sentinel");
 pub const SYSTEM_DETECTIVE: Kaomoji = Kaomoji::new("(◉_◉)", "System Detective");
@@ -36,7 +39,42 @@ pub const DATABASE_EXPERT: Kaomoji = Kaomoji::new("⚆_⚆", "Database Expert");
 pub const EDITOR_USER: Kaomoji = Kaomoji::new("( . .)φ", "Editor User");
 pub const ENVIRONMENT_ENCHANTER: Kaomoji = Kaomoji::new("(∗´ര ᎑ ര`∗)", "Environment Enchanter");
 pub const CONTAINER_CAPTAIN: Kaomoji = Kaomoji::new("(づ｡◕‿‿◕｡)づ", "Container Captain");
+pub const TASK_ORCHESTRATOR: Kaomoji = Kaomoji::new("(￣^￣)ゞ", "Task Orchestrator");
 
 // Research and Reading
+pub const WEB_SURFER: Kaomoji = Kaomoji::new("(~˘▾˘)~", "Web Surfer");
+pub const NET_RETRIEVER: Kaomoji = Kaomoji::new("(っ◔◡◔)っ ⇩", "Net Retriever");
 pub const SEARCH_MAESTRO: Kaomoji = Kaomoji::new("⋋| ◉ ͟ʖ ◉ |⋌", "Search Maestro");
 pub const RESEARCH_KING: Kaomoji = Kaomoji::new("╭༼ ººل͟ºº ༽╮", "Research King");
+
+/// Every tool and command face, listed for `personalities list`
+pub const TOOL_KAOMOJI: &[&Kaomoji] = &[
+    &GIT_MANAGER,
+    &CODE_HISTORIAN,
+    &TEST_TASKMASTER,
+    &BUG_HUNTER,
+    &QUALITY_AUDITOR,
+    &REFACTOR_SURGEON,
+    &COMPILATION_WARRIOR,
+    &DEPENDENCY_WRANGLER,
+    &DEPLOYMENT_GUARD,
+    &INFRA_PLANNER,
+    &BACKGROUND_RUNNER,
+    &TASK_ASSASSIN,
+    &NETWORK_SENTINEL,
+    &SYSTEM_DETECTIVE,
+    &SYSTEM_ADMIN,
+    &PERMISSION_POLICE,
+    &FILE_EXPLORER,
+    &STRING_SURGEON,
+    &COMPRESSION_CHEF,
+    &DATABASE_EXPERT,
+    &EDITOR_USER,
+    &ENVIRONMENT_ENCHANTER,
+    &CONTAINER_CAPTAIN,
+    &TASK_ORCHESTRATOR,
+    &WEB_SURFER,
+    &NET_RETRIEVER,
+    &SEARCH_MAESTRO,
+    &RESEARCH_KING,
+];
@@ -1,12 +1,17 @@
+pub mod atomic_write;
 pub mod cli;
 pub mod config;
+pub mod debug_log;
 pub mod error;
 pub mod hooks;
 pub mod icons;
+pub mod input;
 pub mod kaomoji;
 pub mod platform;
 pub mod state;
+pub mod stats;
 pub mod statusline;
+pub mod streak;
 pub mod theme;
 pub mod types;
 pub mod version;
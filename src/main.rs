@@ -1,21 +1,30 @@
 use anyhow::Result;
 use clap::{Arg, Command};
+use clap_complete::Shell;
 use colored::Colorize;
 
+mod atomic_write;
 mod cli;
 mod config;
+mod debug_log;
 mod error;
 mod hooks;
 mod icons;
+mod input;
 mod kaomoji;
 mod platform;
 mod state;
+mod stats;
 mod statusline;
+mod streak;
 mod theme;
 mod types;
 mod version;
 
-#[tokio::main]
+const BIN_NAME: &str = "claude-code-personalities";
+
+// The statusline runs on every render, so avoid spinning up a worker thread pool
+#[tokio::main(flavor = "current_thread")]
 async fn main() {
     if let Err(e) = run().await {
         // Check if it's our custom error type that already has nice formatting
@@ -29,8 +38,9 @@ async fn main() {
     }
 }
 
-async fn run() -> Result<()> {
-    let matches = Command::new("claude-code-personalities")
+/// Build the command-line interface definition
+fn build_cli() -> Command {
+    Command::new(BIN_NAME)
         .version(version::CURRENT_VERSION)
         .about("Dynamic text-face personalities for Claude Code's statusline")
         .subcommand(
@@ -49,18 +59,211 @@ async fn run() -> Result<()> {
                         .action(clap::ArgAction::SetTrue),
                 ),
         )
-        .subcommand(Command::new("update").about("Update to the latest version"))
-        .subcommand(Command::new("uninstall").about("Remove Claude Code Personalities"))
-        .subcommand(Command::new("status").about("Check installation status"))
-        .subcommand(Command::new("check-update").about("Check for available updates"))
+        .subcommand(
+            Command::new("update")
+                .about("Update to the latest version")
+                .arg(
+                    Arg::new("skip-checksum")
+                        .long("skip-checksum")
+                        .help("Install a release that publishes no checksum without asking")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("uninstall")
+                .about("Remove Claude Code Personalities")
+                .arg(
+                    Arg::new("purge")
+                        .long("purge")
+                        .help("Also remove user preferences and session state files")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("status").about("Check installation status").arg(
+                Arg::new("session-id")
+                    .long("session-id")
+                    .value_name("ID")
+                    .help("Render the statusline from this session's saved state"),
+            ),
+        )
+        .subcommand(
+            Command::new("check-update")
+                .about("Check for available updates")
+                .arg(
+                    Arg::new("quiet")
+                        .long("quiet")
+                        .visible_alias("check")
+                        .help("Print nothing; exit 0 if up to date, 10 if an update is available, 1 on error")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("simulate")
+                .about("Run a synthetic tool use through the hooks and print the statusline")
+                .arg(
+                    Arg::new("tool")
+                        .long("tool")
+                        .value_name("NAME")
+                        .help("Tool name, e.g. Edit, Bash or Grep")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("file")
+                        .long("file")
+                        .value_name("PATH")
+                        .help("File the tool touched"),
+                )
+                .arg(
+                    Arg::new("command")
+                        .long("command")
+                        .value_name("COMMAND")
+                        .help("Shell command, for the Bash tool"),
+                )
+                .arg(
+                    Arg::new("session-id")
+                        .long("session-id")
+                        .value_name("ID")
+                        .default_value("simulate")
+                        .help("Session whose state the simulated hook updates"),
+                ),
+        )
+        .subcommand(
+            Command::new("stats").about("Show local usage counts (when collect_stats is on)"),
+        )
+        .subcommand(
+            Command::new("personalities")
+                .about("Browse the built-in personalities")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("list").about("List every personality face, grouped by category"),
+                ),
+        )
         .subcommand(
             Command::new("config")
                 .about("Configure Claude Code Personalities display options")
                 .subcommand(
                     Command::new("display").about("Configure what appears in the statusline"),
                 )
-                .subcommand(Command::new("theme").about("Change color theme"))
-                .subcommand(Command::new("reset").about("Reset all settings to defaults")),
+                .subcommand(
+                    Command::new("theme")
+                        .about("Change color theme")
+                        .arg(
+                            Arg::new("preview")
+                                .long("preview")
+                                .help("Preview a sample statusline in every theme")
+                                .action(clap::ArgAction::SetTrue),
+                        )
+                        .subcommand(
+                            Command::new("set")
+                                .about("Set the color theme without prompting")
+                                .arg(
+                                    Arg::new("name")
+                                        .value_name("THEME")
+                                        .help("Theme name, e.g. dracula or high-contrast")
+                                        .required(true),
+                                ),
+                        )
+                        .subcommand(
+                            Command::new("validate")
+                                .about("Check a theme color file for invalid colors")
+                                .arg(
+                                    Arg::new("file")
+                                        .value_name("FILE")
+                                        .help("Theme color file with `role = color` lines")
+                                        .required(true),
+                                ),
+                        ),
+                )
+                .subcommand(
+                    Command::new("icon")
+                        .about("Override the icon shown for an activity")
+                        .arg(
+                            Arg::new("activity")
+                                .value_name("ACTIVITY")
+                                .help("Activity name, e.g. Testing")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("icon")
+                                .value_name("ICON")
+                                .help("Icon to show (an empty string hides the icon)"),
+                        )
+                        .arg(
+                            Arg::new("reset")
+                                .long("reset")
+                                .help("Restore the default icon for this activity")
+                                .action(clap::ArgAction::SetTrue)
+                                .conflicts_with("icon"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("face")
+                        .about("Override the face shown for a mood")
+                        .arg(
+                            Arg::new("mood")
+                                .value_name("MOOD")
+                                .help("Mood to customize")
+                                .required(true)
+                                .value_parser(["frustrated", "in-the-zone", "normal"]),
+                        )
+                        .arg(
+                            Arg::new("face")
+                                .value_name("FACE")
+                                .help("Face to show, e.g. (•̀ᴗ•́)و"),
+                        )
+                        .arg(
+                            Arg::new("reset")
+                                .long("reset")
+                                .help("Restore the built-in face for this mood")
+                                .action(clap::ArgAction::SetTrue)
+                                .conflicts_with("face"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("personality-pack")
+                        .about("Switch to a themed kaomoji pack")
+                        .arg(
+                            Arg::new("pack")
+                                .value_name("PACK")
+                                .help("Pack name (cats, bears, robots) or `default` for the built-in faces"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("order")
+                        .about("Set the order of statusline sections")
+                        .arg(
+                            Arg::new("sections")
+                                .value_name("SECTIONS")
+                                .help("Comma-separated sections, e.g. personality,git,activity,model"),
+                        )
+                        .arg(
+                            Arg::new("exact")
+                                .long("exact")
+                                .help("Drop sections that are not listed instead of appending them")
+                                .action(clap::ArgAction::SetTrue)
+                                .requires("sections"),
+                        ),
+                )
+                .subcommand(Command::new("show").about("List the current value of every setting"))
+                .subcommand(
+                    Command::new("diff")
+                        .about("Print only the settings that differ from the defaults"),
+                )
+                .subcommand(
+                    Command::new("migrate")
+                        .about("Upgrade the saved config to the current format"),
+                )
+                .subcommand(
+                    Command::new("reset").about("Reset all settings to defaults").arg(
+                        Arg::new("section")
+                            .long("section")
+                            .value_name("NAME")
+                            .help(
+                                "Only reset one group: git, activity, directory, model, display, or theme",
+                            ),
+                    ),
+                ),
         )
         .arg(
             Arg::new("statusline")
@@ -68,6 +271,37 @@ async fn run() -> Result<()> {
                 .help("Run in statusline mode (called by Claude Code)")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("tmux")
+                .long("tmux")
+                .help("Print tmux-formatted output from saved state (for status-right)")
+                .requires("statusline")
+                .conflicts_with("watch")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output encoding: ansi (default), plain, pango (Waybar, i3blocks), or tmux")
+                .value_parser(statusline::format::FORMAT_NAMES)
+                .requires("statusline")
+                .conflicts_with_all(["tmux", "watch"]),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("Re-render the statusline whenever the session state changes (debugging)")
+                .requires("statusline")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("session_id")
+                .long("session-id")
+                .value_name("ID")
+                .help("Session to watch (defaults to CLAUDE_SESSION_ID or claude_current)")
+                .requires("watch"),
+        )
         .arg(
             Arg::new("hook")
                 .long("hook")
@@ -75,13 +309,61 @@ async fn run() -> Result<()> {
                 .value_name("TYPE")
                 .value_parser(["pre-tool", "post-tool", "prompt-submit", "session-end"]),
         )
-        .get_matches();
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .help("Print hook diagnostics to stderr")
+                .requires("hook")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Generate shell completions")
+                .hide(true)
+                .arg(
+                    Arg::new("shell")
+                        .value_name("SHELL")
+                        .required(true)
+                        .value_parser(["bash", "zsh", "fish"]),
+                ),
+        )
+}
+
+/// Write completions for `shell` (bash, zsh, or fish) to `out`
+fn write_completions(shell: &str, out: &mut dyn std::io::Write) -> Result<()> {
+    let shell: Shell = shell
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Unsupported shell '{shell}': {e}"))?;
+    // Generate into a buffer first: the generator panics if the writer fails
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut build_cli(), BIN_NAME, &mut script);
+    out.write_all(&script)?;
+    Ok(())
+}
+
+async fn run() -> Result<()> {
+    let matches = build_cli().get_matches();
 
     // Handle different modes
-    if matches.get_flag("statusline") {
-        statusline::run_statusline().await
+    if matches.get_flag("watch") {
+        let session_id = state::SessionState::resolve_session_id(
+            matches.get_one::<String>("session_id").cloned(),
+            None,
+            None,
+        );
+        statusline::run_watch(&session_id).await
+    } else if matches.get_flag("tmux") {
+        statusline::run_tmux_statusline().await
+    } else if matches.get_flag("statusline") {
+        let format = matches
+            .get_one::<String>("format")
+            .map_or(Ok(statusline::format::OutputFormat::default()), |format| {
+                format.parse()
+            })
+            .map_err(|e: String| anyhow::anyhow!(e))?;
+        statusline::run_statusline(format).await
     } else if let Some(hook_type) = matches.get_one::<String>("hook") {
-        hooks::run_hook(hook_type).await
+        hooks::run_hook(hook_type, matches.get_flag("verbose")).await
     } else {
         // CLI commands
         match matches.subcommand() {
@@ -90,15 +372,67 @@ async fn run() -> Result<()> {
                 let backup = sub_matches.get_flag("backup");
                 cli::init(non_interactive, backup).await
             }
-            Some(("update", _)) => cli::update().await,
-            Some(("uninstall", _)) => cli::uninstall().await,
-            Some(("status", _)) => cli::status().await,
-            Some(("check-update", _)) => cli::check_update().await,
+            Some(("update", sub_matches)) => {
+                cli::update(sub_matches.get_flag("skip-checksum")).await
+            }
+            Some(("uninstall", sub_matches)) => cli::uninstall(sub_matches.get_flag("purge")).await,
+            Some(("status", sub_matches)) => {
+                cli::status(
+                    sub_matches
+                        .get_one::<String>("session-id")
+                        .map(String::as_str),
+                )
+                .await
+            }
+            Some(("check-update", sub_matches)) => {
+                let quiet = sub_matches.get_flag("quiet");
+                let outcome = cli::check_update(quiet).await?;
+                if quiet {
+                    std::process::exit(outcome.exit_code());
+                }
+                Ok(())
+            }
+            Some(("completions", sub_matches)) => {
+                let shell = sub_matches
+                    .get_one::<String>("shell")
+                    .map_or("bash", String::as_str);
+                write_completions(shell, &mut std::io::stdout())
+            }
+            Some(("simulate", sub_matches)) => {
+                let arg = |name: &str| sub_matches.get_one::<String>(name).map(String::as_str);
+                cli::simulate(
+                    arg("session-id").unwrap_or("simulate"),
+                    arg("tool").unwrap_or_default(),
+                    arg("file"),
+                    arg("command"),
+                )
+                .await
+            }
+            Some(("stats", _)) => cli::stats(),
+            Some(("personalities", _)) => cli::list_personalities(),
             Some(("config", sub_matches)) => {
-                let subcommand = sub_matches.subcommand().map(|(name, _)| name);
-                cli::config::handle_config_command(subcommand).await
+                cli::config::handle_config_command(sub_matches.subcommand()).await
             }
             _ => cli::help(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_completions_cover_flags() {
+        let mut output = Vec::new();
+        write_completions("bash", &mut output).unwrap();
+        let script = String::from_utf8(output).unwrap();
+        assert!(!script.is_empty());
+        assert!(script.contains("statusline"));
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        build_cli().debug_assert();
+    }
+}
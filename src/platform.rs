@@ -1,5 +1,36 @@
 use anyhow::{Result, anyhow};
 use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Directory holding per-session state, lock and error files.
+///
+/// `/tmp` on Unix so every hook and statusline process of a session agrees on
+/// it regardless of `TMPDIR`. On Windows this is the user's temp directory,
+/// normally `%LOCALAPPDATA%\Temp`.
+#[must_use]
+pub fn state_dir() -> PathBuf {
+    #[cfg(windows)]
+    {
+        dirs::data_local_dir()
+            .map(|dir| dir.join("Temp"))
+            .filter(|dir| dir.is_dir())
+            .unwrap_or_else(env::temp_dir)
+    }
+    #[cfg(not(windows))]
+    {
+        PathBuf::from("/tmp")
+    }
+}
+
+/// A `git` command that won't take optional locks, so reading status from the
+/// statusline never blocks or races a git operation Claude is running
+#[must_use]
+pub fn git_command() -> Command {
+    let mut command = Command::new("git");
+    command.env("GIT_OPTIONAL_LOCKS", "0");
+    command
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Platform {
@@ -74,6 +105,32 @@ fn detect_arch() -> Result<String> {
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_state_dir_unix() {
+        assert_eq!(state_dir(), PathBuf::from("/tmp"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_state_dir_windows() {
+        let dir = state_dir();
+        assert!(dir.is_absolute());
+        assert!(dir.is_dir());
+        assert!(!dir.starts_with("/tmp"));
+    }
+
+    #[test]
+    fn test_git_command_disables_optional_locks() {
+        let command = git_command();
+        assert_eq!(command.get_program(), "git");
+        assert!(
+            command
+                .get_envs()
+                .any(|(key, value)| key == "GIT_OPTIONAL_LOCKS" && value == Some("0".as_ref()))
+        );
+    }
+
     #[test]
     fn test_platform_detection() {
         let platform = Platform::detect().unwrap();
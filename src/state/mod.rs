@@ -1,13 +1,89 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::fs;
 
+use crate::atomic_write::write_temp_file;
 use crate::kaomoji::BOOTING_UP;
+use crate::platform;
 use crate::types::Activity;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// How long to wait for another hook to release the state lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+/// Age after which a lock file is assumed to be left behind by a crashed holder
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(10);
+/// Delay between attempts to acquire the state lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Advisory lock held while a session state is loaded, modified, and saved.
+///
+/// The lock is a `.lock` sidecar created with `create_new`, so only one holder
+/// can exist at a time. It is removed when the guard is dropped.
+struct StateLock {
+    path: PathBuf,
+    held: bool,
+}
+
+impl StateLock {
+    /// Acquire the lock for a session, waiting up to `LOCK_TIMEOUT`.
+    ///
+    /// Never fails: a lock file older than `LOCK_STALE_AFTER` (left behind by a
+    /// crashed process) is replaced, and when the lock is merely busy or can't be
+    /// created at all (e.g. permissions) the caller proceeds unlocked rather than
+    /// disrupting Claude Code. An unlocked guard never removes the lock file.
+    async fn acquire(session_id: &str) -> Self {
+        let path = SessionState::get_lock_path(session_id);
+        let started = Instant::now();
+
+        loop {
+            match Self::create(&path).await {
+                Ok(()) => return Self { path, held: true },
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if started.elapsed() >= LOCK_TIMEOUT {
+                        let held = Self::is_stale(&path).await
+                            && fs::remove_file(&path).await.is_ok()
+                            && Self::create(&path).await.is_ok();
+                        return Self { path, held };
+                    }
+                    tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+                }
+                Err(_) => return Self { path, held: false },
+            }
+        }
+    }
+
+    /// Create the lock file, failing if it already exists
+    async fn create(path: &Path) -> std::io::Result<()> {
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .await
+            .map(drop)
+    }
+
+    /// Whether the lock file at `path` is old enough that its holder must be gone
+    async fn is_stale(path: &Path) -> bool {
+        fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= LOCK_STALE_AFTER)
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        if self.held {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct MoodState {
     pub frustration_level: u8,        // 0-10, increases with errors
     pub momentum: u8,                 // 0-10, consecutive successes
@@ -17,6 +93,13 @@ pub struct MoodState {
 impl MoodState {
     /// Update mood based on error occurrence
     pub fn update(&mut self, had_error: bool) {
+        self.update_with_momentum(had_error, true);
+    }
+
+    /// Update mood, only building momentum on success when `builds_momentum` is set.
+    ///
+    /// Frustration still decays on any success.
+    pub fn update_with_momentum(&mut self, had_error: bool, builds_momentum: bool) {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -37,7 +120,9 @@ impl MoodState {
                 self.frustration_level = self.frustration_level.saturating_sub(1);
             }
 
-            self.momentum = (self.momentum + 1).min(10);
+            if builds_momentum {
+                self.momentum = (self.momentum + 1).min(10);
+            }
         }
     }
 
@@ -51,6 +136,270 @@ impl MoodState {
     }
 }
 
+/// Error count a reset must clear to trigger the recovery celebration
+pub const CELEBRATION_ERROR_THRESHOLD: u32 = 3;
+
+/// How long the recovery celebration is shown, in seconds
+pub const CELEBRATION_SECONDS: u64 = 5;
+
+/// Ceiling for `SessionState::consecutive_actions`, well past every pattern threshold
+pub const MAX_CONSECUTIVE_ACTIONS: u32 = 9999;
+
+/// Maximum number of entries kept in `SessionState::recent_activities`
+pub const RECENT_ACTIVITIES_LIMIT: usize = 10;
+
+/// Version control system managing a workspace
+#[derive(Debug, Clone, PartialEq)]
+pub enum VcsKind {
+    Git,
+    /// Jujutsu, usually colocated with a git repository
+    Jujutsu,
+    /// Mercurial, only when the repository has no `.git` alongside `.hg`
+    Mercurial,
+}
+
+impl VcsKind {
+    /// Detect the VCS for a directory: Jujutsu if it or any ancestor has a `.jj` directory,
+    /// Mercurial if the nearest repository root has `.hg` but no `.git`, otherwise git.
+    #[must_use]
+    pub fn detect(dir: &Path) -> Self {
+        if dir
+            .ancestors()
+            .any(|ancestor| ancestor.join(".jj").is_dir())
+        {
+            return Self::Jujutsu;
+        }
+
+        let nearest_root = dir
+            .ancestors()
+            .find(|ancestor| ancestor.join(".git").exists() || ancestor.join(".hg").is_dir());
+        match nearest_root {
+            Some(root) if !root.join(".git").exists() => Self::Mercurial,
+            _ => Self::Git,
+        }
+    }
+}
+
+/// Nearest directory at or above `dir` that is a git, Jujutsu, or Mercurial repository root
+#[must_use]
+pub fn find_repo_root(dir: &Path) -> Option<&Path> {
+    dir.ancestors().find(|ancestor| {
+        ancestor.join(".git").exists()
+            || ancestor.join(".jj").is_dir()
+            || ancestor.join(".hg").is_dir()
+    })
+}
+
+/// `jj log` arguments printing the bookmarks of the nearest bookmarked ancestor of `@`
+const JJ_BOOKMARK_ARGS: [&str; 9] = [
+    "log",
+    "--no-graph",
+    "--ignore-working-copy",
+    "--color",
+    "never",
+    "-r",
+    "latest(::@ & bookmarks())",
+    "-T",
+    "local_bookmarks.map(|b| b.name()).join(\" \")",
+];
+
+/// `git rev-parse` arguments printing the superproject path (only inside a submodule)
+/// followed by the repository top level
+const SUBMODULE_ARGS: [&str; 3] = [
+    "rev-parse",
+    "--show-superproject-working-tree",
+    "--show-toplevel",
+];
+
+/// Name of the submodule from `git rev-parse` output, or `None` outside a submodule.
+///
+/// Outside a submodule git prints only the top level, so a second line means
+/// the first one was the superproject.
+fn parse_submodule_name(output: &str) -> Option<String> {
+    let mut lines = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let _superproject = lines.next()?;
+    let toplevel = lines.next()?;
+    Path::new(toplevel)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// Length of the abbreviated commit hash shown for a detached HEAD
+const SHORT_HASH_LEN: usize = 7;
+
+/// Git directory of the repository containing `dir`, following the `gitdir:`
+/// pointer that worktrees and submodules keep in a `.git` file
+#[must_use]
+pub fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    let root = dir
+        .ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())?;
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = content.trim().strip_prefix("gitdir:")?.trim();
+    Some(root.join(git_dir))
+}
+
+/// Abbreviated commit hash when `HEAD` is detached, `None` when it names a branch
+#[must_use]
+pub fn read_detached_head(git_dir: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    if head.starts_with("ref:") || head.is_empty() {
+        return None;
+    }
+    Some(head.chars().take(SHORT_HASH_LEN).collect())
+}
+
+/// Operation left in progress in the repository, e.g. `rebasing` while a rebase
+/// is stopped on a conflict
+#[must_use]
+pub fn read_git_operation(git_dir: &Path) -> Option<&'static str> {
+    if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+        Some("rebasing")
+    } else if git_dir.join("MERGE_HEAD").is_file() {
+        Some("merging")
+    } else if git_dir.join("BISECT_LOG").is_file() {
+        Some("bisecting")
+    } else {
+        None
+    }
+}
+
+/// Whether `filename` is a `claude_code_personalities_<kind>_<session>...` artifact
+/// of `session_id` (or one of its subagents), without matching sessions that merely
+/// share a prefix such as `abc` and `abcd`.
+fn is_session_artifact_of(filename: &str, session_id: &str) -> bool {
+    let Some((_kind, rest)) = filename
+        .strip_prefix("claude_code_personalities_")
+        .and_then(|name| name.split_once('_'))
+    else {
+        return false;
+    };
+    rest.strip_prefix(session_id).is_some_and(|suffix| {
+        suffix.is_empty() || suffix.starts_with('.') || suffix.starts_with("__agent_")
+    })
+}
+
+/// Whether `before` and `after` differ only in what the statusline shows for the
+/// current tool call, which the next hook overwrites anyway
+fn only_display_details_changed(before: &SessionState, after: &SessionState) -> bool {
+    let mut after = after.clone();
+    after.current_job.clone_from(&before.current_job);
+    after.current_file.clone_from(&before.current_file);
+    after.last_updated_at = before.last_updated_at;
+    after == *before
+}
+
+/// Whether a save at `now` falls within `debounce` of the last save at `last_saved`.
+///
+/// Unknown or future modification times never count as recent.
+fn is_within_debounce(last_saved: Option<SystemTime>, now: SystemTime, debounce: Duration) -> bool {
+    last_saved
+        .and_then(|saved| now.duration_since(saved).ok())
+        .is_some_and(|age| age < debounce)
+}
+
+/// Whether a failed rename means the target is a file we can't replace, rather
+/// than a problem saving would also hit at another path
+fn is_foreign_file_error(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::PermissionDenied
+            | ErrorKind::AlreadyExists
+            | ErrorKind::IsADirectory
+            | ErrorKind::DirectoryNotEmpty
+    )
+}
+
+/// Real user id of this process
+#[cfg(unix)]
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and always succeeds
+    unsafe { libc::getuid() }
+}
+
+#[cfg(not(unix))]
+fn current_uid() -> u32 {
+    0
+}
+
+/// Dirty flag and changed file count from `hg status` output (one file per line)
+fn parse_hg_status(output: &str) -> (bool, usize) {
+    let count = output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count();
+    (count > 0, count)
+}
+
+/// Current Mercurial branch in `current_dir`, or `None` if `hg` is unavailable or fails
+async fn read_hg_branch(current_dir: &str) -> Option<String> {
+    let output = tokio::process::Command::new("hg")
+        .arg("branch")
+        .current_dir(current_dir)
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!branch.is_empty()).then_some(branch)
+}
+
+/// Tracked and untracked changed file counts from `git status --porcelain` output.
+///
+/// Each line is one file; `??` marks an untracked file, any other status code a
+/// change to a tracked one.
+fn parse_porcelain_status(output: &str) -> (usize, usize) {
+    output.lines().filter(|line| !line.trim().is_empty()).fold(
+        (0, 0),
+        |(tracked, untracked), line| {
+            if line.starts_with("??") {
+                (tracked, untracked + 1)
+            } else {
+                (tracked + 1, untracked)
+            }
+        },
+    )
+}
+
+/// Whether a git result cached at `checked_at` is older than 2 seconds
+fn is_git_cache_stale(checked_at: Option<u64>) -> bool {
+    match checked_at {
+        None => true, // No cache yet
+        Some(timestamp) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            now.saturating_sub(timestamp) > 2 // Refresh if older than 2 seconds
+        }
+    }
+}
+
+/// Number of stash entries in `git stash list` output (one entry per line)
+fn parse_stash_count(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+}
+
+/// Extract the first bookmark name from `jj log` template output
+fn parse_jj_bookmark(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .next()
+        .map(|name| name.trim_end_matches('*').to_string())
+        .filter(|name| !name.is_empty())
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PersonalityModifier {
     Frustrated,
@@ -58,7 +407,7 @@ pub enum PersonalityModifier {
     Normal,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionState {
     pub session_id: String,
     pub activity: Activity,
@@ -72,10 +421,30 @@ pub struct SessionState {
     #[serde(default)]
     pub git_dirty_count: Option<usize>, // Number of dirty files
     #[serde(default)]
+    pub git_modified_count: Option<usize>, // Dirty files git already tracks
+    #[serde(default)]
+    pub git_untracked_count: Option<usize>, // Dirty files git doesn't track yet
+    #[serde(default)]
     pub git_status_checked_at: Option<u64>, // Unix timestamp for cache invalidation
+    #[serde(default)]
+    pub git_stash_count: Option<usize>, // Number of stash entries
+    #[serde(default)]
+    pub git_stash_checked_at: Option<u64>, // Unix timestamp of the last stash count
+    #[serde(default)]
+    pub git_detached_head: Option<String>, // Short commit hash while HEAD is detached
+    #[serde(default)]
+    pub git_operation: Option<String>, // In-progress operation, e.g. "rebasing"
     pub personality: String,
     pub previous_personality: Option<String>,
     pub consecutive_actions: u32,
+    #[serde(default)]
+    pub total_actions: u32, // Never reset by activity changes
+    #[serde(default)]
+    pub last_updated_at: Option<u64>, // Unix timestamp of the last recorded activity
+    #[serde(default)]
+    pub session_started_at: Option<u64>, // Unix timestamp when the state was first created
+    #[serde(default)]
+    pub celebrate_until: Option<u64>, // Unix timestamp until which a recovery is celebrated
     pub error_count: u32,
     #[serde(default)]
     pub recent_activities: Vec<Activity>,
@@ -93,10 +462,20 @@ impl Default for SessionState {
             git_branch: None,
             git_dirty: None,
             git_dirty_count: None,
+            git_modified_count: None,
+            git_untracked_count: None,
             git_status_checked_at: None,
+            git_stash_count: None,
+            git_stash_checked_at: None,
+            git_detached_head: None,
+            git_operation: None,
             personality: BOOTING_UP.personality(),
             previous_personality: None,
             consecutive_actions: 0,
+            total_actions: 0,
+            last_updated_at: None,
+            session_started_at: None,
+            celebrate_until: None,
             error_count: 0,
             recent_activities: Vec::new(),
             mood: MoodState::default(),
@@ -120,29 +499,84 @@ impl SessionState {
     /// This function currently does not return errors in practice, as all failure cases
     /// fall back to creating a default state. It returns Result for API compatibility.
     pub async fn load(session_id: &str) -> Result<Self> {
-        let path = Self::get_state_path(session_id);
-
-        if path.exists() {
-            // Try to read existing state
-            if let Ok(content) = fs::read_to_string(&path).await {
-                if let Ok(state) = serde_json::from_str::<SessionState>(&content) {
-                    return Ok(state);
-                }
-            }
-            // If read or parse fails, fall through to create default state
+        // If the file is missing, unreadable, or invalid, fall through to a default state
+        if let Some(state) = Self::read_from_disk(session_id).await {
+            return Ok(state);
         }
 
         // Return default state without saving
         // This avoids race conditions when multiple hooks fire simultaneously
         // The state will be saved on the first update operation
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
         Ok(Self {
             session_id: session_id.to_string(),
+            session_started_at: Some(now),
             ..Default::default()
         })
     }
 
+    /// Read the persisted state for a session, if a valid one exists
+    async fn read_from_disk(session_id: &str) -> Option<Self> {
+        let content = fs::read_to_string(Self::active_state_path(session_id))
+            .await
+            .ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Apply `change` to the latest persisted state under the session lock, then save.
+    ///
+    /// Re-reading inside the lock means concurrent hooks build on each other's
+    /// writes instead of clobbering them. If nothing is persisted yet, the
+    /// in-memory state is used as the starting point.
+    async fn modify(&mut self, change: impl FnOnce(&mut SessionState)) -> Result<()> {
+        self.modify_debounced(Duration::ZERO, change).await
+    }
+
+    /// [`Self::modify`], skipping the save when the state file was written less
+    /// than `debounce` ago and the change only touches display details
+    ///
+    /// Counters, mood and the personality feed the next hook's decisions, so a
+    /// change to any of them is always written.
+    async fn modify_debounced(
+        &mut self,
+        debounce: Duration,
+        change: impl FnOnce(&mut SessionState),
+    ) -> Result<()> {
+        let _lock = StateLock::acquire(&self.session_id).await;
+
+        let persisted = Self::read_from_disk(&self.session_id).await;
+        let mut latest = persisted.clone().unwrap_or_else(|| self.clone());
+        change(&mut latest);
+
+        // Each hook is a fresh process, so the last save time comes from the file
+        let lasting_change = persisted
+            .as_ref()
+            .is_none_or(|before| !only_display_details_changed(before, &latest));
+        let last_saved = if debounce.is_zero() || lasting_change {
+            None
+        } else {
+            fs::metadata(Self::active_state_path(&self.session_id))
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        };
+        if !is_within_debounce(last_saved, SystemTime::now(), debounce) {
+            latest.save().await?;
+        }
+
+        *self = latest;
+        Ok(())
+    }
+
     /// Save the current session state to disk.
     ///
+    /// The state is written to a temporary file and renamed into place, so readers
+    /// never observe a partially written file. If the shared path is taken by a file
+    /// this user can't replace (e.g. a stale one from another user on a CI runner),
+    /// the state moves to a per-user path instead.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
@@ -152,64 +586,96 @@ impl SessionState {
     pub async fn save(&self) -> Result<()> {
         use anyhow::Context;
 
-        let path = Self::get_state_path(&self.session_id);
+        let path = Self::active_state_path(&self.session_id);
         let content = serde_json::to_string_pretty(self).with_context(|| {
             format!(
                 "Failed to serialize session state for session {}",
                 self.session_id
             )
         })?;
-        fs::write(&path, content)
+
+        // Unique per writer so concurrent unlocked saves never share a temp file
+        let temp_path = write_temp_file(&path, &content)
             .await
-            .with_context(|| format!("Failed to save session state to {}", path.display()))?;
+            .with_context(|| format!("Failed to write session state beside {}", path.display()))?;
+        let mut result = fs::rename(&temp_path, &path).await;
+        if let Err(e) = &result
+            && is_foreign_file_error(e.kind())
+        {
+            result = fs::rename(&temp_path, Self::user_state_path(&self.session_id)).await;
+        }
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(e)
+                .with_context(|| format!("Failed to save session state to {}", path.display()));
+        }
         Ok(())
     }
 
     /// Update the current activity and personality, then save to disk.
     ///
+    /// The write is skipped when the state file was saved less than `debounce`
+    /// ago and only display details changed. Every recorded action moves the
+    /// counters, so in practice only repeated no-op updates are skipped. The
+    /// in-memory state is updated either way.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - The session state cannot be saved to disk after updating
     /// - File system operations fail during save
     /// - JSON serialization fails
-    pub async fn update_activity(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_activity_debounced(
         &mut self,
         activity: Activity,
         current_job: Option<String>,
         current_file: Option<String>,
-        git_branch: Option<String>,
         personality: String,
+        builds_momentum: bool,
+        debounce: Duration,
     ) -> Result<()> {
         use anyhow::Context;
 
-        // Update consecutive actions
-        if self.activity == activity {
-            self.consecutive_actions += 1;
-        } else {
-            self.consecutive_actions = 1;
-        }
-
-        // Check for personality change
-        if self.personality != personality {
-            self.previous_personality = Some(self.personality.clone());
-        }
+        let session_id = self.session_id.clone();
+        self.modify_debounced(debounce, |state| {
+            // Update consecutive actions, clamped so long sessions never overflow
+            if state.activity == activity {
+                state.consecutive_actions = state
+                    .consecutive_actions
+                    .saturating_add(1)
+                    .min(MAX_CONSECUTIVE_ACTIONS);
+            } else {
+                state.consecutive_actions = 1;
+            }
+            state.total_actions = state.total_actions.saturating_add(1);
+            state.last_updated_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|elapsed| elapsed.as_secs());
+
+            // Check for personality change
+            if state.personality != personality {
+                state.previous_personality = Some(state.personality.clone());
+            }
 
-        self.activity = activity;
-        self.current_job = current_job;
-        self.current_file = current_file;
-        self.git_branch = git_branch;
-        self.personality = personality;
+            state.recent_activities.push(activity.clone());
+            let overflow = state
+                .recent_activities
+                .len()
+                .saturating_sub(RECENT_ACTIVITIES_LIMIT);
+            state.recent_activities.drain(..overflow);
 
-        // Update mood for successful activity (no error)
-        self.mood.update(false);
+            state.activity = activity;
+            state.current_job = current_job;
+            state.current_file = current_file;
+            state.personality = personality;
 
-        self.save().await.with_context(|| {
-            format!(
-                "Failed to save updated activity for session {}",
-                self.session_id
-            )
+            // Update mood for successful activity (no error)
+            state.mood.update_with_momentum(false, builds_momentum);
         })
+        .await
+        .with_context(|| format!("Failed to save updated activity for session {session_id}"))
     }
 
     /// Increment the error count and save to disk.
@@ -223,14 +689,13 @@ impl SessionState {
     pub async fn increment_errors(&mut self) -> Result<()> {
         use anyhow::Context;
 
-        self.error_count += 1;
-        self.mood.update(true); // Update mood for error
-        self.save().await.with_context(|| {
-            format!(
-                "Failed to save incremented error count for session {}",
-                self.session_id
-            )
+        let session_id = self.session_id.clone();
+        self.modify(|state| {
+            state.error_count += 1;
+            state.mood.update(true); // Update mood for error
         })
+        .await
+        .with_context(|| format!("Failed to save incremented error count for session {session_id}"))
     }
 
     /// Reset the error count to zero and save to disk.
@@ -244,13 +709,20 @@ impl SessionState {
     pub async fn reset_errors(&mut self) -> Result<()> {
         use anyhow::Context;
 
-        self.error_count = 0;
-        self.save().await.with_context(|| {
-            format!(
-                "Failed to save reset error count for session {}",
-                self.session_id
-            )
+        let session_id = self.session_id.clone();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.modify(|state| {
+            // Recovering from a frustrating streak earns a brief celebration
+            if state.error_count >= CELEBRATION_ERROR_THRESHOLD {
+                state.celebrate_until = Some(now + CELEBRATION_SECONDS);
+            }
+            state.error_count = 0;
         })
+        .await
+        .with_context(|| format!("Failed to save reset error count for session {session_id}"))
     }
 
     /// Check if git status cache is stale (older than 2 seconds).
@@ -258,16 +730,7 @@ impl SessionState {
     /// Returns true if the cache should be refreshed, false if cached value is still valid.
     #[must_use]
     pub fn should_refresh_git_status(&self) -> bool {
-        match self.git_status_checked_at {
-            None => true, // No cache yet
-            Some(timestamp) => {
-                let now = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                now - timestamp > 2 // Refresh if older than 2 seconds
-            }
-        }
+        is_git_cache_stale(self.git_status_checked_at)
     }
 
     /// Check git working tree status and update the state with caching.
@@ -289,41 +752,35 @@ impl SessionState {
         }
 
         // Run git status --porcelain (exits with 0 and empty output if clean)
-        let output = tokio::process::Command::new("git")
-            .args(&["status", "--porcelain"])
-            .env("GIT_OPTIONAL_LOCKS", "0")
+        let output = tokio::process::Command::from(platform::git_command())
+            .args(["status", "--porcelain"])
             .output()
             .await;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                // Working tree is dirty if there's any output
-                let has_changes = !output.stdout.is_empty();
-                self.git_dirty = Some(has_changes);
-
-                // Count number of changed files (each line is a file)
-                if has_changes {
-                    let count = output
-                        .stdout
-                        .split(|&b| b == b'\n')
-                        .filter(|line| !line.is_empty())
-                        .count();
-                    self.git_dirty_count = Some(count);
-                } else {
-                    self.git_dirty_count = Some(0);
-                }
-
-                // Update cache timestamp
-                let now = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                self.git_status_checked_at = Some(now);
-            }
+        if let Ok(output) = output
+            && output.status.success()
+        {
+            self.record_porcelain_status(&String::from_utf8_lossy(&output.stdout));
+
+            // Update cache timestamp
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            self.git_status_checked_at = Some(now);
         }
         // If git command fails, keep existing cached value (don't set to None)
     }
 
+    /// Store the dirty flag and changed file counts from `git status --porcelain` output
+    fn record_porcelain_status(&mut self, output: &str) {
+        let (tracked, untracked) = parse_porcelain_status(output);
+        self.git_dirty = Some(tracked + untracked > 0);
+        self.git_dirty_count = Some(tracked + untracked);
+        self.git_modified_count = Some(tracked);
+        self.git_untracked_count = Some(untracked);
+    }
+
     /// Check git working tree status in a specific directory and update the state with caching.
     ///
     /// This is the same as `refresh_git_status()` but runs git commands in the specified directory.
@@ -337,46 +794,120 @@ impl SessionState {
             return;
         }
 
+        if VcsKind::detect(Path::new(current_dir)) == VcsKind::Mercurial {
+            self.refresh_hg_status(current_dir).await;
+            return;
+        }
+
         // Run git status --porcelain in the specified directory
-        let output = tokio::process::Command::new("git")
+        let output = tokio::process::Command::from(platform::git_command())
             .args(["status", "--porcelain"])
             .current_dir(current_dir)
-            .env("GIT_OPTIONAL_LOCKS", "0")
             .output()
             .await;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                // Working tree is dirty if there's any output
-                let has_changes = !output.stdout.is_empty();
-                self.git_dirty = Some(has_changes);
-
-                // Count number of changed files (each line is a file)
-                if has_changes {
-                    let count = output
-                        .stdout
-                        .split(|&b| b == b'\n')
-                        .filter(|line| !line.is_empty())
-                        .count();
-                    self.git_dirty_count = Some(count);
-                } else {
-                    self.git_dirty_count = Some(0);
-                }
+        if let Ok(output) = output
+            && output.status.success()
+        {
+            self.record_porcelain_status(&String::from_utf8_lossy(&output.stdout));
+
+            // Update cache timestamp
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            self.git_status_checked_at = Some(now);
+        }
+        // If git command fails, keep existing cached value (don't set to None)
+    }
+
+    /// Refresh the stash entry count from `git stash list`.
+    ///
+    /// Cached for 2 seconds on its own timestamp, independent of the git status
+    /// cache. Keeps the cached value if git fails.
+    pub async fn refresh_git_stash_count(&mut self, current_dir: Option<&str>) {
+        if !is_git_cache_stale(self.git_stash_checked_at) {
+            return;
+        }
+
+        let mut command = tokio::process::Command::from(platform::git_command());
+        command.args(["stash", "list"]);
+        if let Some(dir) = current_dir {
+            command.current_dir(dir);
+        }
 
-                // Update cache timestamp
-                let now = SystemTime::now()
+        if let Ok(output) = command.output().await
+            && output.status.success()
+        {
+            self.git_stash_count =
+                Some(parse_stash_count(&String::from_utf8_lossy(&output.stdout)));
+            self.git_stash_checked_at = Some(
+                SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .unwrap_or_default()
-                    .as_secs();
-                self.git_status_checked_at = Some(now);
-            }
+                    .as_secs(),
+            );
+        }
+    }
+
+    /// Refresh the dirty flag and count from `hg status` in a Mercurial repository.
+    ///
+    /// Keeps the existing cached value if `hg` is not installed or fails.
+    async fn refresh_hg_status(&mut self, current_dir: &str) {
+        let Ok(output) = tokio::process::Command::new("hg")
+            .args(["status"])
+            .current_dir(current_dir)
+            .output()
+            .await
+        else {
+            return;
+        };
+
+        if output.status.success() {
+            let (dirty, count) = parse_hg_status(&String::from_utf8_lossy(&output.stdout));
+            self.git_dirty = Some(dirty);
+            self.git_dirty_count = Some(count);
+            self.git_modified_count = None;
+            self.git_untracked_count = None;
+            self.git_status_checked_at = Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            );
         }
-        // If git command fails, keep existing cached value (don't set to None)
+    }
+
+    /// Refresh the branch from a Jujutsu workspace using the nearest bookmark.
+    ///
+    /// Runs `jj log` for the closest bookmarked ancestor of the working copy and stores
+    /// its bookmark name in `git_branch`. Returns `false` if `jj` is not installed or
+    /// fails, so the caller can fall back to git.
+    pub async fn refresh_jj_status(&mut self, current_dir: &str) -> bool {
+        let Ok(output) = tokio::process::Command::new("jj")
+            .args(JJ_BOOKMARK_ARGS)
+            .current_dir(current_dir)
+            .output()
+            .await
+        else {
+            return false;
+        };
+
+        if !output.status.success() {
+            return false;
+        }
+
+        if let Some(bookmark) = parse_jj_bookmark(&String::from_utf8_lossy(&output.stdout)) {
+            self.git_branch = Some(bookmark);
+        }
+        true
     }
 
     /// Refresh git branch from the specified directory.
     ///
     /// This method runs `git branch --show-current` to get the current branch.
+    /// In Jujutsu workspaces the nearest jj bookmark is used instead, falling back
+    /// to git when `jj` is unavailable.
     /// Uses the same 2-second cache as git status to avoid performance overhead.
     ///
     /// # Arguments
@@ -387,46 +918,85 @@ impl SessionState {
             return;
         }
 
+        match VcsKind::detect(Path::new(current_dir)) {
+            VcsKind::Jujutsu if self.refresh_jj_status(current_dir).await => return,
+            VcsKind::Mercurial => {
+                if let Some(branch) = read_hg_branch(current_dir).await {
+                    self.git_branch = Some(branch);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        // Read straight from the git directory: a detached HEAD has no branch for
+        // git to report, and operations in progress only show up as files there
+        let git_dir = find_git_dir(Path::new(current_dir));
+        self.git_detached_head = git_dir.as_deref().and_then(read_detached_head);
+        self.git_operation = git_dir
+            .as_deref()
+            .and_then(read_git_operation)
+            .map(str::to_string);
+
+        let Some(branch) = Self::read_git_branch(current_dir).await else {
+            // Don't overwrite the existing cached value
+            return;
+        };
+
+        // Inside a submodule, say so instead of looking like the superproject's branch
+        let submodule = tokio::process::Command::from(platform::git_command())
+            .args(SUBMODULE_ARGS)
+            .current_dir(current_dir)
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_submodule_name(&String::from_utf8_lossy(&output.stdout)));
+
+        self.git_branch = Some(match submodule {
+            Some(name) => format!("{name}:{branch}"),
+            None => branch,
+        });
+    }
+
+    /// Current git branch in `current_dir`, or `None` when it can't be determined
+    async fn read_git_branch(current_dir: &str) -> Option<String> {
         // Try modern git first (2.22+)
-        if let Ok(output) = tokio::process::Command::new("git")
+        if let Ok(output) = tokio::process::Command::from(platform::git_command())
             .args(["branch", "--show-current"])
             .current_dir(current_dir)
-            .env("GIT_OPTIONAL_LOCKS", "0")
             .output()
             .await
+            && output.status.success()
         {
-            if output.status.success() {
-                let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !branch.is_empty() {
-                    self.git_branch = Some(branch);
-                    return;
-                }
+            let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !branch.is_empty() {
+                return Some(branch);
             }
         }
 
         // Fallback for older git or detached HEAD
-        if let Ok(output) = tokio::process::Command::new("git")
+        if let Ok(output) = tokio::process::Command::from(platform::git_command())
             .args(["rev-parse", "--abbrev-ref", "HEAD"])
             .current_dir(current_dir)
-            .env("GIT_OPTIONAL_LOCKS", "0")
             .output()
             .await
+            && output.status.success()
         {
-            if output.status.success() {
-                let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !branch.is_empty() && branch != "HEAD" {
-                    self.git_branch = Some(branch);
-                    return;
-                }
+            let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !branch.is_empty() && branch != "HEAD" {
+                return Some(branch);
             }
         }
 
-        // If both fail, don't overwrite existing cached value
+        None
     }
 
-    /// Clean up session state files for the given session ID.
+    /// Clean up all files belonging to the given session ID.
     ///
-    /// This function removes both the state file and error count file.
+    /// Removes every `claude_code_personalities_*` artifact for the session in the
+    /// state directory: the state and error files, lock files, leftover temp files
+    /// from interrupted saves, and the state of the session's subagents.
     /// Missing files are ignored and will not cause an error.
     ///
     /// # Errors
@@ -435,25 +1005,84 @@ impl SessionState {
     /// - This function currently does not return errors as file removal failures are ignored
     pub async fn cleanup(session_id: &str) -> Result<()> {
         let state_path = Self::get_state_path(session_id);
-        let error_path = Self::get_error_path(session_id);
+        let Some(state_dir) = state_path.parent() else {
+            return Ok(());
+        };
 
-        // Ignore errors if files don't exist
-        let _ = fs::remove_file(&state_path).await;
-        let _ = fs::remove_file(&error_path).await;
+        let Ok(mut entries) = fs::read_dir(state_dir).await else {
+            return Ok(());
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if is_session_artifact_of(&entry.file_name().to_string_lossy(), session_id) {
+                // Ignore errors if the file was already removed
+                let _ = fs::remove_file(entry.path()).await;
+            }
+        }
 
         Ok(())
     }
 
+    /// Resolve the session id used for state files.
+    ///
+    /// Falls back to the parent session, then `CLAUDE_SESSION_ID`, then
+    /// `claude_current` when Claude Code sends no session id. Subagents with an
+    /// `agent_id` get their own namespace so they don't overwrite each other's state.
+    #[must_use]
+    pub fn resolve_session_id(
+        session_id: Option<String>,
+        parent_session_id: Option<String>,
+        agent_id: Option<&str>,
+    ) -> String {
+        let base = session_id.or(parent_session_id).unwrap_or_else(|| {
+            // Use a predictable session ID for the current Claude session
+            std::env::var("CLAUDE_SESSION_ID").unwrap_or_else(|_| "claude_current".to_string())
+        });
+
+        // Keep the agent id filename-safe
+        let agent: String = agent_id
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            .collect();
+
+        if agent.is_empty() {
+            base
+        } else {
+            format!("{base}__agent_{agent}")
+        }
+    }
+
     #[must_use]
     pub fn get_state_path(session_id: &str) -> PathBuf {
-        PathBuf::from(format!(
-            "/tmp/claude_code_personalities_activity_{session_id}.json"
+        platform::state_dir().join(format!(
+            "claude_code_personalities_activity_{session_id}.json"
         ))
     }
 
-    fn get_error_path(session_id: &str) -> PathBuf {
-        PathBuf::from(format!(
-            "/tmp/claude_code_personalities_errors_{session_id}.count"
+    /// Per-user state path used when the shared one belongs to someone else
+    #[must_use]
+    pub fn user_state_path(session_id: &str) -> PathBuf {
+        platform::state_dir().join(format!(
+            "claude_code_personalities_activity_{session_id}.uid{}.json",
+            current_uid()
+        ))
+    }
+
+    /// Where this user's state for a session lives: the per-user path once a save
+    /// has fallen back to it, otherwise the shared path
+    #[must_use]
+    pub fn active_state_path(session_id: &str) -> PathBuf {
+        let user_path = Self::user_state_path(session_id);
+        if user_path.exists() {
+            user_path
+        } else {
+            Self::get_state_path(session_id)
+        }
+    }
+
+    fn get_lock_path(session_id: &str) -> PathBuf {
+        platform::state_dir().join(format!(
+            "claude_code_personalities_activity_{session_id}.json.lock"
         ))
     }
 }
@@ -496,6 +1125,7 @@ mod tests {
         assert_eq!(state.personality, "( ˘ ³˘) Chillin");
         assert_eq!(state.consecutive_actions, 0);
         assert_eq!(state.error_count, 0);
+        assert!(state.session_started_at.is_some());
     }
 
     #[tokio::test]
@@ -511,10 +1141,20 @@ mod tests {
             git_branch: None,
             git_dirty: None,
             git_dirty_count: None,
+            git_modified_count: None,
+            git_untracked_count: None,
             git_status_checked_at: None,
+            git_stash_count: None,
+            git_stash_checked_at: None,
+            git_detached_head: None,
+            git_operation: None,
             personality: "Cowder".to_string(),
             previous_personality: None,
             consecutive_actions: 5,
+            total_actions: 12,
+            last_updated_at: None,
+            session_started_at: None,
+            celebrate_until: None,
             error_count: 2,
             recent_activities: Vec::new(),
             mood: MoodState::default(),
@@ -530,12 +1170,110 @@ mod tests {
         assert_eq!(loaded_state.current_job, Some("test.js".to_string()));
         assert_eq!(loaded_state.personality, "Cowder");
         assert_eq!(loaded_state.consecutive_actions, 5);
+        assert_eq!(loaded_state.total_actions, 12);
         assert_eq!(loaded_state.error_count, 2);
 
         // Cleanup
         SessionState::cleanup(&session_id).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_consecutive_actions_stay_at_cap() {
+        let session_id = create_test_session_id();
+        let mut state = SessionState {
+            session_id: session_id.clone(),
+            activity: Activity::Editing,
+            consecutive_actions: MAX_CONSECUTIVE_ACTIONS - 1,
+            ..Default::default()
+        };
+
+        for _ in 0..3 {
+            state
+                .update_activity_debounced(
+                    Activity::Editing,
+                    None,
+                    None,
+                    "Editor".to_string(),
+                    true,
+                    Duration::ZERO,
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(state.consecutive_actions, MAX_CONSECUTIVE_ACTIONS);
+
+        SessionState::cleanup(&session_id).await.unwrap();
+    }
+
+    #[test]
+    fn test_debounce_decision() {
+        let now = SystemTime::now();
+        let debounce = Duration::from_millis(500);
+
+        let recent = now - Duration::from_millis(100);
+        assert!(is_within_debounce(Some(recent), now, debounce));
+
+        let old = now - Duration::from_secs(2);
+        assert!(!is_within_debounce(Some(old), now, debounce));
+
+        // Disabled, unknown, or from the future (clock skew): always save
+        assert!(!is_within_debounce(Some(recent), now, Duration::ZERO));
+        assert!(!is_within_debounce(None, now, debounce));
+        assert!(!is_within_debounce(
+            Some(now + Duration::from_secs(5)),
+            now,
+            debounce
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_debounced_update_keeps_counters() {
+        let session_id = create_test_session_id();
+        let mut state = SessionState::load(&session_id).await.unwrap();
+        async fn update(state: &mut SessionState, personality: &str) {
+            state
+                .update_activity_debounced(
+                    Activity::Editing,
+                    None,
+                    None,
+                    personality.to_string(),
+                    true,
+                    Duration::from_secs(60),
+                )
+                .await
+                .unwrap();
+        }
+
+        // Nothing on disk yet, so the first update is written
+        update(&mut state, "Code Wizard").await;
+        assert_eq!(state.consecutive_actions, 1);
+
+        // Same personality and activity right after: the counters still reach disk
+        update(&mut state, "Code Wizard").await;
+        assert_eq!(state.consecutive_actions, 2);
+        let on_disk = SessionState::read_from_disk(&session_id).await.unwrap();
+        assert_eq!(on_disk.consecutive_actions, 2);
+        assert_eq!(on_disk.total_actions, 2);
+
+        // Only the shown file changes: memory moves on, disk doesn't
+        state
+            .modify_debounced(Duration::from_secs(60), |state| {
+                state.current_file = Some("main.rs".to_string());
+            })
+            .await
+            .unwrap();
+        assert_eq!(state.current_file.as_deref(), Some("main.rs"));
+        let on_disk = SessionState::read_from_disk(&session_id).await.unwrap();
+        assert_eq!(on_disk.current_file, None);
+
+        // A new personality is always written
+        update(&mut state, "Code Berserker").await;
+        let on_disk = SessionState::read_from_disk(&session_id).await.unwrap();
+        assert_eq!(on_disk.personality, "Code Berserker");
+
+        SessionState::cleanup(&session_id).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_update_activity() {
         let session_id = create_test_session_id();
@@ -543,12 +1281,13 @@ mod tests {
 
         // First update
         state
-            .update_activity(
+            .update_activity_debounced(
                 Activity::Editing,
                 None,
                 Some("main.js".to_string()),
-                None,
                 "JS Master".to_string(),
+                true,
+                Duration::ZERO,
             )
             .await
             .unwrap();
@@ -560,12 +1299,13 @@ mod tests {
 
         // Same activity should increment consecutive
         state
-            .update_activity(
+            .update_activity_debounced(
                 Activity::Editing,
                 None,
                 Some("utils.js".to_string()),
-                None,
                 "JS Master".to_string(),
+                true,
+                Duration::ZERO,
             )
             .await
             .unwrap();
@@ -574,18 +1314,20 @@ mod tests {
 
         // Different activity should reset consecutive
         state
-            .update_activity(
+            .update_activity_debounced(
                 Activity::Reading,
                 None,
                 Some("README.md".to_string()),
-                None,
                 "Documentation Writer".to_string(),
+                true,
+                Duration::ZERO,
             )
             .await
             .unwrap();
 
         assert_eq!(state.activity, Activity::Reading);
         assert_eq!(state.consecutive_actions, 1);
+        assert_eq!(state.total_actions, 3);
 
         // Cleanup
         SessionState::cleanup(&session_id).await.unwrap();
@@ -613,6 +1355,161 @@ mod tests {
         SessionState::cleanup(&session_id).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_save_falls_back_when_shared_path_is_unwritable() {
+        let session_id = create_test_session_id();
+        // Something we can't replace occupies the shared path
+        let shared_path = SessionState::get_state_path(&session_id);
+        std::fs::create_dir_all(shared_path.join("stale")).unwrap();
+
+        let state = SessionState {
+            session_id: session_id.clone(),
+            personality: "Fallback".to_string(),
+            ..Default::default()
+        };
+        state.save().await.unwrap();
+
+        let user_path = SessionState::user_state_path(&session_id);
+        assert!(user_path.exists());
+        assert_eq!(SessionState::active_state_path(&session_id), user_path);
+        let loaded = SessionState::load(&session_id).await.unwrap();
+        assert_eq!(loaded.personality, "Fallback");
+
+        // Later saves keep using the per-user path
+        let mut loaded = loaded;
+        loaded.reset_errors().await.unwrap();
+        assert!(user_path.exists());
+
+        SessionState::cleanup(&session_id).await.unwrap();
+        assert!(!user_path.exists());
+        std::fs::remove_dir_all(&shared_path).unwrap();
+    }
+
+    #[test]
+    fn test_detached_head_detection() {
+        let repo = tempfile::tempdir().unwrap();
+        let git_dir = repo.path().join(".git");
+        let nested = repo.path().join("src");
+        std::fs::create_dir_all(&git_dir).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_git_dir(&nested), Some(git_dir.clone()));
+
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        assert_eq!(read_detached_head(&git_dir), None);
+
+        std::fs::write(
+            git_dir.join("HEAD"),
+            "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678\n",
+        )
+        .unwrap();
+        assert_eq!(read_detached_head(&git_dir).as_deref(), Some("a1b2c3d"));
+    }
+
+    #[test]
+    fn test_git_dir_from_gitdir_file() {
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::write(
+            worktree.path().join(".git"),
+            "gitdir: ../main/.git/worktrees/feature\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_git_dir(worktree.path()),
+            Some(worktree.path().join("../main/.git/worktrees/feature"))
+        );
+    }
+
+    #[test]
+    fn test_git_operation_detection() {
+        let repo = tempfile::tempdir().unwrap();
+        let git_dir = repo.path();
+        assert_eq!(read_git_operation(git_dir), None);
+
+        std::fs::write(git_dir.join("BISECT_LOG"), "").unwrap();
+        assert_eq!(read_git_operation(git_dir), Some("bisecting"));
+
+        std::fs::write(git_dir.join("MERGE_HEAD"), "a1b2c3d\n").unwrap();
+        assert_eq!(read_git_operation(git_dir), Some("merging"));
+
+        std::fs::create_dir(git_dir.join("rebase-merge")).unwrap();
+        assert_eq!(read_git_operation(git_dir), Some("rebasing"));
+
+        std::fs::remove_dir(git_dir.join("rebase-merge")).unwrap();
+        std::fs::create_dir(git_dir.join("rebase-apply")).unwrap();
+        assert_eq!(read_git_operation(git_dir), Some("rebasing"));
+    }
+
+    #[test]
+    fn test_state_paths_live_in_platform_state_dir() {
+        let state_dir = platform::state_dir();
+        for path in [
+            SessionState::get_state_path("abc"),
+            SessionState::user_state_path("abc"),
+            SessionState::get_lock_path("abc"),
+        ] {
+            assert_eq!(path.parent(), Some(state_dir.as_path()), "{path:?}");
+        }
+        assert_eq!(
+            SessionState::get_state_path("abc").file_name().unwrap(),
+            "claude_code_personalities_activity_abc.json"
+        );
+    }
+
+    #[test]
+    fn test_foreign_file_errors() {
+        assert!(is_foreign_file_error(ErrorKind::PermissionDenied));
+        assert!(is_foreign_file_error(ErrorKind::IsADirectory));
+        assert!(!is_foreign_file_error(ErrorKind::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_removes_all_session_artifacts() {
+        let session_id = create_test_session_id();
+        let state_path = SessionState::get_state_path(&session_id);
+        let state_dir = state_path.parent().unwrap();
+        let artifacts = [
+            format!("claude_code_personalities_activity_{session_id}.json"),
+            format!("claude_code_personalities_activity_{session_id}.json.lock"),
+            format!("claude_code_personalities_activity_{session_id}.json.42.0.tmp"),
+            format!("claude_code_personalities_errors_{session_id}.count"),
+            format!("claude_code_personalities_activity_{session_id}__agent_a1.json"),
+        ];
+        // A different session whose id starts with this one must survive
+        let other = format!("claude_code_personalities_activity_{session_id}0.json");
+
+        for name in artifacts.iter().chain(std::iter::once(&other)) {
+            std::fs::write(state_dir.join(name), "{}").unwrap();
+        }
+
+        SessionState::cleanup(&session_id).await.unwrap();
+
+        for name in &artifacts {
+            assert!(!state_dir.join(name).exists(), "{name} should be removed");
+        }
+        assert!(state_dir.join(&other).exists());
+        std::fs::remove_file(state_dir.join(&other)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reset_after_error_streak_celebrates() {
+        let session_id = create_test_session_id();
+        let mut state = SessionState::load(&session_id).await.unwrap();
+
+        // Resetting with no errors is not a recovery
+        state.reset_errors().await.unwrap();
+        assert_eq!(state.celebrate_until, None);
+
+        for _ in 0..5 {
+            state.increment_errors().await.unwrap();
+        }
+        state.reset_errors().await.unwrap();
+        assert_eq!(state.error_count, 0);
+        assert!(state.celebrate_until.is_some());
+
+        // Cleanup
+        SessionState::cleanup(&session_id).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_state_persistence() {
         let session_id = create_test_session_id();
@@ -621,12 +1518,13 @@ mod tests {
         {
             let mut state = SessionState::load(&session_id).await.unwrap();
             state
-                .update_activity(
+                .update_activity_debounced(
                     Activity::Testing,
                     None,
                     None,
-                    None,
                     "Test Engineer".to_string(),
+                    true,
+                    Duration::ZERO,
                 )
                 .await
                 .unwrap();
@@ -675,22 +1573,20 @@ mod tests {
         let initial_state = SessionState::load(&session_id).await.unwrap();
         initial_state.save().await.unwrap();
 
-        // Simulate concurrent updates with delay to reduce race conditions
+        // Simulate concurrent updates
         let handles: Vec<_> = (0..3)
             .map(|i| {
                 let session_id = session_id.clone();
                 tokio::spawn(async move {
-                    // Add small delay to reduce concurrent file access
-                    tokio::time::sleep(std::time::Duration::from_millis(i * 10)).await;
-
                     let mut state = SessionState::load(&session_id).await.unwrap();
                     state
-                        .update_activity(
+                        .update_activity_debounced(
                             Activity::parse_activity(&format!("activity_{i}")),
                             None,
                             Some(format!("file_{i}.js")),
-                            None,
                             format!("Personality {i}"),
+                            true,
+                            Duration::ZERO,
                         )
                         .await
                         .unwrap();
@@ -707,14 +1603,255 @@ mod tests {
         // Load final state
         let final_state = SessionState::load(&session_id).await.unwrap();
 
-        // Should have some activity and errors
-        assert!(final_state.error_count > 0);
+        // Every update and error should have been recorded
+        assert_eq!(final_state.error_count, 3);
+        assert_eq!(final_state.total_actions, 3);
         assert_ne!(final_state.activity, Activity::Idle);
 
         // Cleanup
         SessionState::cleanup(&session_id).await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_increment_errors_are_not_lost() {
+        let session_id = create_test_session_id();
+
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let session_id = session_id.clone();
+                tokio::spawn(async move {
+                    let mut state = SessionState::load(&session_id).await.unwrap();
+                    state.increment_errors().await.unwrap();
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let final_state = SessionState::load(&session_id).await.unwrap();
+        assert_eq!(final_state.error_count, 10);
+        assert!(!SessionState::get_lock_path(&session_id).exists());
+
+        SessionState::cleanup(&session_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_busy_lock_is_not_taken_over() {
+        let session_id = create_test_session_id();
+        let lock_path = SessionState::get_lock_path(&session_id);
+
+        // A slow holder's fresh lock survives a waiter that gives up
+        std::fs::write(&lock_path, "").unwrap();
+        let waiter = StateLock::acquire(&session_id).await;
+        assert!(!waiter.held);
+        drop(waiter);
+        assert!(lock_path.exists());
+
+        // A lock abandoned long ago is replaced and released as usual
+        std::fs::File::options()
+            .write(true)
+            .open(&lock_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - LOCK_STALE_AFTER * 2)
+            .unwrap();
+        let taker = StateLock::acquire(&session_id).await;
+        assert!(taker.held);
+        drop(taker);
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn test_vcs_detection() {
+        let git_workspace = tempfile::tempdir().unwrap();
+        assert_eq!(VcsKind::detect(git_workspace.path()), VcsKind::Git);
+
+        let jj_workspace = tempfile::tempdir().unwrap();
+        std::fs::create_dir(jj_workspace.path().join(".jj")).unwrap();
+        let nested = jj_workspace.path().join("src").join("module");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(VcsKind::detect(jj_workspace.path()), VcsKind::Jujutsu);
+        // Subdirectories of a jj workspace are detected too
+        assert_eq!(VcsKind::detect(&nested), VcsKind::Jujutsu);
+
+        // A stray `.jj` file is not a workspace
+        let not_jj = tempfile::tempdir().unwrap();
+        std::fs::write(not_jj.path().join(".jj"), "").unwrap();
+        assert_eq!(VcsKind::detect(not_jj.path()), VcsKind::Git);
+    }
+
+    #[test]
+    fn test_mercurial_detection() {
+        let hg_repo = tempfile::tempdir().unwrap();
+        std::fs::create_dir(hg_repo.path().join(".hg")).unwrap();
+        let nested = hg_repo.path().join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(VcsKind::detect(hg_repo.path()), VcsKind::Mercurial);
+        assert_eq!(VcsKind::detect(&nested), VcsKind::Mercurial);
+
+        // `.git` alongside `.hg` keeps the git path
+        std::fs::create_dir(hg_repo.path().join(".git")).unwrap();
+        assert_eq!(VcsKind::detect(hg_repo.path()), VcsKind::Git);
+
+        // A git repository nested inside an hg one is git
+        let outer = tempfile::tempdir().unwrap();
+        std::fs::create_dir(outer.path().join(".hg")).unwrap();
+        let inner = outer.path().join("vendor");
+        std::fs::create_dir_all(inner.join(".git")).unwrap();
+        assert_eq!(VcsKind::detect(&inner), VcsKind::Git);
+    }
+
+    #[test]
+    fn test_parse_hg_status() {
+        assert_eq!(parse_hg_status(""), (false, 0));
+        assert_eq!(
+            parse_hg_status("M src/main.rs\n? notes.txt\n! removed.rs\n"),
+            (true, 3)
+        );
+    }
+
+    #[test]
+    fn test_parse_jj_bookmark() {
+        assert_eq!(parse_jj_bookmark("main"), Some("main".to_string()));
+        assert_eq!(
+            parse_jj_bookmark("feature/login main\n"),
+            Some("feature/login".to_string())
+        );
+        assert_eq!(parse_jj_bookmark("main*"), Some("main".to_string()));
+        assert_eq!(parse_jj_bookmark(""), None);
+        assert_eq!(parse_jj_bookmark("  \n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_recent_activities_capped_in_order() {
+        let session_id = create_test_session_id();
+        let mut state = SessionState::load(&session_id).await.unwrap();
+
+        let activities = Activity::all();
+        for activity in activities.iter().take(RECENT_ACTIVITIES_LIMIT + 3) {
+            state
+                .update_activity_debounced(
+                    activity.clone(),
+                    None,
+                    None,
+                    "Test".to_string(),
+                    true,
+                    Duration::ZERO,
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(state.recent_activities.len(), RECENT_ACTIVITIES_LIMIT);
+        assert_eq!(
+            state.recent_activities,
+            activities[3..RECENT_ACTIVITIES_LIMIT + 3].to_vec()
+        );
+
+        SessionState::cleanup(&session_id).await.unwrap();
+    }
+
+    #[test]
+    fn test_subagent_state_paths_are_isolated() {
+        let main = SessionState::resolve_session_id(Some("abc".to_string()), None, None);
+        let agent_a = SessionState::resolve_session_id(Some("abc".to_string()), None, Some("a1"));
+        let agent_b = SessionState::resolve_session_id(Some("abc".to_string()), None, Some("b2"));
+
+        assert_eq!(main, "abc");
+        assert_ne!(
+            SessionState::get_state_path(&agent_a),
+            SessionState::get_state_path(&agent_b)
+        );
+        assert_ne!(
+            SessionState::get_state_path(&main),
+            SessionState::get_state_path(&agent_a)
+        );
+
+        // Without its own session id, a subagent is grouped under its parent
+        let orphan = SessionState::resolve_session_id(None, Some("abc".to_string()), Some("a1"));
+        assert_eq!(orphan, agent_a);
+
+        // Path separators in agent ids can't escape the state directory
+        let sneaky = SessionState::resolve_session_id(Some("abc".to_string()), None, Some("../x"));
+        assert_eq!(sneaky, "abc__agent_x");
+    }
+
+    #[test]
+    fn test_parse_porcelain_status() {
+        assert_eq!(parse_porcelain_status(""), (0, 0));
+        assert_eq!(
+            parse_porcelain_status(
+                " M src/main.rs\nM  src/lib.rs\nA  src/new.rs\n D old.rs\nR  a.rs -> b.rs\n?? notes.txt\n?? scratch/\n"
+            ),
+            (5, 2)
+        );
+        assert_eq!(parse_porcelain_status("?? only-new.rs\n"), (0, 1));
+
+        let mut state = SessionState::default();
+        state.record_porcelain_status(" M src/main.rs\n?? notes.txt\n?? todo.md\n");
+        assert_eq!(state.git_dirty, Some(true));
+        assert_eq!(state.git_dirty_count, Some(3));
+        assert_eq!(state.git_modified_count, Some(1));
+        assert_eq!(state.git_untracked_count, Some(2));
+
+        state.record_porcelain_status("");
+        assert_eq!(state.git_dirty, Some(false));
+        assert_eq!(state.git_dirty_count, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_stash_count_has_its_own_cache() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // A fresh git status check doesn't hold back the stash count
+        let mut state = SessionState {
+            git_status_checked_at: Some(now),
+            ..Default::default()
+        };
+        state.refresh_git_stash_count(None).await;
+        assert!(state.git_stash_count.is_some());
+        assert!(state.git_stash_checked_at.is_some());
+
+        // A fresh stash check keeps the cached count
+        state.git_stash_count = Some(7);
+        state.git_stash_checked_at = Some(now);
+        state.refresh_git_stash_count(None).await;
+        assert_eq!(state.git_stash_count, Some(7));
+    }
+
+    #[test]
+    fn test_parse_stash_count() {
+        assert_eq!(parse_stash_count(""), 0);
+        assert_eq!(
+            parse_stash_count("stash@{0}: WIP on main: 1a2b3c4 Fix parser\n"),
+            1
+        );
+        assert_eq!(
+            parse_stash_count(
+                "stash@{0}: WIP on main: 1a2b3c4 Fix parser\nstash@{1}: On feature/x: experiment\n\n"
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn test_parse_submodule_name() {
+        // Inside a submodule: superproject first, then the submodule's top level
+        assert_eq!(
+            parse_submodule_name("/work/mono\n/work/mono/vendor/libfoo\n"),
+            Some("libfoo".to_string())
+        );
+
+        // Regular repository: only the top level is printed
+        assert_eq!(parse_submodule_name("/work/mono\n"), None);
+        assert_eq!(parse_submodule_name(""), None);
+    }
+
     #[tokio::test]
     async fn test_invalid_json_handling() {
         let session_id = create_test_session_id();
@@ -0,0 +1,147 @@
+//! Opt-in local usage counters
+//!
+//! With `collect_stats` enabled, every tool hook tallies the activity and
+//! personality it settled on in `ccp-stats.json` beside the preferences file.
+//! The counts never leave the machine; `claude-code-personalities stats` prints
+//! them. Reads and writes are best effort, so concurrent hooks may occasionally
+//! drop a count, but writes are atomic and a file that fails to parse is left
+//! alone rather than replaced by a fresh set of counts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::atomic_write::write_atomically_blocking;
+use crate::config::PersonalityPreferences;
+use crate::types::Activity;
+
+/// File name of the stats file, stored beside the preferences file
+pub const STATS_FILE_NAME: &str = "ccp-stats.json";
+
+/// How many times each activity and personality was shown
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    /// Count per activity name, e.g. `Coding`
+    pub activities: BTreeMap<String, u64>,
+    /// Count per personality, e.g. `ლ(╹◡╹ლ) Cowder`
+    pub personalities: BTreeMap<String, u64>,
+}
+
+impl UsageStats {
+    /// Count one showing of `activity` with `personality`
+    pub fn record(&mut self, activity: &Activity, personality: &str) {
+        *self.activities.entry(activity.to_string()).or_default() += 1;
+        *self
+            .personalities
+            .entry(personality.to_string())
+            .or_default() += 1;
+    }
+
+    /// Entries of `counts`, most frequent first (ties in name order)
+    #[must_use]
+    pub fn ranked(counts: &BTreeMap<String, u64>) -> Vec<(&str, u64)> {
+        let mut ranked: Vec<(&str, u64)> = counts
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        ranked
+    }
+
+    /// Read the stats from `path`, starting empty if it is missing or unreadable
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        Self::read(path).unwrap_or_default()
+    }
+
+    /// Read the stats from `path`, starting empty only if there is no file yet
+    fn read(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write the stats to `path`
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_atomically_blocking(path, &serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Location of the stats file, beside the preferences file
+#[must_use]
+pub fn stats_path() -> Option<PathBuf> {
+    PersonalityPreferences::get_preferences_path()
+        .ok()
+        .map(|path| path.with_file_name(STATS_FILE_NAME))
+}
+
+/// Count one showing of `activity` with `personality`. Failures are ignored.
+pub fn record_usage(activity: &Activity, personality: &str) {
+    if let Some(path) = stats_path() {
+        let _ = record_usage_at(&path, activity, personality);
+    }
+}
+
+/// Add one showing to the stats file at `path`. A file that exists but doesn't
+/// parse (e.g. one caught mid-write) is never overwritten.
+fn record_usage_at(path: &Path, activity: &Activity, personality: &str) -> std::io::Result<()> {
+    let mut stats = UsageStats::read(path)?;
+    stats.record(activity, personality);
+    stats.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_activity_is_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATS_FILE_NAME);
+
+        record_usage_at(&path, &Activity::Coding, "ლ(╹◡╹ლ) Cowder").unwrap();
+        record_usage_at(&path, &Activity::Coding, "ლ(╹◡╹ლ) Cowder").unwrap();
+        record_usage_at(&path, &Activity::Reading, "φ(．．) Documentation Writer").unwrap();
+
+        let stats = UsageStats::load(&path);
+        assert_eq!(stats.activities.get("Coding"), Some(&2));
+        assert_eq!(stats.activities.get("Reading"), Some(&1));
+        assert_eq!(stats.personalities.get("ლ(╹◡╹ლ) Cowder"), Some(&2));
+    }
+
+    #[test]
+    fn test_ranked_orders_by_count() {
+        let mut stats = UsageStats::default();
+        stats.record(&Activity::Reading, "a");
+        stats.record(&Activity::Coding, "b");
+        stats.record(&Activity::Coding, "b");
+        assert_eq!(
+            UsageStats::ranked(&stats.activities),
+            vec![("Coding", 2), ("Reading", 1)]
+        );
+    }
+
+    #[test]
+    fn test_missing_or_corrupt_file_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATS_FILE_NAME);
+        assert_eq!(UsageStats::load(&path), UsageStats::default());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(UsageStats::load(&path), UsageStats::default());
+    }
+
+    #[test]
+    fn test_unparsable_file_is_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATS_FILE_NAME);
+        let torn = r#"{"activities":{"Coding":41"#;
+        std::fs::write(&path, torn).unwrap();
+
+        assert!(record_usage_at(&path, &Activity::Coding, "a").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), torn);
+    }
+}
@@ -0,0 +1,353 @@
+//! Output encodings for the rendered statusline
+//!
+//! The renderer always produces ANSI-colored text. A formatter translates that
+//! into whatever the host status bar understands: ANSI as-is for Claude Code,
+//! plain text, Pango markup for Waybar and i3blocks, or tmux style tags.
+
+use super::tmux;
+use crate::theme::colors::Color;
+
+/// Output encodings accepted by `--statusline --format`
+pub const FORMAT_NAMES: [&str; 4] = ["plain", "ansi", "pango", "tmux"];
+
+/// Translates ANSI-colored statusline text into another encoding
+pub trait StatuslineFormatter {
+    fn format(&self, ansi: &str) -> String;
+}
+
+/// Which encoding to print the statusline in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// No colors or escape sequences
+    Plain,
+    /// ANSI escape sequences, as Claude Code expects
+    #[default]
+    Ansi,
+    /// Pango markup, e.g. `<span foreground="#ff79c6">`
+    Pango,
+    /// tmux style tags, e.g. `#[fg=#ff79c6]`
+    Tmux,
+}
+
+impl OutputFormat {
+    /// The formatter implementing this encoding
+    #[must_use]
+    pub fn formatter(self) -> &'static dyn StatuslineFormatter {
+        match self {
+            Self::Plain => &PlainFormatter,
+            Self::Ansi => &AnsiFormatter,
+            Self::Pango => &PangoFormatter,
+            Self::Tmux => &TmuxFormatter,
+        }
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "plain" => Ok(Self::Plain),
+            "ansi" => Ok(Self::Ansi),
+            "pango" => Ok(Self::Pango),
+            "tmux" => Ok(Self::Tmux),
+            _ => Err(format!(
+                "Unknown output format: {s} (valid formats: {})",
+                FORMAT_NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Passes ANSI output through unchanged
+pub struct AnsiFormatter;
+
+impl StatuslineFormatter for AnsiFormatter {
+    fn format(&self, ansi: &str) -> String {
+        ansi.to_string()
+    }
+}
+
+/// Strips every escape sequence
+pub struct PlainFormatter;
+
+impl StatuslineFormatter for PlainFormatter {
+    fn format(&self, ansi: &str) -> String {
+        strip_ansi(ansi)
+    }
+}
+
+/// The text of `ansi` with every escape sequence removed
+pub(crate) fn strip_ansi(ansi: &str) -> String {
+    AnsiSegments::new(ansi)
+        .filter_map(|segment| match segment {
+            AnsiSegment::Text(text) => Some(text),
+            AnsiSegment::Sgr(_) => None,
+        })
+        .collect()
+}
+
+/// Emits tmux `#[...]` style tags
+pub struct TmuxFormatter;
+
+impl StatuslineFormatter for TmuxFormatter {
+    fn format(&self, ansi: &str) -> String {
+        tmux::ansi_to_tmux(ansi)
+    }
+}
+
+/// Emits Pango markup, one `<span>` per styled run of text
+pub struct PangoFormatter;
+
+impl StatuslineFormatter for PangoFormatter {
+    fn format(&self, ansi: &str) -> String {
+        let mut style = PangoStyle::default();
+        let mut output = String::with_capacity(ansi.len());
+
+        for segment in AnsiSegments::new(ansi) {
+            match segment {
+                AnsiSegment::Sgr(params) => style.apply_sgr(params),
+                AnsiSegment::Text(text) => {
+                    let attributes = style.attributes();
+                    if attributes.is_empty() {
+                        output.push_str(&escape_pango(text));
+                    } else {
+                        output.push_str(&format!(
+                            "<span {}>{}</span>",
+                            attributes.join(" "),
+                            escape_pango(text)
+                        ));
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Text attributes set by SGR sequences so far
+#[derive(Debug, Default)]
+struct PangoStyle {
+    foreground: Option<String>,
+    background: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl PangoStyle {
+    /// Update the style from one SGR sequence's parameters (e.g. `1;38;2;255;0;0`)
+    fn apply_sgr(&mut self, params: &str) {
+        let codes: Vec<u16> = params
+            .split(';')
+            .map(|code| code.parse().unwrap_or(0))
+            .collect();
+        let mut index = 0;
+
+        while index < codes.len() {
+            let code = codes[index];
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                39 => self.foreground = None,
+                49 => self.background = None,
+                30..=37 | 90..=97 => {
+                    let base = if code >= 90 { code - 90 + 8 } else { code - 30 };
+                    self.foreground = Some(xterm_hex(base));
+                }
+                40..=47 | 100..=107 => {
+                    let base = if code >= 100 {
+                        code - 100 + 8
+                    } else {
+                        code - 40
+                    };
+                    self.background = Some(xterm_hex(base));
+                }
+                38 | 48 => {
+                    let color = match codes.get(index + 1) {
+                        Some(5) => {
+                            let color = codes.get(index + 2).map(|&n| xterm_hex(n));
+                            index += 2;
+                            color
+                        }
+                        Some(2) => {
+                            let color = codes
+                                .get(index + 2..index + 5)
+                                .map(|rgb| format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2]));
+                            index += 4;
+                            color
+                        }
+                        _ => None,
+                    };
+                    if code == 38 {
+                        self.foreground = color;
+                    } else {
+                        self.background = color;
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+    }
+
+    /// Pango `<span>` attributes for the current style
+    fn attributes(&self) -> Vec<String> {
+        let mut attributes = Vec::new();
+        if let Some(color) = &self.foreground {
+            attributes.push(format!("foreground=\"{color}\""));
+        }
+        if let Some(color) = &self.background {
+            attributes.push(format!("background=\"{color}\""));
+        }
+        if self.bold {
+            attributes.push("weight=\"bold\"".to_string());
+        }
+        if self.dim {
+            attributes.push("alpha=\"60%\"".to_string());
+        }
+        if self.italic {
+            attributes.push("style=\"italic\"".to_string());
+        }
+        if self.underline {
+            attributes.push("underline=\"single\"".to_string());
+        }
+        attributes
+    }
+}
+
+/// Escape the characters Pango markup treats specially
+fn escape_pango(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Hex color of an xterm 256-color palette index
+fn xterm_hex(index: u16) -> String {
+    let (r, g, b) = Color::terminal_256_to_rgb(u8::try_from(index).unwrap_or(u8::MAX));
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// A run of plain text or the parameters of one SGR (`ESC [ ... m`) sequence
+#[derive(Debug, PartialEq)]
+pub(crate) enum AnsiSegment<'a> {
+    Text(&'a str),
+    Sgr(&'a str),
+}
+
+/// Splits ANSI text into text runs and SGR sequences; other escapes are dropped
+pub(crate) struct AnsiSegments<'a> {
+    rest: &'a str,
+}
+
+impl<'a> AnsiSegments<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self { rest: input }
+    }
+}
+
+impl<'a> Iterator for AnsiSegments<'a> {
+    type Item = AnsiSegment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let Some(start) = self.rest.find('\u{1b}') else {
+                let text = self.rest;
+                self.rest = "";
+                return Some(AnsiSegment::Text(text));
+            };
+            if start > 0 {
+                let (text, tail) = self.rest.split_at(start);
+                self.rest = tail;
+                return Some(AnsiSegment::Text(text));
+            }
+
+            // ESC [ params m
+            let Some(sequence) = self.rest.strip_prefix("\u{1b}[") else {
+                self.rest = &self.rest[1..];
+                continue;
+            };
+            let Some(end) = sequence.find(|c: char| !(c.is_ascii_digit() || c == ';')) else {
+                self.rest = "";
+                return None;
+            };
+            if sequence[end..].starts_with('m') {
+                self.rest = &sequence[end + 1..];
+                return Some(AnsiSegment::Sgr(&sequence[..end]));
+            }
+            // Not a color sequence; drop the escape and keep going
+            self.rest = &sequence[end..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\u{1b}[1;38;2;255;121;198mOpus\u{1b}[0m \u{1b}[38;5;244m<main>\u{1b}[0m";
+
+    #[test]
+    fn test_plain_strips_colors() {
+        assert_eq!(
+            OutputFormat::Plain.formatter().format(SAMPLE),
+            "Opus <main>"
+        );
+        assert_eq!(PlainFormatter.format("no colors"), "no colors");
+    }
+
+    #[test]
+    fn test_pango_markup() {
+        assert_eq!(
+            OutputFormat::Pango.formatter().format(SAMPLE),
+            "<span foreground=\"#ff79c6\" weight=\"bold\">Opus</span> \
+             <span foreground=\"#808080\">&lt;main&gt;</span>"
+        );
+        assert_eq!(
+            PangoFormatter.format("\u{1b}[31mred\u{1b}[39m & plain"),
+            "<span foreground=\"#cd0000\">red</span> &amp; plain"
+        );
+    }
+
+    #[test]
+    fn test_ansi_and_tmux_formats() {
+        assert_eq!(OutputFormat::Ansi.formatter().format(SAMPLE), SAMPLE);
+        assert_eq!(
+            OutputFormat::Tmux.formatter().format(SAMPLE),
+            tmux::ansi_to_tmux(SAMPLE)
+        );
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        for name in FORMAT_NAMES {
+            assert!(name.parse::<OutputFormat>().is_ok(), "{name}");
+        }
+        assert_eq!("Pango".parse(), Ok(OutputFormat::Pango));
+        assert!("html".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_xterm_palette() {
+        assert_eq!(xterm_hex(244), "#808080");
+        assert_eq!(xterm_hex(226), "#ffff00");
+        assert_eq!(xterm_hex(15), "#ffffff");
+    }
+}
@@ -1,37 +1,143 @@
+pub mod format;
 pub mod personality;
+pub mod tmux;
 
 use anyhow::Result;
+use chrono::Timelike;
 use colored::Colorize;
-use regex::Regex;
 use serde::Deserialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::io::{self, Read, Write};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use unicode_width::UnicodeWidthStr;
 
-use crate::config::{PersonalityPreferences, StatuslineSection};
-use crate::icons::{ICON_FOLDER, ICON_GIT_BRANCH, get_activity_icon, get_model_icon};
-use crate::state::SessionState;
+use crate::config::preferences::DEFAULT_CLOCK_FORMAT;
+use crate::config::{
+    GitElement, GitRunFrom, GitStatusStyle, ModelNameStyle, PersonalityPreferences, SeparatorStyle,
+    StatuslineSection,
+};
+use crate::debug_log::{self, DebugRecord};
+use crate::icons::{
+    ICON_BOLT, ICON_CALENDAR_CHECK, ICON_CLOCK, ICON_FOLDER, ICON_GIT_BRANCH, ICON_WARNING,
+    get_activity_trail_icon_for_mode, get_model_icon_for_mode, get_ui_icon, model_family,
+    resolve_activity_icon,
+};
+use crate::input;
+use crate::kaomoji::{BOOTING_UP, CELEBRATING, RESTING};
+use crate::state::{SessionState, find_repo_root};
+use crate::statusline::format::{OutputFormat, strip_ansi};
+use crate::streak;
+use crate::theme::Theme;
+use crate::theme::colors::Color;
+use crate::types::Activity;
 use crate::version::VersionManager;
 
 #[derive(Debug, Deserialize)]
 pub struct ClaudeInput {
     pub session_id: Option<String>,
+    /// Session of the main agent when rendering for a subagent
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+    /// Subagent identifier, used to give subagents separate state
+    #[serde(default)]
+    pub agent_id: Option<String>,
     pub model: Option<ModelInfo>,
     pub workspace: Option<WorkspaceInfo>,
+    #[serde(default)]
+    pub context_window: Option<ContextWindowInfo>,
+    #[serde(default)]
+    pub exceeds_200k_tokens: Option<bool>,
 }
 
+/// Context window usage reported by Claude Code (all fields optional across versions)
 #[derive(Debug, Deserialize)]
+pub struct ContextWindowInfo {
+    pub context_window_size: Option<u64>,
+    pub total_input_tokens: Option<u64>,
+    pub used_percentage: Option<f64>,
+}
+
+impl ClaudeInput {
+    /// Percentage of the context window in use, if the input carries enough to tell
+    #[must_use]
+    pub fn context_usage_percent(&self) -> Option<u8> {
+        if let Some(window) = &self.context_window {
+            if let Some(percentage) = window.used_percentage {
+                return Some(percentage.clamp(0.0, 100.0).round() as u8);
+            }
+            if let (Some(used), Some(size)) =
+                (window.total_input_tokens, window.context_window_size)
+                && size > 0
+            {
+                let percentage = (used as f64 / size as f64 * 100.0).clamp(0.0, 100.0);
+                return Some(percentage.round() as u8);
+            }
+        }
+
+        // Older inputs only flag when the window is effectively full
+        match self.exceeds_200k_tokens {
+            Some(true) => Some(100),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(from = "RawModelInfo")]
 pub struct ModelInfo {
     pub display_name: Option<String>,
 }
 
+/// `model` as Claude Code might send it: normally `{"display_name": "Opus"}`, but a
+/// bare `"Opus"` is accepted too, and any other shape yields no name rather than
+/// failing the whole input
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawModelInfo {
+    Name(String),
+    Object {
+        #[serde(default)]
+        display_name: Option<serde_json::Value>,
+    },
+    Other(serde::de::IgnoredAny),
+}
+
+impl From<RawModelInfo> for ModelInfo {
+    fn from(raw: RawModelInfo) -> Self {
+        let display_name = match raw {
+            RawModelInfo::Name(name) => Some(name),
+            RawModelInfo::Object { display_name } => display_name
+                .as_ref()
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+            RawModelInfo::Other(_) => None,
+        };
+        Self { display_name }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WorkspaceInfo {
     pub current_dir: Option<String>,
     pub project_dir: Option<String>,
 }
 
-/// Run the statusline generator, reading JSON from stdin and outputting formatted statusline.
+impl WorkspaceInfo {
+    /// Directory to run git commands in, per the `git_run_from` preference
+    #[must_use]
+    pub fn git_dir(&self, run_from: GitRunFrom) -> Option<&str> {
+        let current = self.current_dir.as_deref();
+        match run_from {
+            GitRunFrom::CurrentDir => current,
+            GitRunFrom::ProjectDir => self.project_dir.as_deref().or(current),
+        }
+    }
+}
+
+/// Run the statusline generator, reading JSON from stdin and printing the statusline
+/// in `format`.
 ///
 /// # Errors
 ///
@@ -40,7 +146,7 @@ pub struct WorkspaceInfo {
 /// - The input JSON is malformed or cannot be parsed
 /// - Session state cannot be loaded from disk
 /// - Personality preferences cannot be loaded
-pub async fn run_statusline() -> Result<()> {
+pub async fn run_statusline(format: OutputFormat) -> Result<()> {
     use anyhow::Context;
     use colored::control;
 
@@ -48,10 +154,7 @@ pub async fn run_statusline() -> Result<()> {
     control::set_override(true);
 
     // Read JSON from stdin
-    let mut input = String::new();
-    io::stdin()
-        .read_to_string(&mut input)
-        .with_context(|| "Failed to read input from stdin")?;
+    let input = input::read_stdin().with_context(|| "Failed to read input from stdin")?;
 
     if input.trim().is_empty() {
         return Err(anyhow::anyhow!("No input received from Claude Code"))
@@ -67,11 +170,14 @@ pub async fn run_statusline() -> Result<()> {
         format!("Failed to parse JSON input from Claude Code. Received: {preview}")
     })?;
 
-    // Use a consistent fallback when session_id is missing
-    let session_id = claude_input.session_id.unwrap_or_else(|| {
-        // Use a predictable session ID for the current Claude session
-        std::env::var("CLAUDE_SESSION_ID").unwrap_or_else(|_| "claude_current".to_string())
-    });
+    let context_usage = claude_input.context_usage_percent();
+
+    // Use a consistent fallback when session_id is missing, isolating subagents
+    let session_id = SessionState::resolve_session_id(
+        claude_input.session_id,
+        claude_input.parent_session_id,
+        claude_input.agent_id.as_deref(),
+    );
     let model_name = claude_input
         .model
         .and_then(|m| m.display_name)
@@ -81,22 +187,37 @@ pub async fn run_statusline() -> Result<()> {
     let mut state = SessionState::load(&session_id)
         .await
         .with_context(|| format!("Failed to load session state for session '{session_id}'"))?;
-    let prefs = PersonalityPreferences::load_or_default()
+    // Project-local config in the workspace overrides the global preferences
+    let workspace_dirs: Vec<&str> = claude_input
+        .workspace
+        .iter()
+        .flat_map(|w| [w.current_dir.as_deref(), w.project_dir.as_deref()])
+        .flatten()
+        .collect();
+    let mut prefs = PersonalityPreferences::load_or_default_for_workspace(&workspace_dirs)
         .await
         .with_context(|| "Failed to load personality preferences")?;
+    apply_theme_override(&mut prefs, env_override(THEME_OVERRIDE_VAR));
+    apply_quiet_hours(&mut prefs, chrono::Local::now().hour());
 
-    // Get current directory from workspace for git operations
+    // Get the workspace directory for git operations
     let current_dir = claude_input
         .workspace
         .as_ref()
-        .and_then(|w| w.current_dir.as_deref());
+        .and_then(|w| w.git_dir(prefs.git_run_from));
 
     // Refresh git branch if enabled (with caching to avoid performance overhead)
     // This runs git commands in the correct project directory
-    if prefs.show_git && prefs.show_git_branch {
-        if let Some(dir) = current_dir {
-            state.refresh_git_branch(dir).await;
-        }
+    if prefs.show_git
+        && prefs.show_git_branch
+        && let Some(dir) = current_dir
+    {
+        state.refresh_git_branch(dir).await;
+    }
+
+    // Refresh the stash count (cached separately from git status)
+    if prefs.show_git && prefs.show_git_stash {
+        state.refresh_git_stash_count(current_dir).await;
     }
 
     // Refresh git status if enabled (with caching to avoid performance overhead)
@@ -130,21 +251,128 @@ pub async fn run_statusline() -> Result<()> {
         None
     };
 
+    // Screenshot overrides change only what is shown, never the saved state
+    let display_state = forced_display_state(
+        &state,
+        env_override(FORCE_PERSONALITY_VAR),
+        env_override(FORCE_ACTIVITY_VAR),
+    );
+
     // Use static renderer
     let statusline = build_statusline(
-        &state,
+        &display_state,
         &model_name,
         &prefs,
         claude_input.workspace.as_ref(),
         update_available.as_deref(),
+        context_usage,
     );
 
-    print!("{statusline}");
+    print!("{}", format.formatter().format(&statusline));
     io::stdout().flush().ok();
 
+    debug_log::log(&DebugRecord {
+        activity: Some(state.activity.to_string()),
+        personality: Some(state.personality.clone()),
+        ..DebugRecord::new("statusline", &session_id)
+    });
+
+    Ok(())
+}
+
+/// Print the statusline in tmux format from persisted state only.
+///
+/// Skips stdin, git, and update checks so it stays fast enough for tmux's
+/// `status-right`, and never fails: missing preferences fall back to defaults.
+///
+/// # Errors
+///
+/// This function only returns an error if stdout cannot be written to.
+pub async fn run_tmux_statusline() -> Result<()> {
+    colored::control::set_override(true);
+
+    let session_id = SessionState::resolve_session_id(None, None, None);
+    let state = SessionState::load(&session_id)
+        .await
+        .unwrap_or_else(|_| SessionState {
+            session_id: session_id.clone(),
+            ..Default::default()
+        });
+    let mut prefs = PersonalityPreferences::load_or_default()
+        .await
+        .unwrap_or_default();
+    apply_quiet_hours(&mut prefs, chrono::Local::now().hour());
+
+    let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
+    print!("{}", tmux::ansi_to_tmux(&statusline));
+    io::stdout().flush()?;
+
     Ok(())
 }
 
+/// How often `--watch` re-reads the session state
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Re-render the statusline whenever a session's persisted state changes (debug loop).
+///
+/// Each render replaces the current terminal line. Exits on Ctrl-C, restoring the cursor.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - Personality preferences cannot be loaded
+/// - Session state cannot be loaded from disk
+/// - The terminal cannot be written to
+pub async fn run_watch(session_id: &str) -> Result<()> {
+    use anyhow::Context;
+    use crossterm::{cursor, execute};
+
+    let prefs = PersonalityPreferences::load_or_default()
+        .await
+        .with_context(|| "Failed to load personality preferences")?;
+
+    let mut stdout = io::stdout();
+    execute!(stdout, cursor::Hide).with_context(|| "Failed to hide cursor")?;
+
+    let mut previous: Option<SessionState> = None;
+    let mut was_quiet = None;
+    // One listener for the whole watch, so a Ctrl-C during a load or render isn't lost
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let result = loop {
+        let state = match SessionState::load(session_id).await {
+            Ok(state) => state,
+            Err(e) => break Err(e),
+        };
+
+        // A watch can outlast the quiet hours, so re-render when they start or end too
+        let quiet = prefs.in_quiet_hours(chrono::Local::now().hour());
+        if state_changed(previous.as_ref(), &state) || was_quiet != Some(quiet) {
+            let mut prefs = prefs.clone();
+            prefs.use_colors &= !quiet;
+            let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
+            print!("\r\u{1b}[2K{statusline}");
+            stdout.flush().ok();
+            previous = Some(state);
+            was_quiet = Some(quiet);
+        }
+
+        tokio::select! {
+            _ = &mut ctrl_c => break Ok(()),
+            () = tokio::time::sleep(WATCH_INTERVAL) => {}
+        }
+    };
+
+    execute!(stdout, cursor::Show).ok();
+    println!();
+    result.with_context(|| format!("Failed to watch session state for session '{session_id}'"))
+}
+
+/// Whether the state differs from the last rendered one (always true for the first render)
+fn state_changed(previous: Option<&SessionState>, current: &SessionState) -> bool {
+    previous != Some(current)
+}
+
 #[must_use]
 pub fn build_statusline(
     state: &SessionState,
@@ -152,61 +380,223 @@ pub fn build_statusline(
     prefs: &PersonalityPreferences,
     workspace: Option<&WorkspaceInfo>,
     update_available: Option<&str>,
+    context_usage: Option<u8>,
 ) -> String {
-    let mut parts = Vec::new();
+    let render_group = |right_aligned: Option<bool>| {
+        let mut parts = Vec::new();
+
+        // Iterate over section order from preferences
+        for section in &prefs.display_order() {
+            if right_aligned
+                .is_some_and(|right| prefs.right_align_sections.contains(section) != right)
+            {
+                continue;
+            }
+            let section_text = render_section(
+                section,
+                state,
+                model_name,
+                prefs,
+                workspace,
+                update_available,
+                context_usage,
+            );
+
+            add_section_to_parts(&mut parts, section, section_text, prefs);
+        }
 
-    // Iterate over section order from preferences
-    for section in &prefs.section_order {
-        let section_text = match section {
-            StatuslineSection::Personality => render_personality_section(state, prefs),
-            StatuslineSection::Directory => render_directory_section(workspace, prefs),
-            StatuslineSection::Git => render_git_section(state, prefs),
-            StatuslineSection::Activity => render_activity_section(state, prefs),
-            StatuslineSection::Model => render_model_section(model_name, prefs),
-            StatuslineSection::UpdateAvailable => render_update_section(update_available, prefs),
-            StatuslineSection::DebugInfo => render_debug_section(state, prefs),
-        };
+        join_parts(&parts, prefs)
+    };
+
+    let width = if prefs.right_align_sections.is_empty() {
+        None
+    } else {
+        terminal_width()
+    };
+    let statusline = match width {
+        Some(width) => align_right(&render_group(Some(false)), &render_group(Some(true)), width),
+        // Without a known width, keep every section inline in its configured order
+        None => render_group(None),
+    };
+
+    finish_statusline(statusline, prefs, no_color_requested())
+}
+
+/// Detect the terminal width from `$COLUMNS`, falling back to querying the terminal
+fn terminal_width() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse::<usize>().ok())
+        .filter(|&columns| columns > 0)
+        .or_else(|| {
+            crossterm::terminal::size()
+                .ok()
+                .map(|(columns, _)| usize::from(columns))
+                .filter(|&columns| columns > 0)
+        })
+}
+
+/// Join the left and right groups, padding with spaces so the right group ends at `width`.
+///
+/// At least one space is kept between the groups when they don't fit.
+fn align_right(left: &str, right: &str, width: usize) -> String {
+    if right.is_empty() {
+        return left.to_string();
+    }
+    if left.is_empty() {
+        let padding = width.saturating_sub(strip_ansi_display_width(right));
+        return format!("{}{right}", " ".repeat(padding));
+    }
+
+    let used = strip_ansi_display_width(left) + strip_ansi_display_width(right);
+    let padding = width.saturating_sub(used).max(1);
+    format!("{left}{}{right}", " ".repeat(padding))
+}
 
-        add_section_to_parts(&mut parts, section_text, prefs);
+/// ANSI sequence that resets all colors and text attributes
+const ANSI_RESET: &str = "\u{1b}[0m";
+
+/// Environment variable that replaces the displayed personality (for screenshots)
+const FORCE_PERSONALITY_VAR: &str = "CCP_FORCE_PERSONALITY";
+
+/// Environment variable that replaces the displayed activity (for screenshots)
+const FORCE_ACTIVITY_VAR: &str = "CCP_FORCE_ACTIVITY";
+
+/// Environment variable that replaces the saved theme for one render, without persisting it
+const THEME_OVERRIDE_VAR: &str = "CCP_THEME";
+
+/// Non-empty value of an environment variable
+fn env_override(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Copy of `state` with forced personality and activity applied for display.
+///
+/// The caller's state (and so the persisted state) is never modified.
+fn forced_display_state(
+    state: &SessionState,
+    personality: Option<String>,
+    activity: Option<String>,
+) -> Cow<'_, SessionState> {
+    let activity = activity.and_then(|name| name.parse::<Activity>().ok());
+    if personality.is_none() && activity.is_none() {
+        return Cow::Borrowed(state);
+    }
+
+    let mut forced = state.clone();
+    if let Some(personality) = personality {
+        forced.personality = personality;
+        // Keep the forced face even when the session looks idle, new, or is celebrating
+        forced.last_updated_at = None;
+        forced.session_started_at = None;
+        forced.celebrate_until = None;
+    }
+    if let Some(activity) = activity {
+        forced.activity = activity;
+    }
+    Cow::Owned(forced)
+}
+
+/// Use the theme named by `theme` for this render; unknown names keep the saved theme
+fn apply_theme_override(prefs: &mut PersonalityPreferences, theme: Option<String>) {
+    if let Some(theme) = theme.and_then(|name| name.parse::<Theme>().ok()) {
+        prefs.theme = theme;
+    }
+}
+
+/// Mute the statusline during quiet hours by rendering it without colors
+fn apply_quiet_hours(prefs: &mut PersonalityPreferences, hour: u32) {
+    if prefs.in_quiet_hours(hour) {
+        prefs.use_colors = false;
+    }
+}
+
+/// Whether the user asked for plain output via the `NO_COLOR` convention
+fn no_color_requested() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Append a trailing reset when colors are in use so they don't bleed past the statusline
+fn finish_statusline(
+    mut statusline: String,
+    prefs: &PersonalityPreferences,
+    no_color: bool,
+) -> String {
+    if prefs.display.reset_at_end && prefs.use_colors && !no_color && !statusline.is_empty() {
+        statusline.push_str(ANSI_RESET);
+    }
+    statusline
+}
+
+/// Render a single statusline section, returning `None` when it is hidden or empty
+fn render_section(
+    section: &StatuslineSection,
+    state: &SessionState,
+    model_name: &str,
+    prefs: &PersonalityPreferences,
+    workspace: Option<&WorkspaceInfo>,
+    update_available: Option<&str>,
+    context_usage: Option<u8>,
+) -> Option<String> {
+    match section {
+        StatuslineSection::Personality => render_personality_section(state, prefs),
+        StatuslineSection::Directory => render_directory_section(workspace, state, prefs),
+        StatuslineSection::Git => render_git_section(state, prefs),
+        StatuslineSection::Activity => render_activity_section(state, prefs),
+        StatuslineSection::Model => render_model_section(model_name, prefs),
+        StatuslineSection::UpdateAvailable => render_update_section(update_available, prefs),
+        StatuslineSection::ActivityTrail => render_activity_trail_section(state, prefs),
+        StatuslineSection::ActionCount => render_action_count_section(state, prefs),
+        StatuslineSection::ContextUsage => render_context_usage_section(context_usage, prefs),
+        StatuslineSection::DebugInfo => render_debug_section(state, prefs),
+        StatuslineSection::Clock => render_clock_section(prefs, &chrono::Local::now()),
+        StatuslineSection::DayStreak => {
+            render_day_streak_section(prefs, prefs.show_day_streak.then(streak::current_streak))
+        }
+        StatuslineSection::Errors => render_errors_section(state, prefs),
     }
+}
 
-    parts.join("")
+/// Last component of a path, accepting both `/` and `\` separators regardless of host OS
+/// (Claude on Windows and WSL can report paths like `C:\Users\me\proj`).
+fn path_basename(path: &str) -> Option<&str> {
+    path.trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty() && !name.ends_with(':'))
 }
 
 /// Format workspace information for display in statusline
 fn format_workspace_info(workspace: &WorkspaceInfo, prefs: &PersonalityPreferences) -> String {
     let mut workspace_parts = Vec::new();
 
-    // Add folder icon if using icons
-    if prefs.show_directory_icon {
-        workspace_parts.push(ICON_FOLDER.to_string());
+    // Add folder icon if using icons; it has its own color so it can sit muted next to the name
+    let folder_icon = get_ui_icon(ICON_FOLDER, prefs.icon_mode);
+    if prefs.show_directory_icon && !folder_icon.is_empty() {
+        workspace_parts.push(if prefs.use_colors {
+            prefs.theme.apply_directory_icon(folder_icon)
+        } else {
+            folder_icon.to_string()
+        });
     }
 
     // Only add directory name if label is enabled
     if prefs.show_directory_label {
         // Prefer project name from project_dir, fallback to current_dir
-        if let Some(project_dir) = &workspace.project_dir {
-            if let Some(project_name) = std::path::Path::new(project_dir).file_name() {
-                workspace_parts.push(project_name.to_string_lossy().to_string());
-            }
-        } else if let Some(current_dir) = &workspace.current_dir
-            && let Some(dir_name) = std::path::Path::new(current_dir).file_name()
-        {
-            workspace_parts.push(dir_name.to_string_lossy().to_string());
+        let dir_name = match &workspace.project_dir {
+            Some(project_dir) => path_basename(project_dir),
+            None => workspace.current_dir.as_deref().and_then(path_basename),
+        };
+        if let Some(dir_name) = dir_name {
+            workspace_parts.push(if prefs.use_colors {
+                prefs.theme.apply_directory(dir_name)
+            } else {
+                dir_name.to_string()
+            });
         }
     }
 
-    if workspace_parts.is_empty() {
-        return String::new();
-    }
-
-    let workspace_text = workspace_parts.join(" ");
-
-    if prefs.use_colors {
-        prefs.theme.apply_directory(&workspace_text)
-    } else {
-        workspace_text
-    }
+    workspace_parts.join(" ")
 }
 
 /// Render the personality section
@@ -217,25 +607,96 @@ fn render_personality_section(
     if !prefs.show_personality {
         return None;
     }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let personality = displayed_personality(state, prefs, now);
     let personality_text = if prefs.use_colors {
-        prefs
-            .theme
-            .apply_personality_with_context(&state.personality, state)
+        if is_celebrating(state, now) {
+            prefs.theme.apply_success(&personality)
+        } else {
+            prefs
+                .theme
+                .apply_personality_with_context(&personality, state)
+        }
     } else {
-        state.personality.clone()
+        personality
     };
-    Some(personality_text)
+    match streak_milestone_tag(state.consecutive_actions).filter(|_| prefs.show_streak_milestones) {
+        Some(tag) if prefs.use_colors => Some(format!(
+            "{personality_text} {}",
+            prefs.theme.apply_success(&tag)
+        )),
+        Some(tag) => Some(format!("{personality_text} {tag}")),
+        None => Some(personality_text),
+    }
+}
+
+/// Action streak lengths that earn a congratulatory tag
+const STREAK_MILESTONES: [u32; 3] = [10, 20, 50];
+
+/// Tag shown next to the personality while the streak sits exactly on a milestone
+fn streak_milestone_tag(consecutive_actions: u32) -> Option<String> {
+    STREAK_MILESTONES
+        .contains(&consecutive_actions)
+        .then(|| format!("+ {consecutive_actions} streak!"))
+}
+
+/// Whether a recent recovery from an error streak is still being celebrated
+fn is_celebrating(state: &SessionState, now: u64) -> bool {
+    state.celebrate_until.is_some_and(|until| now < until)
+}
+
+/// Whether a session that hasn't done any work yet is still within its boot grace period
+fn is_booting(state: &SessionState, prefs: &PersonalityPreferences, now: u64) -> bool {
+    state.activity == Activity::Idle
+        && state
+            .session_started_at
+            .is_some_and(|started| now.saturating_sub(started) < prefs.boot_grace_seconds)
+}
+
+/// The personality to show, swapping in the celebration face right after a
+/// recovery, the booting-up face early in an idle session, and the resting face
+/// once the session has gone idle.
+///
+/// Only affects rendering; the persisted personality is left untouched.
+fn displayed_personality(state: &SessionState, prefs: &PersonalityPreferences, now: u64) -> String {
+    if is_celebrating(state, now) {
+        return CELEBRATING.personality();
+    }
+    if is_booting(state, prefs, now) {
+        return BOOTING_UP.personality();
+    }
+    let idle = prefs.idle_timeout_seconds > 0
+        && state
+            .last_updated_at
+            .is_some_and(|updated| now.saturating_sub(updated) >= prefs.idle_timeout_seconds);
+    if idle {
+        return RESTING.personality();
+    }
+    // A session that hasn't settled on a personality yet still holds the
+    // built-in fallback from `SessionState::default`
+    match prefs.default_personality() {
+        Some(personality) if state.personality == BOOTING_UP.personality() => {
+            personality.to_string()
+        }
+        _ => state.personality.clone(),
+    }
 }
 
 /// Render the directory/workspace section
 fn render_directory_section(
     workspace: Option<&WorkspaceInfo>,
+    state: &SessionState,
     prefs: &PersonalityPreferences,
 ) -> Option<String> {
     if !prefs.show_current_dir {
         return None;
     }
     let workspace = workspace?;
+    if prefs.hide_dir_when_repo_root && git_branch_shown(state, prefs) && is_repo_root(workspace) {
+        return None;
+    }
     let workspace_text = format_workspace_info(workspace, prefs);
     if workspace_text.is_empty() {
         return None;
@@ -243,93 +704,212 @@ fn render_directory_section(
     Some(workspace_text)
 }
 
+/// Whether the git section will show a branch name
+fn git_branch_shown(state: &SessionState, prefs: &PersonalityPreferences) -> bool {
+    prefs.show_git
+        && prefs.show_git_branch
+        && state
+            .git_branch
+            .as_ref()
+            .is_some_and(|branch| !branch.is_empty())
+}
+
+/// Whether the displayed directory has the same name as its repository root
+fn is_repo_root(workspace: &WorkspaceInfo) -> bool {
+    let Some(dir) = workspace
+        .project_dir
+        .as_deref()
+        .or(workspace.current_dir.as_deref())
+    else {
+        return false;
+    };
+    let root_name = find_repo_root(Path::new(dir))
+        .and_then(|root| root.to_str())
+        .and_then(path_basename);
+    root_name.is_some() && root_name == path_basename(dir)
+}
+
+/// Glyph shown before the stash entry count in the git section
+const GIT_STASH_GLYPH: &str = "\u{2691}";
+
+/// Shorten `text` to `max_width` characters by replacing its middle with an
+/// ellipsis, keeping both ends, e.g. `featur\u{2026}ption` (0 leaves it whole)
+fn truncate_middle(text: &str, max_width: usize) -> String {
+    let len = text.chars().count();
+    if max_width == 0 || len <= max_width {
+        return text.to_string();
+    }
+    let keep = max_width - 1;
+    let head: String = text.chars().take(keep.div_ceil(2)).collect();
+    let tail: String = text.chars().skip(len - keep / 2).collect();
+    format!("{head}\u{2026}{tail}")
+}
+
+/// Marker shown before the commit hash of a detached HEAD
+const DETACHED_HEAD_MARKER: &str = "@";
+
+/// Status shown after the branch: a glyph like ` \u{00b1}3`, or a word like
+/// ` dirty(3)` for users who can't rely on the glyph's color
+fn git_status_text(is_dirty: bool, state: &SessionState, prefs: &PersonalityPreferences) -> String {
+    if is_dirty && let Some(detail) = detailed_dirty_counts(state, prefs) {
+        return match prefs.git_status_style {
+            GitStatusStyle::ColorGlyph => format!(" {detail}"),
+            GitStatusStyle::TextLabel => format!(" dirty({detail})"),
+        };
+    }
+    let count = state
+        .git_dirty_count
+        .filter(|&count| is_dirty && count > 0)
+        .map(|count| format_dirty_count(count, prefs.git_dirty_count_cap));
+    match (prefs.git_status_style, is_dirty, count) {
+        (GitStatusStyle::ColorGlyph, true, count) => format!(
+            " {}{}",
+            prefs.display.git_dirty_glyph,
+            count.unwrap_or_default()
+        ),
+        (GitStatusStyle::ColorGlyph, false, _) => format!(" {}", prefs.display.git_clean_glyph),
+        (GitStatusStyle::TextLabel, true, Some(count)) => format!(" dirty({count})"),
+        (GitStatusStyle::TextLabel, true, None) => " dirty".to_string(),
+        (GitStatusStyle::TextLabel, false, _) => " clean".to_string(),
+    }
+}
+
+/// Tracked and untracked counts, e.g. `~3 +2` (zero parts left out), when
+/// `git_detailed_status` is on and the split is known
+fn detailed_dirty_counts(state: &SessionState, prefs: &PersonalityPreferences) -> Option<String> {
+    if !prefs.git_detailed_status {
+        return None;
+    }
+    let parts: Vec<String> = [
+        ('~', state.git_modified_count?),
+        ('+', state.git_untracked_count?),
+    ]
+    .into_iter()
+    .filter(|&(_, count)| count > 0)
+    .map(|(marker, count)| {
+        format!(
+            "{marker}{}",
+            format_dirty_count(count, prefs.git_dirty_count_cap)
+        )
+    })
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Format a dirty file count, capping it (e.g. `99+`) so the git segment stays narrow.
+///
+/// A cap of 0 shows the raw count.
+fn format_dirty_count(count: usize, cap: usize) -> String {
+    if cap > 0 && count > cap {
+        format!("{cap}+")
+    } else {
+        count.to_string()
+    }
+}
+
 /// Render the git branch section
 fn render_git_section(state: &SessionState, prefs: &PersonalityPreferences) -> Option<String> {
     if !prefs.show_git || !prefs.show_git_branch {
         return None;
     }
-    let branch = state.git_branch.as_ref()?;
+    // A detached HEAD shows its commit instead of the last branch seen
+    let detached = state
+        .git_detached_head
+        .as_ref()
+        .filter(|_| prefs.show_detached_head);
+    let branch = match detached {
+        Some(hash) => format!("{DETACHED_HEAD_MARKER} {hash}"),
+        None => state.git_branch.clone()?,
+    };
     if branch.is_empty() {
         return None;
     }
 
-    // Build git text piece by piece: icon + label + status
-    let mut git_parts = Vec::new();
-
-    // Icon
-    if prefs.show_git_icon {
-        git_parts.push(ICON_GIT_BRANCH.to_string());
-    }
-
-    // Branch name
-    let branch_display = if !branch.contains('/') {
-        format!("{} branch", branch)
+    // Build git text piece by piece, in the configured order
+    let git_icon = if prefs.show_git_icon {
+        get_ui_icon(ICON_GIT_BRANCH, prefs.icon_mode)
     } else {
-        branch.clone()
+        ""
     };
-    git_parts.push(branch_display);
-
-    // Combine icon and branch name
-    let base_text = git_parts.join(" ");
+    // Branch name, with the suffix word for plain names like `main`
+    let suffix =
+        if detached.is_none() && !branch.contains('/') && !prefs.git_branch_suffix.is_empty() {
+            Some(format!(" {}", prefs.git_branch_suffix))
+        } else {
+            None
+        };
+    let status = state.git_dirty.filter(|_| prefs.show_git_status);
+    // A detached commit hash has no branch type to color by; the color comes
+    // from the full name since truncation may cut the prefix short
+    let by_type = prefs.color_branch_by_type && detached.is_none();
+    let branch_color = prefs.theme.branch_color(&branch, by_type);
+    let branch = truncate_middle(&branch, prefs.git_branch_max_width);
 
-    // Build final text with colors and status
-    let branch_text = if prefs.use_colors {
-        let base_colored = if !branch.contains('/') {
-            let branch_part = if prefs.show_git_icon {
-                format!("{} {}", ICON_GIT_BRANCH, branch)
+    let mut git_parts = Vec::new();
+    for element in prefs.git_elements() {
+        match element {
+            GitElement::Icon if !git_icon.is_empty() => git_parts.push(if prefs.use_colors {
+                prefs.theme.apply_file(git_icon)
             } else {
-                branch.clone()
-            };
-            format!(
-                "{}{}",
-                prefs.theme.apply_file(&branch_part),
-                " branch".dimmed()
-            )
-        } else {
-            prefs.theme.apply_file(&base_text)
-        };
-
-        // Add git status indicator if enabled
-        if prefs.show_git_status {
-            if let Some(is_dirty) = state.git_dirty {
-                let status_text = if is_dirty {
-                    let count = state.git_dirty_count.unwrap_or(0);
-                    if count > 0 {
-                        prefs.theme.apply_warning(&format!(" ±{}", count))
-                    } else {
-                        prefs.theme.apply_warning(" ±")
-                    }
-                } else {
-                    prefs.theme.apply_success(" ✓")
-                };
-                format!("{base_colored}{status_text}")
+                git_icon.to_string()
+            }),
+            GitElement::Icon => {}
+            GitElement::Branch => git_parts.push(match (&suffix, prefs.use_colors) {
+                (Some(suffix), true) => {
+                    format!("{}{}", branch_color.apply(&branch), suffix.dimmed())
+                }
+                (None, true) => branch_color.apply(&branch).to_string(),
+                (suffix, false) => format!("{branch}{}", suffix.as_deref().unwrap_or("")),
+            }),
+            GitElement::Status => {
+                if let Some(is_dirty) = status {
+                    let text = git_status_text(is_dirty, state, prefs);
+                    let text = text.trim_start();
+                    git_parts.push(match (is_dirty, prefs.use_colors) {
+                        (true, true) => prefs.theme.apply_warning(text),
+                        (false, true) => prefs.theme.apply_success(text),
+                        (_, false) => text.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    let branch_text = git_parts.join(" ");
+
+    // In-progress operation, e.g. ` (rebasing)`
+    let branch_text = match state
+        .git_operation
+        .as_ref()
+        .filter(|_| prefs.show_detached_head)
+    {
+        Some(operation) => {
+            let operation_text = format!(" ({operation})");
+            if prefs.use_colors {
+                format!(
+                    "{branch_text}{}",
+                    prefs.theme.apply_warning(&operation_text)
+                )
             } else {
-                base_colored
+                format!("{branch_text}{operation_text}")
             }
-        } else {
-            base_colored
         }
-    } else {
-        // No colors - simple concatenation
-        if prefs.show_git_status {
-            if let Some(is_dirty) = state.git_dirty {
-                let status_text = if is_dirty {
-                    let count = state.git_dirty_count.unwrap_or(0);
-                    if count > 0 {
-                        format!(" ±{}", count)
-                    } else {
-                        " ±".to_string()
-                    }
-                } else {
-                    " ✓".to_string()
-                };
-                format!("{base_text}{status_text}")
+        None => branch_text,
+    };
+
+    // Stash indicator, e.g. `\u{2691}3`; nothing when the stash is empty
+    let branch_text = match state
+        .git_stash_count
+        .filter(|&count| prefs.show_git_stash && count > 0)
+    {
+        Some(count) => {
+            let stash_text = format!(" {GIT_STASH_GLYPH}{count}");
+            if prefs.use_colors {
+                format!("{branch_text}{}", prefs.theme.apply_info(&stash_text))
             } else {
-                base_text
+                format!("{branch_text}{stash_text}")
             }
-        } else {
-            base_text
         }
+        None => branch_text,
     };
 
     if branch_text.is_empty() {
@@ -345,7 +925,7 @@ fn render_activity_section(state: &SessionState, prefs: &PersonalityPreferences)
     }
 
     let activity_icon = if prefs.show_activity_icon {
-        get_activity_icon(&state.activity)
+        resolve_activity_icon(&state.activity, &prefs.activity_icons, prefs.icon_mode)
     } else {
         ""
     };
@@ -360,12 +940,15 @@ fn render_activity_section(state: &SessionState, prefs: &PersonalityPreferences)
         activity_parts.push(colored_icon);
     }
 
-    // Only show activity label text if enabled
-    if prefs.show_activity_label {
+    // Only show activity label text if enabled; compact mode lets the icon stand
+    // alone, but activities without one keep their label
+    let compact = prefs.compact_activity && !activity_icon.is_empty();
+    if prefs.show_activity_label && !compact {
+        let label = prefs.activity_label_style.label(&state.activity);
         let activity_str = if prefs.use_colors {
-            prefs.theme.apply_activity(&state.activity.to_string())
+            prefs.theme.apply_activity(&label)
         } else {
-            state.activity.to_string()
+            label
         };
         activity_parts.push(activity_str);
     }
@@ -384,15 +967,15 @@ fn render_activity_section(state: &SessionState, prefs: &PersonalityPreferences)
             }
         }
         // Otherwise check for file (for edit/read operations)
-        else if let Some(file) = &state.current_file {
-            if !file.is_empty() {
-                let file_text = if prefs.use_colors {
-                    prefs.theme.apply_file(file)
-                } else {
-                    file.clone()
-                };
-                activity_parts.push(file_text);
-            }
+        else if let Some(file) = &state.current_file
+            && !file.is_empty()
+        {
+            let file_text = if prefs.use_colors {
+                prefs.theme.apply_file(file)
+            } else {
+                file.clone()
+            };
+            activity_parts.push(file_text);
         }
     }
 
@@ -410,7 +993,7 @@ fn render_model_section(model_name: &str, prefs: &PersonalityPreferences) -> Opt
     }
 
     let model_icon = if prefs.show_model_icon {
-        get_model_icon(model_name)
+        get_model_icon_for_mode(model_name, prefs.icon_mode)
     } else {
         ""
     };
@@ -421,14 +1004,14 @@ fn render_model_section(model_name: &str, prefs: &PersonalityPreferences) -> Opt
         model_parts.push(model_icon.to_string());
     }
     if prefs.show_model_label {
-        model_parts.push(model_name.to_string());
+        model_parts.push(styled_model_name(model_name, prefs.model_name_style));
     }
 
     if model_parts.is_empty() {
         return None;
     }
 
-    let model_text = model_parts.join(" ");
+    let model_text = prefs.model_bracket_style.wrap(&model_parts.join(" "));
     let colored_model = if prefs.use_colors {
         prefs
             .theme
@@ -440,17 +1023,32 @@ fn render_model_section(model_name: &str, prefs: &PersonalityPreferences) -> Opt
     Some(colored_model)
 }
 
+/// Shorten a model name to its family or initial; unknown models keep the full name
+fn styled_model_name(model_name: &str, style: ModelNameStyle) -> String {
+    match (style, model_family(model_name)) {
+        (ModelNameStyle::Short, Some(family)) => family.to_string(),
+        (ModelNameStyle::Initials, Some(family)) => family[..1].to_string(),
+        _ => model_name.to_string(),
+    }
+}
+
 /// Render the update available section
 fn render_update_section(
     update_available: Option<&str>,
     prefs: &PersonalityPreferences,
 ) -> Option<String> {
     let version = update_available?;
-    let update_icon = "\u{f062}"; // nf-fa-arrow_up
+    let notice = prefs
+        .update_format
+        .as_deref()
+        .filter(|format| !format.is_empty())
+        .unwrap_or("v{version}")
+        .replace("{version}", version);
+    let update_icon = get_ui_icon(&prefs.update_icon, prefs.icon_mode);
     let update_text = if update_icon.is_empty() {
-        format!("v{version}")
+        notice
     } else {
-        format!("{update_icon} v{version}")
+        format!("{update_icon} {notice}")
     };
 
     let colored_update = if prefs.use_colors {
@@ -462,48 +1060,305 @@ fn render_update_section(
     Some(colored_update)
 }
 
-/// Render the debug info section
-fn render_debug_section(state: &SessionState, prefs: &PersonalityPreferences) -> Option<String> {
-    if !prefs.display.show_debug_info {
+/// Number of recent activities shown in the activity trail
+const ACTIVITY_TRAIL_LENGTH: usize = 5;
+
+/// Render the most recent activities as a compact trail of icons, oldest first
+fn render_activity_trail_section(
+    state: &SessionState,
+    prefs: &PersonalityPreferences,
+) -> Option<String> {
+    if !prefs.show_activity_trail || state.recent_activities.is_empty() {
         return None;
     }
 
-    let debug_info = format!(
-        "[E:{} C:{} S:{}]",
-        state.error_count, state.consecutive_actions, state.session_id
-    );
-
-    let debug_text = if prefs.use_colors {
-        prefs.theme.apply_separator(&debug_info)
+    let skip = state
+        .recent_activities
+        .len()
+        .saturating_sub(ACTIVITY_TRAIL_LENGTH);
+    let trail: Vec<&str> = state.recent_activities[skip..]
+        .iter()
+        .map(|activity| get_activity_trail_icon_for_mode(activity, prefs.icon_mode))
+        .collect();
+    let trail_text = trail.join(" ");
+
+    let colored_trail = if prefs.use_colors {
+        prefs.theme.apply_activity(&trail_text)
     } else {
-        debug_info
+        trail_text
     };
 
-    Some(debug_text)
+    Some(colored_trail)
 }
 
-/// Helper to add a section with proper separator handling
-fn add_section_to_parts(
-    parts: &mut Vec<String>,
-    section_text: Option<String>,
+/// Render the session action counter section
+fn render_action_count_section(
+    state: &SessionState,
     prefs: &PersonalityPreferences,
-) {
-    let Some(text) = section_text else {
-        return;
-    };
-    if text.is_empty() {
-        return;
+) -> Option<String> {
+    if !prefs.show_action_count || state.total_actions == 0 {
+        return None;
     }
 
-    if parts.is_empty() {
-        parts.push(text);
+    let count_icon = get_ui_icon(ICON_BOLT, prefs.icon_mode);
+    let count_text = if count_icon.is_empty() {
+        state.total_actions.to_string()
     } else {
-        let spacing = " ";
-        if prefs.display.show_separators {
-            let separator = if prefs.use_colors {
-                prefs.theme.apply_separator(&prefs.display.separator_char)
-            } else {
-                prefs.display.separator_char.clone()
+        format!("{count_icon} {}", state.total_actions)
+    };
+
+    let colored_count = if prefs.use_colors {
+        prefs.theme.apply_info(&count_text)
+    } else {
+        count_text
+    };
+
+    Some(colored_count)
+}
+
+fn render_clock_section(
+    prefs: &PersonalityPreferences,
+    now: &chrono::DateTime<chrono::Local>,
+) -> Option<String> {
+    if !prefs.show_clock {
+        return None;
+    }
+
+    let time = format_clock(now, &prefs.clock_format);
+    let clock_icon = get_ui_icon(ICON_CLOCK, prefs.icon_mode);
+    let clock_text = if clock_icon.is_empty() {
+        time
+    } else {
+        format!("{clock_icon} {time}")
+    };
+
+    let colored_clock = if prefs.use_colors {
+        prefs.theme.apply_info(&clock_text)
+    } else {
+        clock_text
+    };
+
+    Some(colored_clock)
+}
+
+/// Render the day streak, e.g. `3d`; hidden when disabled or there is no streak
+fn render_day_streak_section(prefs: &PersonalityPreferences, days: Option<u32>) -> Option<String> {
+    let days = days.filter(|&days| prefs.show_day_streak && days > 0)?;
+
+    let streak_icon = get_ui_icon(ICON_CALENDAR_CHECK, prefs.icon_mode);
+    let streak_text = if streak_icon.is_empty() {
+        format!("{days}d")
+    } else {
+        format!("{streak_icon} {days}d")
+    };
+
+    let colored_streak = if prefs.use_colors {
+        prefs.theme.apply_success(&streak_text)
+    } else {
+        streak_text
+    };
+
+    Some(colored_streak)
+}
+
+/// Render the recent error count, e.g. `\u{f071}3`; hidden when disabled or error-free
+fn render_errors_section(state: &SessionState, prefs: &PersonalityPreferences) -> Option<String> {
+    if !prefs.show_error_count || state.error_count == 0 {
+        return None;
+    }
+
+    let errors_text = format!(
+        "{}{}",
+        get_ui_icon(ICON_WARNING, prefs.icon_mode),
+        state.error_count
+    );
+
+    let colored_errors = if prefs.use_colors {
+        prefs.theme.apply_warning(&errors_text)
+    } else {
+        errors_text
+    };
+
+    Some(colored_errors)
+}
+
+/// Format `now` with a `strftime`-style `format`, falling back to `%H:%M` when it is invalid
+fn format_clock(now: &chrono::DateTime<chrono::Local>, format: &str) -> String {
+    use std::fmt::Write;
+
+    let mut time = String::new();
+    // Invalid specifiers surface as a formatting error rather than a panic this way
+    if write!(time, "{}", now.format(format)).is_err() {
+        time = now.format(DEFAULT_CLOCK_FORMAT).to_string();
+    }
+    time
+}
+
+/// Number of cells in the context usage gauge
+const CONTEXT_GAUGE_WIDTH: usize = 5;
+
+/// Format a context usage percentage as a compact gauge, e.g. `▓▓▓░░ 62%`
+fn format_context_gauge(percent: u8) -> String {
+    let percent = percent.min(100);
+    let filled = (usize::from(percent) * CONTEXT_GAUGE_WIDTH + 50) / 100;
+    format!(
+        "{}{} {percent}%",
+        "\u{2593}".repeat(filled),
+        "\u{2591}".repeat(CONTEXT_GAUGE_WIDTH - filled)
+    )
+}
+
+/// Render the context window usage section
+fn render_context_usage_section(
+    context_usage: Option<u8>,
+    prefs: &PersonalityPreferences,
+) -> Option<String> {
+    if !prefs.show_context_usage {
+        return None;
+    }
+    let percent = context_usage?;
+    let gauge = format_context_gauge(percent);
+
+    let colored_gauge = if prefs.use_colors {
+        match percent {
+            90.. => prefs.theme.apply_error(&gauge),
+            75.. => prefs.theme.apply_warning(&gauge),
+            _ => prefs.theme.apply_info(&gauge),
+        }
+    } else {
+        gauge
+    };
+
+    Some(colored_gauge)
+}
+
+/// Render the debug info section
+fn render_debug_section(state: &SessionState, prefs: &PersonalityPreferences) -> Option<String> {
+    if !prefs.display.show_debug_info {
+        return None;
+    }
+
+    // Mood internals help diagnose why a frustrated personality isn't decaying
+    let since_error = state
+        .mood
+        .last_error_time
+        .map(|last_error| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            format!(" LE:{}s", now.saturating_sub(last_error))
+        })
+        .unwrap_or_default();
+    let debug_info = format!(
+        "[E:{} C:{} T:{} F:{} M:{}{} S:{}]",
+        state.error_count,
+        state.consecutive_actions,
+        state.total_actions,
+        state.mood.frustration_level,
+        state.mood.momentum,
+        since_error,
+        state.session_id
+    );
+
+    let debug_text = if prefs.use_colors {
+        prefs.theme.apply_separator(&debug_info)
+    } else {
+        debug_info
+    };
+
+    Some(debug_text)
+}
+
+/// Powerline right-pointing arrow used between sections in Powerline mode
+const POWERLINE_SEPARATOR: &str = "\u{e0b0}";
+
+/// Whether sections are drawn as colored Powerline segments joined by arrows
+fn uses_powerline_segments(prefs: &PersonalityPreferences) -> bool {
+    prefs.use_colors
+        && prefs.display.show_separators
+        && prefs.display.separator_style == SeparatorStyle::Powerline
+}
+
+/// Background of the Powerline segment at `index`, alternating between the
+/// theme's separator tone and a slightly lighter blend of it
+fn powerline_background(index: usize, prefs: &PersonalityPreferences) -> Color {
+    let colors = prefs.theme.colors();
+    if index.is_multiple_of(2) {
+        colors.separator
+    } else {
+        colors.separator.lerp(&colors.personality, 0.2)
+    }
+}
+
+/// A section drawn as a Powerline segment: the arrow from the previous
+/// segment's background into this one, then the text on its own background.
+/// An `accent` arrow is drawn in the theme's info color instead.
+fn powerline_segment(
+    text: &str,
+    index: usize,
+    accent: bool,
+    prefs: &PersonalityPreferences,
+) -> String {
+    let background = powerline_background(index, prefs).background_code();
+    let arrow = index
+        .checked_sub(1)
+        .map(|previous| {
+            let arrow_color = if accent {
+                prefs.theme.colors().info
+            } else {
+                powerline_background(previous, prefs)
+            };
+            let foreground = arrow_color.foreground_code();
+            format!("{foreground}{background}{POWERLINE_SEPARATOR}\x1b[39m")
+        })
+        .unwrap_or_default();
+    // Keep the background through the section's own color resets
+    let text = text
+        .replace("\x1b[0m", &format!("\x1b[0m{background}"))
+        .replace("\x1b[49m", &background);
+    format!("{arrow}{background} {text} \x1b[0m")
+}
+
+/// Join rendered sections, closing the last Powerline segment with an arrow
+/// onto the terminal background
+fn join_parts(parts: &[String], prefs: &PersonalityPreferences) -> String {
+    let mut line = parts.join("");
+    if uses_powerline_segments(prefs) && !parts.is_empty() {
+        let last = powerline_background(parts.len() - 1, prefs).foreground_code();
+        line.push_str(&format!("{last}{POWERLINE_SEPARATOR}\x1b[0m"));
+    }
+    line
+}
+
+/// Helper to add a section with proper separator handling
+fn add_section_to_parts(
+    parts: &mut Vec<String>,
+    section: &StatuslineSection,
+    section_text: Option<String>,
+    prefs: &PersonalityPreferences,
+) {
+    let Some(text) = visible_section(section_text, prefs) else {
+        return;
+    };
+
+    let accent = prefs.display.accent_separator_before.as_ref() == Some(section);
+    if uses_powerline_segments(prefs) {
+        parts.push(powerline_segment(&text, parts.len(), accent, prefs));
+    } else if parts.is_empty() {
+        parts.push(text);
+    } else {
+        let spacing = " ";
+        if prefs.display.show_separators {
+            let separator_char = match prefs.display.separator_style {
+                _ if accent => prefs.display.accent_separator_char.as_str(),
+                SeparatorStyle::Plain => prefs.display.separator_char.as_str(),
+                SeparatorStyle::Powerline => POWERLINE_SEPARATOR,
+            };
+            let separator = match (prefs.use_colors, accent) {
+                (true, true) => prefs.theme.apply_info(separator_char),
+                (true, false) => prefs.theme.apply_separator(separator_char),
+                (false, _) => separator_char.to_string(),
             };
             parts.push(format!("{spacing}{separator}{spacing}{text}"));
         } else {
@@ -512,6 +1367,15 @@ fn add_section_to_parts(
     }
 }
 
+/// The section's text, unless it is empty or (when collapsing separators) has
+/// nothing visible once colors are stripped
+fn visible_section(section_text: Option<String>, prefs: &PersonalityPreferences) -> Option<String> {
+    section_text.filter(|text| {
+        let blank = prefs.display.collapse_separators && strip_ansi(text).trim().is_empty();
+        !text.is_empty() && !blank
+    })
+}
+
 /// Tracks the position and width of each section in the rendered statusline
 #[derive(Debug, Default)]
 pub struct SectionPositions {
@@ -521,11 +1385,8 @@ pub struct SectionPositions {
 
 /// Strip ANSI escape codes and calculate display width
 fn strip_ansi_display_width(s: &str) -> usize {
-    // Regex to match ANSI escape sequences
-    let ansi_regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
-    let stripped = ansi_regex.replace_all(s, "");
     // Use unicode-width for accurate display width
-    UnicodeWidthStr::width(stripped.as_ref())
+    UnicodeWidthStr::width(strip_ansi(s).as_str())
 }
 
 /// Build statusline with position tracking for each section
@@ -536,52 +1397,55 @@ pub fn build_statusline_with_positions(
     prefs: &PersonalityPreferences,
     workspace: Option<&WorkspaceInfo>,
     update_available: Option<&str>,
+    context_usage: Option<u8>,
 ) -> (String, SectionPositions) {
     let mut parts = Vec::new();
     let mut positions = SectionPositions::default();
     let mut current_pos: usize = 0;
 
     // Iterate over section order from preferences
-    for section in &prefs.section_order {
-        let section_text = match section {
-            StatuslineSection::Personality => render_personality_section(state, prefs),
-            StatuslineSection::Directory => render_directory_section(workspace, prefs),
-            StatuslineSection::Git => render_git_section(state, prefs),
-            StatuslineSection::Activity => render_activity_section(state, prefs),
-            StatuslineSection::Model => render_model_section(model_name, prefs),
-            StatuslineSection::UpdateAvailable => render_update_section(update_available, prefs),
-            StatuslineSection::DebugInfo => render_debug_section(state, prefs),
-        };
+    for section in &prefs.display_order() {
+        let section_text = visible_section(
+            render_section(
+                section,
+                state,
+                model_name,
+                prefs,
+                workspace,
+                update_available,
+                context_usage,
+            ),
+            prefs,
+        );
 
         if let Some(ref text) = section_text {
-            if !text.is_empty() {
-                // Calculate the position and width before adding separator
-                let section_start = if parts.is_empty() {
-                    0
+            // Calculate the position and width before adding separator
+            // Powerline segments pad their text with a space on each side
+            let section_start = if parts.is_empty() {
+                usize::from(uses_powerline_segments(prefs))
+            } else {
+                // Account for spacing and separator: " | " or " "
+                let separator_width = if prefs.display.show_separators {
+                    3 // " | " = space + separator + space
                 } else {
-                    // Account for spacing and separator: " | " or " "
-                    let separator_width = if prefs.display.show_separators {
-                        3 // " | " = space + separator + space
-                    } else {
-                        1 // " " = single space
-                    };
-                    current_pos + separator_width
+                    1 // " " = single space
                 };
+                current_pos + separator_width
+            };
 
-                let section_width = strip_ansi_display_width(text);
-                positions
-                    .positions
-                    .insert(section.clone(), (section_start, section_width));
+            let section_width = strip_ansi_display_width(text);
+            positions
+                .positions
+                .insert(section.clone(), (section_start, section_width));
 
-                // Update current position for next section
-                current_pos = section_start + section_width;
-            }
+            // Update current position for next section
+            current_pos = section_start + section_width;
         }
 
-        add_section_to_parts(&mut parts, section_text, prefs);
+        add_section_to_parts(&mut parts, section, section_text, prefs);
     }
 
-    (parts.join(""), positions)
+    (join_parts(&parts, prefs), positions)
 }
 
 #[cfg(test)]
@@ -590,6 +1454,7 @@ mod tests {
     use crate::config::preferences::DisplayConfig;
     use crate::icons::*;
     use crate::state::SessionState;
+    use crate::theme::Theme;
     use crate::types::Activity;
 
     fn create_test_state() -> SessionState {
@@ -601,10 +1466,20 @@ mod tests {
             git_branch: None,
             git_dirty: None,
             git_dirty_count: None,
+            git_modified_count: None,
+            git_untracked_count: None,
             git_status_checked_at: None,
+            git_stash_count: None,
+            git_stash_checked_at: None,
+            git_detached_head: None,
+            git_operation: None,
             personality: "ლ(╹◡╹ლ) Cowder".to_string(),
             previous_personality: None,
             consecutive_actions: 1,
+            total_actions: 1,
+            last_updated_at: None,
+            session_started_at: None,
+            celebrate_until: None,
             error_count: 0,
             recent_activities: Vec::new(),
             mood: crate::state::MoodState::default(),
@@ -619,7 +1494,7 @@ mod tests {
     fn test_build_statusline_basic() {
         let state = create_test_state();
         let prefs = create_test_preferences();
-        let statusline = build_statusline(&state, "Opus", &prefs, None, None);
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
 
         // Should contain personality (bold formatting is applied but we can't easily test ANSI codes)
         assert!(statusline.contains("ლ(╹◡╹ლ) Cowder"));
@@ -641,11 +1516,11 @@ mod tests {
 
         // Error indicators have been removed - statusline should not contain error icons
         state.error_count = 1;
-        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None);
+        let _statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
 
         // Test with many errors - should still not show any error icons
         state.error_count = 5;
-        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None);
+        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
 
         // Just verify the statusline is not empty
         assert!(!statusline.trim().is_empty());
@@ -656,7 +1531,7 @@ mod tests {
         let mut state = create_test_state();
         let prefs = create_test_preferences();
         state.current_job = None;
-        let statusline = build_statusline(&state, "Haiku", &prefs, None, None);
+        let statusline = build_statusline(&state, "Haiku", &prefs, None, None, None);
 
         // Should contain activity but no job
         assert!(statusline.contains("Editing"));
@@ -668,7 +1543,7 @@ mod tests {
         let mut state = create_test_state();
         let prefs = create_test_preferences();
         state.current_job = Some(String::new());
-        let statusline = build_statusline(&state, "Haiku", &prefs, None, None);
+        let statusline = build_statusline(&state, "Haiku", &prefs, None, None, None);
 
         // Should treat empty job same as no job
         assert!(statusline.contains("Editing"));
@@ -753,6 +1628,100 @@ mod tests {
         assert!(claude_input.workspace.is_some());
     }
 
+    #[test]
+    fn test_claude_input_model_shapes() {
+        let model_name = |json: &str| {
+            serde_json::from_str::<ClaudeInput>(json)
+                .unwrap()
+                .model
+                .and_then(|model| model.display_name)
+        };
+
+        assert_eq!(
+            model_name(r#"{"model": {"display_name": "Opus"}}"#).as_deref(),
+            Some("Opus")
+        );
+        assert_eq!(model_name(r#"{"model": "Opus"}"#).as_deref(), Some("Opus"));
+        assert_eq!(
+            model_name(r#"{"model": {"id": "claude-opus", "display_name": "Opus", "extra": [1]}}"#)
+                .as_deref(),
+            Some("Opus")
+        );
+
+        // Unexpected shapes lose the name but still parse
+        assert_eq!(model_name(r#"{"model": {"display_name": 4}}"#), None);
+        assert_eq!(model_name(r#"{"model": 4}"#), None);
+        assert_eq!(model_name(r#"{"model": {}}"#), None);
+    }
+
+    #[test]
+    fn test_claude_input_context_usage() {
+        let with_percentage: ClaudeInput =
+            serde_json::from_str(r#"{"context_window": {"used_percentage": 61.8}}"#).unwrap();
+        assert_eq!(with_percentage.context_usage_percent(), Some(62));
+
+        let with_tokens: ClaudeInput = serde_json::from_str(
+            r#"{"context_window": {"total_input_tokens": 50000, "context_window_size": 200000}}"#,
+        )
+        .unwrap();
+        assert_eq!(with_tokens.context_usage_percent(), Some(25));
+
+        let exceeded: ClaudeInput =
+            serde_json::from_str(r#"{"exceeds_200k_tokens": true}"#).unwrap();
+        assert_eq!(exceeded.context_usage_percent(), Some(100));
+
+        let not_exceeded: ClaudeInput =
+            serde_json::from_str(r#"{"exceeds_200k_tokens": false}"#).unwrap();
+        assert_eq!(not_exceeded.context_usage_percent(), None);
+
+        let unknown: ClaudeInput = serde_json::from_str("{}").unwrap();
+        assert_eq!(unknown.context_usage_percent(), None);
+    }
+
+    #[test]
+    fn test_context_gauge_rendering() {
+        assert_eq!(
+            format_context_gauge(0),
+            "\u{2591}\u{2591}\u{2591}\u{2591}\u{2591} 0%"
+        );
+        assert_eq!(
+            format_context_gauge(62),
+            "\u{2593}\u{2593}\u{2593}\u{2591}\u{2591} 62%"
+        );
+        assert_eq!(
+            format_context_gauge(100),
+            "\u{2593}\u{2593}\u{2593}\u{2593}\u{2593} 100%"
+        );
+    }
+
+    #[test]
+    fn test_context_usage_section() {
+        let state = create_test_state();
+        let prefs = PersonalityPreferences {
+            show_context_usage: true,
+            use_colors: false,
+            ..Default::default()
+        };
+
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, Some(62));
+        assert!(statusline.contains("\u{2593}\u{2593}\u{2593}\u{2591}\u{2591} 62%"));
+
+        // Unknown usage renders nothing
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
+        assert!(!statusline.contains('%'));
+
+        // Disabled preference hides the gauge
+        let statusline = build_statusline(
+            &state,
+            "Opus",
+            &create_test_preferences(),
+            None,
+            None,
+            Some(62),
+        );
+        assert!(!statusline.contains("62%"));
+    }
+
     #[test]
     fn test_claude_input_parsing_minimal() {
         let json_str = r"{}";
@@ -775,7 +1744,7 @@ mod tests {
         ];
         for (activity, expected_icon) in activities_with_icons {
             state.activity = activity.clone();
-            let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+            let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
             // The icon should be in the statusline
             assert!(statusline.contains(expected_icon));
             assert!(statusline.contains(&activity.to_string()));
@@ -790,7 +1759,7 @@ mod tests {
         ];
         for activity in activities_without_icons {
             state.activity = activity.clone();
-            let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+            let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
             // Should contain activity text but no icon
             assert!(statusline.contains(&activity.to_string()));
         }
@@ -800,7 +1769,7 @@ mod tests {
     fn test_statusline_formatting_structure() {
         let state = create_test_state();
         let prefs = create_test_preferences();
-        let statusline = build_statusline(&state, "TestModel", &prefs, None, None);
+        let statusline = build_statusline(&state, "TestModel", &prefs, None, None, None);
 
         // Should contain separators
         assert!(statusline.contains("•"));
@@ -821,7 +1790,7 @@ mod tests {
         state.current_job =
             Some("very_long_filename_that_might_cause_display_issues.js".to_string());
 
-        let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+        let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
 
         // Should handle long job names gracefully
         assert!(statusline.contains("very_long_filename_that_might_cause_display_issues.js"));
@@ -841,7 +1810,7 @@ mod tests {
 
         for personality in personalities {
             state.personality = personality.to_string();
-            let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+            let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
             assert!(statusline.contains(personality));
         }
     }
@@ -854,15 +1823,15 @@ mod tests {
         // Error indicators have been completely removed
         // Statusline should work normally regardless of error count
         state.error_count = 0;
-        let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+        let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
         assert!(!statusline.trim().is_empty());
 
         state.error_count = 1;
-        let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+        let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
         assert!(!statusline.trim().is_empty());
 
         state.error_count = 10;
-        let statusline = build_statusline(&state, "Claude", &prefs, None, None);
+        let statusline = build_statusline(&state, "Claude", &prefs, None, None, None);
         assert!(!statusline.trim().is_empty());
     }
 
@@ -878,7 +1847,7 @@ mod tests {
 
         // Test with all options enabled (default)
         let prefs_all = PersonalityPreferences::default();
-        let statusline_all = build_statusline(&state, "Opus", &prefs_all, None, None);
+        let statusline_all = build_statusline(&state, "Opus", &prefs_all, None, None, None);
 
         // Should contain personality, activity, model, and separators
         assert!(statusline_all.contains("Chillin"));
@@ -904,7 +1873,7 @@ mod tests {
             ..Default::default()
         };
 
-        let statusline_minimal = build_statusline(&state, "Opus", &prefs_minimal, None, None);
+        let statusline_minimal = build_statusline(&state, "Opus", &prefs_minimal, None, None, None);
         // Should be empty since we disabled everything important
         assert!(statusline_minimal.is_empty());
     }
@@ -916,13 +1885,16 @@ mod tests {
             activity: Activity::Testing,
             error_count: 3,
             consecutive_actions: 7,
+            total_actions: 7,
+            last_updated_at: None,
+            celebrate_until: None,
             session_id: "test123".to_string(),
             ..Default::default()
         };
 
         // Without debug info
         let prefs_normal = PersonalityPreferences::default();
-        let statusline_normal = build_statusline(&state, "Sonnet", &prefs_normal, None, None);
+        let statusline_normal = build_statusline(&state, "Sonnet", &prefs_normal, None, None, None);
         assert!(!statusline_normal.contains("E:3"));
         assert!(!statusline_normal.contains("C:7"));
         assert!(!statusline_normal.contains("S:test123"));
@@ -935,44 +1907,1510 @@ mod tests {
             },
             ..Default::default()
         };
-        let statusline_debug = build_statusline(&state, "Sonnet", &prefs_debug, None, None);
+        let statusline_debug = build_statusline(&state, "Sonnet", &prefs_debug, None, None, None);
         assert!(statusline_debug.contains("E:3"));
         assert!(statusline_debug.contains("C:7"));
+        assert!(statusline_debug.contains("T:7"));
         assert!(statusline_debug.contains("S:test123"));
     }
 
     #[test]
-    fn test_separators_configuration() {
-        use crate::types::Activity;
+    fn test_activity_icon_overrides() {
+        let testing_icon = "\u{f0c3}"; // nf-fa-flask
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        prefs
+            .activity_icons
+            .insert(Activity::Testing, testing_icon.to_string());
+        prefs
+            .activity_icons
+            .insert(Activity::Executing, String::new());
+
+        let testing_state = SessionState {
+            activity: Activity::Testing,
+            ..create_test_state()
+        };
+        let statusline = build_statusline(&testing_state, "Sonnet", &prefs, None, None, None);
+        assert!(statusline.contains(&format!("{testing_icon} Testing")));
+
+        // Empty override hides the default Executing icon
+        let executing_state = SessionState {
+            activity: Activity::Executing,
+            ..create_test_state()
+        };
+        let statusline = build_statusline(&executing_state, "Sonnet", &prefs, None, None, None);
+        assert!(statusline.contains("Executing"));
+        assert!(!statusline.contains(ICON_EXECUTING));
+    }
+
+    #[test]
+    fn test_action_count_section() {
         let state = SessionState {
-            activity: Activity::Coding,
-            current_job: Some("app.rs".to_string()),
+            total_actions: 42,
+            ..create_test_state()
+        };
+
+        // Hidden by default
+        let prefs = create_test_preferences();
+        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
+        assert!(!statusline.contains(ICON_BOLT));
+
+        let prefs = PersonalityPreferences {
+            show_action_count: true,
+            use_colors: false,
             ..Default::default()
         };
+        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
+        assert!(statusline.contains(&format!("{ICON_BOLT} 42")));
+    }
 
-        // With separators (default)
-        let prefs_with_sep = PersonalityPreferences {
+    #[test]
+    fn test_git_dir_follows_git_run_from() {
+        let workspace = WorkspaceInfo {
+            current_dir: Some("/home/user/my-project/src/deep".to_string()),
+            project_dir: Some("/home/user/my-project".to_string()),
+        };
+
+        let default_prefs = PersonalityPreferences::default();
+        assert_eq!(
+            workspace.git_dir(default_prefs.git_run_from),
+            Some("/home/user/my-project/src/deep")
+        );
+        assert_eq!(
+            workspace.git_dir(GitRunFrom::ProjectDir),
+            Some("/home/user/my-project")
+        );
+
+        // Without a project directory git still runs somewhere useful
+        let no_project = WorkspaceInfo {
+            project_dir: None,
+            ..workspace
+        };
+        assert_eq!(
+            no_project.git_dir(GitRunFrom::ProjectDir),
+            Some("/home/user/my-project/src/deep")
+        );
+
+        let prefs: PersonalityPreferences =
+            serde_json::from_str(r#"{ "git_run_from": "project_dir" }"#).unwrap();
+        assert_eq!(prefs.git_run_from, GitRunFrom::ProjectDir);
+        // Configs written before the names were snake_case still load
+        let prefs: PersonalityPreferences =
+            serde_json::from_str(r#"{ "git_run_from": "ProjectDir" }"#).unwrap();
+        assert_eq!(prefs.git_run_from, GitRunFrom::ProjectDir);
+    }
+
+    #[tokio::test]
+    async fn test_git_run_from_picks_the_repository() {
+        let temp = tempfile::tempdir().unwrap();
+        let project = temp.path().join("project");
+        let nested = project.join("vendor").join("lib");
+        let init = |dir: &Path, branch: &str| {
+            std::fs::create_dir_all(dir).unwrap();
+            for args in [
+                vec!["init", "-q", "-b", branch],
+                vec![
+                    "-c",
+                    "user.name=Test",
+                    "-c",
+                    "user.email=test@example.com",
+                    "commit",
+                    "-q",
+                    "--allow-empty",
+                    "-m",
+                    "init",
+                ],
+            ] {
+                let status = std::process::Command::new("git")
+                    .args(args)
+                    .current_dir(dir)
+                    .status()
+                    .unwrap();
+                assert!(status.success());
+            }
+        };
+        init(&project, "main");
+        init(&nested, "vendored");
+
+        let workspace = WorkspaceInfo {
+            current_dir: Some(nested.to_string_lossy().to_string()),
+            project_dir: Some(project.to_string_lossy().to_string()),
+        };
+        for (run_from, branch) in [
+            (GitRunFrom::CurrentDir, "vendored"),
+            (GitRunFrom::ProjectDir, "main"),
+        ] {
+            let mut state = SessionState::default();
+            state
+                .refresh_git_branch(workspace.git_dir(run_from).unwrap())
+                .await;
+            assert_eq!(state.git_branch.as_deref(), Some(branch), "{run_from:?}");
+        }
+    }
+
+    #[test]
+    fn test_hide_dir_when_repo_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = temp.path().join("my-project");
+        let nested = repo.join("src");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        let workspace_at = |dir: &Path| WorkspaceInfo {
+            current_dir: Some(dir.to_string_lossy().to_string()),
+            project_dir: None,
+        };
+
+        let state = SessionState {
+            git_branch: Some("main".to_string()),
+            ..create_test_state()
+        };
+        let prefs = PersonalityPreferences {
+            show_current_dir: true,
+            hide_dir_when_repo_root: true,
+            show_directory_icon: false,
             use_colors: false,
             ..Default::default()
         };
-        let statusline_with_sep = build_statusline(&state, "Haiku", &prefs_with_sep, None, None);
-        assert!(statusline_with_sep.contains("•"));
 
-        // Without separators
-        let prefs_no_sep = PersonalityPreferences {
+        // At the repo root next to a shown branch, the directory is redundant
+        assert_eq!(
+            render_directory_section(Some(&workspace_at(&repo)), &state, &prefs),
+            None
+        );
+
+        // A subdirectory still shows its own name
+        assert_eq!(
+            render_directory_section(Some(&workspace_at(&nested)), &state, &prefs).as_deref(),
+            Some("src")
+        );
+
+        // Without a branch in the statusline the directory stays
+        let no_git = PersonalityPreferences {
+            show_git: false,
+            ..prefs.clone()
+        };
+        assert_eq!(
+            render_directory_section(Some(&workspace_at(&repo)), &state, &no_git).as_deref(),
+            Some("my-project")
+        );
+
+        // Off by default
+        let default_prefs = PersonalityPreferences {
+            hide_dir_when_repo_root: false,
+            ..prefs
+        };
+        assert_eq!(
+            render_directory_section(Some(&workspace_at(&repo)), &state, &default_prefs).as_deref(),
+            Some("my-project")
+        );
+    }
+
+    #[test]
+    fn test_directory_icon_and_name_colored_separately() {
+        let workspace = WorkspaceInfo {
+            current_dir: Some("/home/user/my-project".to_string()),
+            project_dir: None,
+        };
+        let prefs = PersonalityPreferences {
+            theme: Theme::Nord,
+            ..Default::default()
+        };
+
+        // Each part carries its own color rather than one span over both
+        assert_eq!(
+            format_workspace_info(&workspace, &prefs),
+            format!(
+                "{} {}",
+                Theme::Nord.apply_directory_icon(ICON_FOLDER),
+                Theme::Nord.apply_directory("my-project")
+            )
+        );
+        assert_ne!(
+            Theme::Nord
+                .directory_icon_color()
+                .apply(ICON_FOLDER)
+                .fgcolor,
+            Theme::Nord.colors().directory.apply("my-project").fgcolor
+        );
+    }
+
+    #[test]
+    fn test_accent_separator_before_model() {
+        let mut prefs = PersonalityPreferences {
             use_colors: false,
-            display: DisplayConfig {
-                show_separators: false,
-                ..Default::default()
-            },
+            show_model_icon: false,
             ..Default::default()
         };
-        let statusline_no_sep = build_statusline(&state, "Haiku", &prefs_no_sep, None, None);
-        assert!(!statusline_no_sep.contains("•"));
-        // But should still have content
-        assert!(statusline_no_sep.contains("Chillin"));
-        assert!(statusline_no_sep.contains("Coding"));
-        assert!(statusline_no_sep.contains("app.rs"));
-        assert!(statusline_no_sep.contains("Haiku"));
+        prefs.section_order =
+            StatuslineSection::parse_order("personality,activity,model", true).unwrap();
+        let state = SessionState {
+            activity: Activity::Editing,
+            ..create_test_state()
+        };
+
+        // Off by default: every separator is the normal one
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
+        assert_eq!(statusline.matches('\u{2022}').count(), 2, "{statusline:?}");
+        assert!(!statusline.contains('\u{2503}'), "{statusline:?}");
+
+        prefs.display.accent_separator_before = Some(StatuslineSection::Model);
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
+        assert_eq!(statusline.matches('\u{2022}').count(), 1, "{statusline:?}");
+        assert!(statusline.contains(" \u{2503} Opus"), "{statusline:?}");
+
+        prefs.display.accent_separator_char = "|".to_string();
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
+        assert!(statusline.contains(" | Opus"), "{statusline:?}");
+
+        // Nothing to separate when the accented section comes first
+        prefs.section_order = StatuslineSection::parse_order("model,personality", true).unwrap();
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
+        assert!(statusline.starts_with("Opus"), "{statusline:?}");
+        assert!(!statusline.contains('|'), "{statusline:?}");
+    }
+
+    #[test]
+    fn test_blank_sections_leave_no_dangling_separators() {
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git: false,
+            show_current_dir: false,
+            show_activity: false,
+            show_model_icon: false,
+            show_update_available: true,
+            update_icon: String::new(),
+            // Renders as a lone space, so would otherwise get a separator of its own
+            update_format: Some(" ".to_string()),
+            ..Default::default()
+        };
+        let state = create_test_state();
+
+        // Trailing
+        prefs.section_order =
+            StatuslineSection::parse_order("personality,model,update_available", true).unwrap();
+        let statusline = build_statusline(&state, "Opus", &prefs, None, Some("9.9.9"), None);
+        assert!(statusline.ends_with("Opus"), "{statusline:?}");
+
+        // Leading
+        prefs.section_order =
+            StatuslineSection::parse_order("update_available,personality,model", true).unwrap();
+        let statusline = build_statusline(&state, "Opus", &prefs, None, Some("9.9.9"), None);
+        assert!(!statusline.starts_with(' '), "{statusline:?}");
+        assert_eq!(statusline.matches('\u{2022}').count(), 1, "{statusline:?}");
+
+        // In the middle
+        prefs.section_order =
+            StatuslineSection::parse_order("personality,update_available,model", true).unwrap();
+        let statusline = build_statusline(&state, "Opus", &prefs, None, Some("9.9.9"), None);
+        assert!(!statusline.contains("\u{2022}  \u{2022}"), "{statusline:?}");
+        assert_eq!(statusline.matches('\u{2022}').count(), 1, "{statusline:?}");
+
+        // Without collapsing, the blank section keeps its separator
+        prefs.display.collapse_separators = false;
+        let statusline = build_statusline(&state, "Opus", &prefs, None, Some("9.9.9"), None);
+        assert_eq!(statusline.matches('\u{2022}').count(), 2, "{statusline:?}");
+    }
+
+    #[test]
+    fn test_day_streak_section() {
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            icon_mode: IconMode::Ascii,
+            ..Default::default()
+        };
+        assert_eq!(render_day_streak_section(&prefs, Some(3)), None);
+
+        prefs.show_day_streak = true;
+        assert_eq!(
+            render_day_streak_section(&prefs, Some(3)).as_deref(),
+            Some("[days] 3d")
+        );
+        assert_eq!(render_day_streak_section(&prefs, Some(0)), None);
+        assert_eq!(render_day_streak_section(&prefs, None), None);
+    }
+
+    #[test]
+    fn test_errors_section() {
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            icon_mode: IconMode::Unicode,
+            ..Default::default()
+        };
+        let state = SessionState {
+            error_count: 3,
+            ..create_test_state()
+        };
+        assert_eq!(render_errors_section(&state, &prefs), None);
+        assert!(!prefs.display_order().contains(&StatuslineSection::Errors));
+
+        prefs.show_error_count = true;
+        assert_eq!(
+            render_errors_section(&state, &prefs).as_deref(),
+            Some("\u{26a0}3")
+        );
+        assert_eq!(
+            prefs.display_order().last(),
+            Some(&StatuslineSection::Errors)
+        );
+
+        let no_errors = SessionState {
+            error_count: 0,
+            ..state.clone()
+        };
+        assert_eq!(render_errors_section(&no_errors, &prefs), None);
+
+        prefs.icon_mode = IconMode::NerdFont;
+        assert_eq!(
+            render_errors_section(&state, &prefs).as_deref(),
+            Some("\u{f071}3")
+        );
+    }
+
+    #[test]
+    fn test_clock_section() {
+        use chrono::TimeZone;
+
+        let now = chrono::Local
+            .with_ymd_and_hms(2026, 10, 17, 9, 5, 7)
+            .unwrap();
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            icon_mode: IconMode::None,
+            ..Default::default()
+        };
+        assert_eq!(render_clock_section(&prefs, &now), None);
+
+        prefs.show_clock = true;
+        assert_eq!(render_clock_section(&prefs, &now).as_deref(), Some("09:05"));
+
+        prefs.clock_format = "%a %H:%M:%S".to_string();
+        assert_eq!(
+            render_clock_section(&prefs, &now).as_deref(),
+            Some("Sat 09:05:07")
+        );
+
+        prefs.clock_format = "%I:%M %p".to_string();
+        assert_eq!(format_clock(&now, &prefs.clock_format), "09:05 AM");
+
+        // An invalid format falls back to the default instead of panicking
+        assert_eq!(format_clock(&now, "%Q"), "09:05");
+
+        prefs.icon_mode = IconMode::Ascii;
+        prefs.clock_format = DEFAULT_CLOCK_FORMAT.to_string();
+        assert_eq!(
+            render_clock_section(&prefs, &now).as_deref(),
+            Some("[time] 09:05")
+        );
+    }
+
+    #[test]
+    fn test_streak_milestones() {
+        let prefs = PersonalityPreferences {
+            show_streak_milestones: true,
+            use_colors: false,
+            ..Default::default()
+        };
+        let render = |consecutive_actions, prefs: &PersonalityPreferences| {
+            let state = SessionState {
+                consecutive_actions,
+                ..create_test_state()
+            };
+            render_personality_section(&state, prefs).unwrap()
+        };
+
+        for milestone in [10, 20, 50] {
+            assert!(
+                render(milestone, &prefs).ends_with(&format!(" + {milestone} streak!")),
+                "{milestone}"
+            );
+        }
+        for other in [9, 11, 21, 49] {
+            assert!(!render(other, &prefs).contains("streak!"), "{other}");
+        }
+
+        // Off by default
+        let default_prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        assert!(!render(20, &default_prefs).contains("streak!"));
+    }
+
+    #[test]
+    fn test_activity_label_style() {
+        use crate::config::preferences::ActivityLabelStyle;
+        use crate::types::Activity;
+
+        let testing = SessionState {
+            activity: Activity::Testing,
+            current_job: None,
+            current_file: None,
+            ..create_test_state()
+        };
+        let label_in = |activity_label_style| {
+            let prefs = PersonalityPreferences {
+                activity_label_style,
+                show_activity_icon: false,
+                use_colors: false,
+                ..Default::default()
+            };
+            render_activity_section(&testing, &prefs)
+        };
+
+        assert_eq!(
+            label_in(ActivityLabelStyle::TitleCase).as_deref(),
+            Some("Testing")
+        );
+        assert_eq!(
+            label_in(ActivityLabelStyle::Lowercase).as_deref(),
+            Some("testing")
+        );
+        assert_eq!(
+            label_in(ActivityLabelStyle::Verbose).as_deref(),
+            Some("Running tests")
+        );
+        assert_eq!(
+            PersonalityPreferences::default().activity_label_style,
+            ActivityLabelStyle::TitleCase
+        );
+    }
+
+    #[test]
+    fn test_compact_activity() {
+        use crate::types::Activity;
+        let prefs = PersonalityPreferences {
+            compact_activity: true,
+            use_colors: false,
+            ..Default::default()
+        };
+
+        // Reading has an icon, so it stands in for the label
+        let reading = SessionState {
+            activity: Activity::Reading,
+            current_job: None,
+            current_file: None,
+            ..create_test_state()
+        };
+        assert_eq!(
+            render_activity_section(&reading, &prefs).as_deref(),
+            Some(ICON_READING)
+        );
+
+        // Coding has no icon, so the label stays
+        let coding = SessionState {
+            activity: Activity::Coding,
+            current_job: None,
+            current_file: None,
+            ..create_test_state()
+        };
+        assert_eq!(
+            render_activity_section(&coding, &prefs).as_deref(),
+            Some("Coding")
+        );
+    }
+
+    #[test]
+    fn test_icon_mode_replaces_nerd_font_glyphs() {
+        use crate::icons::IconMode;
+        use crate::types::Activity;
+        let state = SessionState {
+            activity: Activity::Reading,
+            current_job: Some("main.rs".to_string()),
+            ..create_test_state()
+        };
+
+        let prefs = PersonalityPreferences {
+            icon_mode: IconMode::Ascii,
+            use_colors: false,
+            ..Default::default()
+        };
+        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
+        assert!(statusline.contains("[read] Reading"));
+        assert!(statusline.contains("[sonnet] Sonnet"));
+        assert!(!statusline.contains(ICON_READING));
+
+        let prefs = PersonalityPreferences {
+            icon_mode: IconMode::None,
+            ..prefs
+        };
+        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
+        assert!(statusline.contains("Reading main.rs"));
+        assert!(!statusline.contains("[read]"));
+        assert!(!statusline.contains(ICON_SONNET));
+    }
+
+    #[test]
+    fn test_separators_configuration() {
+        use crate::types::Activity;
+        let state = SessionState {
+            activity: Activity::Coding,
+            current_job: Some("app.rs".to_string()),
+            ..Default::default()
+        };
+
+        // With separators (default)
+        let prefs_with_sep = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        let statusline_with_sep =
+            build_statusline(&state, "Haiku", &prefs_with_sep, None, None, None);
+        assert!(statusline_with_sep.contains("•"));
+
+        // Without separators
+        let prefs_no_sep = PersonalityPreferences {
+            use_colors: false,
+            display: DisplayConfig {
+                show_separators: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let statusline_no_sep = build_statusline(&state, "Haiku", &prefs_no_sep, None, None, None);
+        assert!(!statusline_no_sep.contains("•"));
+        // But should still have content
+        assert!(statusline_no_sep.contains("Chillin"));
+        assert!(statusline_no_sep.contains("Coding"));
+        assert!(statusline_no_sep.contains("app.rs"));
+        assert!(statusline_no_sep.contains("Haiku"));
+    }
+
+    #[test]
+    fn test_trailing_color_reset() {
+        let state = create_test_state();
+
+        let colored_prefs = PersonalityPreferences::default();
+        let colored =
+            finish_statusline("\u{1b}[1mTest\u{1b}[0m".to_string(), &colored_prefs, false);
+        assert!(colored.ends_with(ANSI_RESET));
+
+        // NO_COLOR suppresses the reset even when colors are enabled
+        let no_color = finish_statusline("Test".to_string(), &colored_prefs, true);
+        assert_eq!(no_color, "Test");
+
+        let plain_prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        let plain = build_statusline(&state, "Sonnet", &plain_prefs, None, None, None);
+        assert!(!plain.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_git_branch_suffix() {
+        let state = SessionState {
+            git_branch: Some("main".to_string()),
+            ..Default::default()
+        };
+
+        let default_prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            show_git_status: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&state, &default_prefs).as_deref(),
+            Some("main branch")
+        );
+
+        let no_suffix = PersonalityPreferences {
+            git_branch_suffix: String::new(),
+            ..default_prefs.clone()
+        };
+        assert_eq!(
+            render_git_section(&state, &no_suffix).as_deref(),
+            Some("main")
+        );
+
+        let colored_no_suffix = PersonalityPreferences {
+            use_colors: true,
+            ..no_suffix
+        };
+        let colored = render_git_section(&state, &colored_no_suffix).unwrap();
+        assert!(colored.contains("main"));
+        assert!(!colored.contains("branch"));
+    }
+
+    #[test]
+    fn test_update_section_format() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_update_section(Some("1.2.3"), &prefs).as_deref(),
+            Some("\u{f062} v1.2.3")
+        );
+
+        let custom = PersonalityPreferences {
+            update_icon: "\u{f01b}".to_string(),
+            update_format: Some("update {version} available".to_string()),
+            ..prefs.clone()
+        };
+        assert_eq!(
+            render_update_section(Some("1.2.3"), &custom).as_deref(),
+            Some("\u{f01b} update 1.2.3 available")
+        );
+
+        let no_icon = PersonalityPreferences {
+            update_icon: String::new(),
+            ..custom
+        };
+        assert_eq!(
+            render_update_section(Some("1.2.3"), &no_icon).as_deref(),
+            Some("update 1.2.3 available")
+        );
+        assert_eq!(render_update_section(None, &prefs), None);
+    }
+
+    #[test]
+    fn test_model_name_styles() {
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            show_model_icon: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render_model_section("Claude 3.5 Sonnet", &prefs).as_deref(),
+            Some("Claude 3.5 Sonnet")
+        );
+
+        prefs.model_name_style = ModelNameStyle::Short;
+        assert_eq!(
+            render_model_section("Claude 3.5 Sonnet", &prefs).as_deref(),
+            Some("Sonnet")
+        );
+
+        prefs.model_name_style = ModelNameStyle::Initials;
+        assert_eq!(
+            render_model_section("Claude 3.5 Sonnet", &prefs).as_deref(),
+            Some("S")
+        );
+
+        // Unknown models keep their full name
+        assert_eq!(
+            render_model_section("Mystery Model", &prefs).as_deref(),
+            Some("Mystery Model")
+        );
+    }
+
+    #[test]
+    fn test_model_bracket_styles() {
+        use crate::config::preferences::ModelBracketStyle;
+
+        let mut prefs = PersonalityPreferences {
+            use_colors: false,
+            show_model_icon: false,
+            model_name_style: ModelNameStyle::Short,
+            ..Default::default()
+        };
+
+        for (style, expected) in [
+            (ModelBracketStyle::None, "Sonnet"),
+            (ModelBracketStyle::Square, "[Sonnet]"),
+            (ModelBracketStyle::Round, "(Sonnet)"),
+            (ModelBracketStyle::Angle, "<Sonnet>"),
+        ] {
+            prefs.model_bracket_style = style;
+            assert_eq!(
+                render_model_section("Claude 3.5 Sonnet", &prefs).as_deref(),
+                Some(expected),
+                "{style:?}"
+            );
+        }
+
+        // The icon sits inside the brackets with the name
+        prefs.show_model_icon = true;
+        prefs.model_bracket_style = ModelBracketStyle::Square;
+        let icon = get_model_icon_for_mode("Claude 3.5 Sonnet", prefs.icon_mode);
+        assert_eq!(
+            render_model_section("Claude 3.5 Sonnet", &prefs),
+            Some(format!("[{icon} Sonnet]"))
+        );
+    }
+
+    #[test]
+    fn test_celebration_face_is_temporary() {
+        let prefs = PersonalityPreferences::default();
+        let now = 10_000;
+
+        let celebrating = SessionState {
+            celebrate_until: Some(now + 3),
+            ..create_test_state()
+        };
+        assert_eq!(
+            displayed_personality(&celebrating, &prefs, now),
+            CELEBRATING.personality()
+        );
+
+        let expired = SessionState {
+            celebrate_until: Some(now),
+            ..create_test_state()
+        };
+        assert_eq!(
+            displayed_personality(&expired, &prefs, now),
+            expired.personality
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours_turn_colors_off() {
+        let mut prefs = PersonalityPreferences {
+            quiet_hours_start: Some(22),
+            quiet_hours_end: Some(6),
+            ..Default::default()
+        };
+        apply_quiet_hours(&mut prefs, 12);
+        assert!(prefs.use_colors);
+        apply_quiet_hours(&mut prefs, 23);
+        assert!(!prefs.use_colors);
+
+        let state = create_test_state();
+        let statusline = build_statusline(&state, "Sonnet", &prefs, None, None, None);
+        assert!(!statusline.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_fresh_session_shows_custom_default_personality() {
+        let prefs = PersonalityPreferences {
+            default_personality: Some("(-_-) Zen Mode".to_string()),
+            ..Default::default()
+        };
+        let fresh = SessionState::default();
+        assert_eq!(displayed_personality(&fresh, &prefs, 0), "(-_-) Zen Mode");
+        assert_eq!(
+            displayed_personality(&fresh, &PersonalityPreferences::default(), 0),
+            BOOTING_UP.personality()
+        );
+
+        let settled = SessionState {
+            personality: "(▀̿Ĺ̯▀̿ ̿) JS Master".to_string(),
+            ..fresh
+        };
+        assert_eq!(
+            displayed_personality(&settled, &prefs, 0),
+            settled.personality
+        );
+    }
+
+    #[test]
+    fn test_boot_grace_keeps_booting_face() {
+        let prefs = PersonalityPreferences {
+            boot_grace_seconds: 10,
+            ..Default::default()
+        };
+        let now = 10_000;
+
+        let early_idle = SessionState {
+            activity: Activity::Idle,
+            personality: "(-_-) Default".to_string(),
+            session_started_at: Some(now - 3),
+            ..create_test_state()
+        };
+        assert_eq!(
+            displayed_personality(&early_idle, &prefs, now),
+            BOOTING_UP.personality()
+        );
+
+        let past_grace = SessionState {
+            session_started_at: Some(now - 10),
+            ..early_idle.clone()
+        };
+        assert_eq!(
+            displayed_personality(&past_grace, &prefs, now),
+            past_grace.personality
+        );
+
+        // Real work ends the grace period early
+        let working = SessionState {
+            activity: Activity::Editing,
+            ..early_idle.clone()
+        };
+        assert_eq!(
+            displayed_personality(&working, &prefs, now),
+            working.personality
+        );
+
+        // Disabled by default
+        assert_eq!(
+            displayed_personality(&early_idle, &PersonalityPreferences::default(), now),
+            early_idle.personality
+        );
+    }
+
+    #[test]
+    fn test_idle_personality_uses_resting_face() {
+        let prefs = PersonalityPreferences {
+            idle_timeout_seconds: 300,
+            ..Default::default()
+        };
+        let now = 10_000;
+
+        let fresh = SessionState {
+            last_updated_at: Some(now - 30),
+            ..create_test_state()
+        };
+        assert_eq!(
+            displayed_personality(&fresh, &prefs, now),
+            fresh.personality
+        );
+
+        let stale = SessionState {
+            last_updated_at: Some(now - 600),
+            ..create_test_state()
+        };
+        assert_eq!(
+            displayed_personality(&stale, &prefs, now),
+            RESTING.personality()
+        );
+        // Persisted personality is untouched
+        assert_ne!(stale.personality, RESTING.personality());
+
+        // A zero timeout disables the resting face
+        let disabled = PersonalityPreferences {
+            idle_timeout_seconds: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            displayed_personality(&stale, &disabled, now),
+            stale.personality
+        );
+    }
+
+    #[test]
+    fn test_visible_width_ignores_ansi() {
+        let colored = "\u{1b}[1;38;2;255;121;198mOpus\u{1b}[0m";
+        assert_eq!(strip_ansi_display_width(colored), 4);
+
+        let segments = format!("{colored} \u{1b}[38;5;244m\u{2022}\u{1b}[0m v1");
+        assert_eq!(strip_ansi_display_width(&segments), 9);
+    }
+
+    #[test]
+    fn test_align_right_pads_to_width() {
+        let left = "\u{1b}[1mleft\u{1b}[0m";
+        let right = "\u{1b}[36mright\u{1b}[0m";
+
+        let aligned = align_right(left, right, 20);
+        assert_eq!(strip_ansi_display_width(&aligned), 20);
+        assert!(aligned.ends_with(right));
+
+        // Too narrow: groups still get a single space between them
+        let cramped = align_right(left, right, 5);
+        assert_eq!(strip_ansi_display_width(&cramped), 10);
+
+        assert_eq!(align_right(left, "", 20), left);
+    }
+
+    #[test]
+    fn test_git_dirty_count_cap() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            git_branch_suffix: String::new(),
+            ..Default::default()
+        };
+        let render = |count: usize| {
+            let state = SessionState {
+                git_branch: Some("main".to_string()),
+                git_dirty: Some(true),
+                git_dirty_count: Some(count),
+                ..Default::default()
+            };
+            render_git_section(&state, &prefs).unwrap()
+        };
+
+        assert_eq!(render(42), "main ±42");
+        assert_eq!(render(99), "main ±99");
+        assert_eq!(render(3847), "main ±99+");
+
+        assert_eq!(format_dirty_count(3847, 0), "3847");
+    }
+
+    #[test]
+    fn test_path_basename_handles_windows_paths() {
+        assert_eq!(path_basename(r"C:\a\b\proj"), Some("proj"));
+        assert_eq!(path_basename("/a/b/proj"), Some("proj"));
+        assert_eq!(path_basename("/a/b/proj/"), Some("proj"));
+        assert_eq!(path_basename(r"C:\a\b\proj\"), Some("proj"));
+        assert_eq!(path_basename("/"), None);
+        assert_eq!(path_basename(r"C:\"), None);
+    }
+
+    #[test]
+    fn test_powerline_separator_style() {
+        let state = create_test_state();
+
+        let plain_prefs = PersonalityPreferences {
+            use_colors: false,
+            display: DisplayConfig {
+                separator_char: "|".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let plain = build_statusline(&state, "Sonnet", &plain_prefs, None, None, None);
+        assert!(plain.contains(" | "));
+        assert!(!plain.contains(POWERLINE_SEPARATOR));
+
+        let powerline_prefs = PersonalityPreferences {
+            display: DisplayConfig {
+                separator_style: SeparatorStyle::Powerline,
+                ..plain_prefs.display.clone()
+            },
+            ..plain_prefs.clone()
+        };
+        let powerline = build_statusline(&state, "Sonnet", &powerline_prefs, None, None, None);
+        assert!(powerline.contains(&format!(" {POWERLINE_SEPARATOR} ")));
+        assert!(!powerline.contains(" | "));
+    }
+
+    #[test]
+    fn test_powerline_segment_backgrounds() {
+        let prefs = PersonalityPreferences {
+            display: DisplayConfig {
+                separator_style: SeparatorStyle::Powerline,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let first = powerline_background(0, &prefs);
+        let second = powerline_background(1, &prefs);
+        assert_ne!(first, second);
+
+        // Each arrow is drawn in the previous segment's color on the next one's background
+        let line = join_parts(
+            &[
+                powerline_segment("one", 0, false, &prefs),
+                powerline_segment("two", 1, false, &prefs),
+            ],
+            &prefs,
+        );
+        assert!(line.starts_with(&format!("{} one ", first.background_code())));
+        assert!(line.contains(&format!(
+            "{}{}{POWERLINE_SEPARATOR}",
+            first.foreground_code(),
+            second.background_code()
+        )));
+        assert!(line.ends_with(&format!(
+            "{}{POWERLINE_SEPARATOR}\x1b[0m",
+            second.foreground_code()
+        )));
+
+        // Color resets inside a section keep the segment background
+        let segment = powerline_segment("\x1b[36mtwo\x1b[0m tail", 1, false, &prefs);
+        assert!(segment.contains(&format!("\x1b[0m{} tail", second.background_code())));
+    }
+
+    #[test]
+    fn test_powerline_accent_separator() {
+        let mut prefs = PersonalityPreferences {
+            show_model_icon: false,
+            display: DisplayConfig {
+                separator_style: SeparatorStyle::Powerline,
+                accent_separator_before: Some(StatuslineSection::Model),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        prefs.section_order =
+            StatuslineSection::parse_order("personality,activity,model", true).unwrap();
+        let state = SessionState {
+            activity: Activity::Editing,
+            ..create_test_state()
+        };
+        let info = prefs.theme.colors().info.foreground_code();
+        let arrow_into = |index: usize, color: String| {
+            format!(
+                "{color}{}{POWERLINE_SEPARATOR}",
+                powerline_background(index, &prefs).background_code()
+            )
+        };
+
+        // The arrow into the model segment takes the accent color, the others don't
+        let statusline = build_statusline(&state, "Opus", &prefs, None, None, None);
+        assert!(
+            statusline.contains(&arrow_into(2, info.clone())),
+            "{statusline:?}"
+        );
+        let plain = powerline_background(0, &prefs).foreground_code();
+        assert!(statusline.contains(&arrow_into(1, plain)), "{statusline:?}");
+        assert!(!statusline.contains(&arrow_into(1, info)), "{statusline:?}");
+    }
+
+    #[test]
+    fn test_watch_state_change_detection() {
+        let state = create_test_state();
+        assert!(state_changed(None, &state));
+        assert!(!state_changed(Some(&state), &state.clone()));
+
+        let updated = SessionState {
+            activity: Activity::Testing,
+            ..state.clone()
+        };
+        assert!(state_changed(Some(&state), &updated));
+    }
+
+    #[tokio::test]
+    async fn test_statusline_render_time_with_warm_state() {
+        let state = SessionState {
+            session_id: "test_statusline_render_time".to_string(),
+            git_branch: Some("main".to_string()),
+            git_dirty: Some(true),
+            git_dirty_count: Some(3),
+            ..create_test_state()
+        };
+        state.save().await.unwrap();
+
+        // Generous budget: loading and rendering should take well under a millisecond each
+        let start = std::time::Instant::now();
+        for _ in 0..100 {
+            let loaded = SessionState::load(&state.session_id).await.unwrap();
+            let statusline = build_statusline(
+                &loaded,
+                "Opus",
+                &PersonalityPreferences::default(),
+                None,
+                None,
+                Some(42),
+            );
+            assert!(!statusline.is_empty());
+        }
+        let elapsed = start.elapsed();
+
+        SessionState::cleanup(&state.session_id).await.unwrap();
+        assert!(
+            elapsed < std::time::Duration::from_secs(2),
+            "100 statusline renders took {elapsed:?}"
+        );
+    }
+
+    #[test]
+    fn test_debug_section_includes_mood() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            display: DisplayConfig {
+                show_debug_info: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut state = create_test_state();
+        state.mood.frustration_level = 4;
+        state.mood.momentum = 2;
+
+        let debug = render_debug_section(&state, &prefs).unwrap();
+        assert!(debug.contains("F:4"), "{debug}");
+        assert!(debug.contains("M:2"), "{debug}");
+        assert!(!debug.contains("LE:"), "{debug}");
+
+        state.mood.last_error_time = Some(0);
+        let debug = render_debug_section(&state, &prefs).unwrap();
+        assert!(debug.contains(" LE:"), "{debug}");
+    }
+
+    #[test]
+    fn test_forced_personality_and_activity() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            ..Default::default()
+        };
+        let state = create_test_state();
+        let original = state.clone();
+
+        let forced = forced_display_state(
+            &state,
+            Some("(◕‿◕) Demo".to_string()),
+            Some("testing".to_string()),
+        );
+        let statusline = build_statusline(&forced, "Opus", &prefs, None, None, None);
+        assert!(statusline.contains("(◕‿◕) Demo"), "{statusline}");
+        assert!(statusline.contains("Testing"), "{statusline}");
+        assert_eq!(state, original);
+
+        // No overrides borrows the state as-is
+        assert!(matches!(
+            forced_display_state(&state, None, None),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_theme_override() {
+        let mut prefs = PersonalityPreferences {
+            theme: Theme::Dracula,
+            ..Default::default()
+        };
+
+        apply_theme_override(&mut prefs, Some("nord".to_string()));
+        assert_eq!(prefs.theme, Theme::Nord);
+        assert_ne!(
+            prefs.theme.colors().directory.apply("my-project").fgcolor,
+            Theme::Dracula
+                .colors()
+                .directory
+                .apply("my-project")
+                .fgcolor
+        );
+
+        apply_theme_override(&mut prefs, Some("neon".to_string()));
+        assert_eq!(prefs.theme, Theme::Nord);
+
+        let mut saved = PersonalityPreferences {
+            theme: Theme::Dracula,
+            ..Default::default()
+        };
+        apply_theme_override(&mut saved, None);
+        assert_eq!(saved.theme, Theme::Dracula);
+    }
+
+    #[test]
+    fn test_git_stash_indicator() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            show_git_status: false,
+            show_git_stash: true,
+            git_branch_suffix: String::new(),
+            ..Default::default()
+        };
+        let state = SessionState {
+            git_branch: Some("main".to_string()),
+            git_stash_count: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&state, &prefs).as_deref(),
+            Some("main \u{2691}3")
+        );
+
+        let empty = SessionState {
+            git_stash_count: Some(0),
+            ..state.clone()
+        };
+        assert_eq!(render_git_section(&empty, &prefs).as_deref(), Some("main"));
+
+        let hidden = PersonalityPreferences {
+            show_git_stash: false,
+            ..prefs
+        };
+        assert_eq!(render_git_section(&state, &hidden).as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_detached_head_and_operation() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            show_git_status: false,
+            show_detached_head: true,
+            ..Default::default()
+        };
+        let state = SessionState {
+            git_branch: Some("main".to_string()),
+            git_detached_head: Some("a1b2c3d".to_string()),
+            git_operation: Some("rebasing".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&state, &prefs).as_deref(),
+            Some("@ a1b2c3d (rebasing)")
+        );
+
+        // Merges happen on a branch
+        let merging = SessionState {
+            git_detached_head: None,
+            git_operation: Some("merging".to_string()),
+            ..state.clone()
+        };
+        assert_eq!(
+            render_git_section(&merging, &prefs).as_deref(),
+            Some("main branch (merging)")
+        );
+
+        // Off by default: the last branch is shown as before
+        let hidden = PersonalityPreferences {
+            show_detached_head: false,
+            ..prefs
+        };
+        assert_eq!(
+            render_git_section(&state, &hidden).as_deref(),
+            Some("main branch")
+        );
+    }
+
+    #[test]
+    fn test_custom_git_status_glyphs() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            git_branch_suffix: String::new(),
+            display: DisplayConfig {
+                git_clean_glyph: "ok".to_string(),
+                git_dirty_glyph: "*".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let clean = SessionState {
+            git_branch: Some("main".to_string()),
+            git_dirty: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&clean, &prefs).as_deref(),
+            Some("main ok")
+        );
+
+        let dirty = SessionState {
+            git_dirty: Some(true),
+            git_dirty_count: Some(4),
+            ..clean.clone()
+        };
+        assert_eq!(
+            render_git_section(&dirty, &prefs).as_deref(),
+            Some("main *4")
+        );
+
+        let colored = PersonalityPreferences {
+            use_colors: true,
+            ..prefs
+        };
+        let text = render_git_section(&dirty, &colored).unwrap();
+        assert!(text.contains("*4"));
+        assert!(!text.contains('\u{00b1}'));
+    }
+
+    #[test]
+    fn test_git_branch_middle_truncation() {
+        let long = "feature/JIRA-1234-really-long-description";
+        let truncated = truncate_middle(long, 12);
+        assert_eq!(truncated, "featur\u{2026}ption");
+        assert_eq!(truncated.chars().count(), 12);
+
+        // Short names and a zero width are left alone
+        assert_eq!(truncate_middle("main", 12), "main");
+        assert_eq!(truncate_middle(long, 0), long);
+
+        let state = SessionState {
+            git_branch: Some(long.to_string()),
+            ..Default::default()
+        };
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            show_git_status: false,
+            git_branch_max_width: 12,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&state, &prefs).as_deref(),
+            Some("featur\u{2026}ption")
+        );
+    }
+
+    #[test]
+    fn test_git_element_order() {
+        let state = SessionState {
+            git_branch: Some("main".to_string()),
+            git_dirty: Some(true),
+            git_dirty_count: Some(3),
+            ..Default::default()
+        };
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            git_branch_suffix: String::new(),
+            git_status_style: GitStatusStyle::TextLabel,
+            git_element_order: vec!["status".to_string(), "branch".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&state, &prefs).as_deref(),
+            Some("dirty(3) main")
+        );
+
+        // The default order puts the icon first and the status last
+        let default_order = PersonalityPreferences {
+            git_element_order: PersonalityPreferences::default().git_element_order,
+            ..prefs.clone()
+        };
+        let icon = get_ui_icon(ICON_GIT_BRANCH, default_order.icon_mode);
+        assert_eq!(
+            render_git_section(&state, &default_order),
+            Some(format!("{icon} main dirty(3)"))
+        );
+
+        // Unknown or repeated names fall back to the default order
+        for invalid in [vec!["status", "bogus"], vec!["branch", "branch"], vec![]] {
+            let fallback = PersonalityPreferences {
+                git_element_order: invalid.iter().map(ToString::to_string).collect(),
+                ..prefs.clone()
+            };
+            assert_eq!(
+                render_git_section(&state, &fallback),
+                Some(format!("{icon} main dirty(3)")),
+                "{invalid:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_git_detailed_status() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            git_branch_suffix: String::new(),
+            git_detailed_status: true,
+            ..Default::default()
+        };
+        let state = SessionState {
+            git_branch: Some("main".to_string()),
+            git_dirty: Some(true),
+            git_dirty_count: Some(5),
+            git_modified_count: Some(3),
+            git_untracked_count: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_git_section(&state, &prefs).as_deref(),
+            Some("main ~3 +2")
+        );
+
+        let only_untracked = SessionState {
+            git_modified_count: Some(0),
+            ..state.clone()
+        };
+        assert_eq!(
+            render_git_section(&only_untracked, &prefs).as_deref(),
+            Some("main +2")
+        );
+
+        let labels = PersonalityPreferences {
+            git_status_style: GitStatusStyle::TextLabel,
+            ..prefs.clone()
+        };
+        assert_eq!(
+            render_git_section(&state, &labels).as_deref(),
+            Some("main dirty(~3 +2)")
+        );
+
+        // Without the split (e.g. Mercurial) or with the option off, the total shows
+        let unsplit = SessionState {
+            git_modified_count: None,
+            git_untracked_count: None,
+            ..state.clone()
+        };
+        let glyph = prefs.display.git_dirty_glyph.clone();
+        assert_eq!(
+            render_git_section(&unsplit, &prefs),
+            Some(format!("main {glyph}5"))
+        );
+        let summary = PersonalityPreferences {
+            git_detailed_status: false,
+            ..prefs
+        };
+        assert_eq!(
+            render_git_section(&state, &summary),
+            Some(format!("main {glyph}5"))
+        );
+    }
+
+    #[test]
+    fn test_git_status_text_labels() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_git_icon: false,
+            git_branch_suffix: String::new(),
+            git_status_style: GitStatusStyle::TextLabel,
+            ..Default::default()
+        };
+        let glyphs = [
+            prefs.display.git_clean_glyph.clone(),
+            prefs.display.git_dirty_glyph.clone(),
+        ];
+
+        let clean = SessionState {
+            git_branch: Some("main".to_string()),
+            git_dirty: Some(false),
+            ..Default::default()
+        };
+        let dirty = SessionState {
+            git_dirty: Some(true),
+            git_dirty_count: Some(3),
+            ..clean.clone()
+        };
+        let dirty_uncounted = SessionState {
+            git_dirty_count: None,
+            ..dirty.clone()
+        };
+        assert_eq!(
+            render_git_section(&clean, &prefs).as_deref(),
+            Some("main clean")
+        );
+        assert_eq!(
+            render_git_section(&dirty, &prefs).as_deref(),
+            Some("main dirty(3)")
+        );
+        assert_eq!(
+            render_git_section(&dirty_uncounted, &prefs).as_deref(),
+            Some("main dirty")
+        );
+
+        let colored = PersonalityPreferences {
+            use_colors: true,
+            ..prefs
+        };
+        for state in [&clean, &dirty] {
+            let text = render_git_section(state, &colored).unwrap();
+            assert!(text.contains("clean") || text.contains("dirty(3)"));
+            assert!(!glyphs.iter().any(|glyph| text.contains(glyph.as_str())));
+        }
+    }
+
+    #[test]
+    fn test_activity_trail_section() {
+        let prefs = PersonalityPreferences {
+            use_colors: false,
+            show_activity_trail: true,
+            ..Default::default()
+        };
+
+        let empty = create_test_state();
+        assert!(render_activity_trail_section(&empty, &prefs).is_none());
+
+        let state = SessionState {
+            recent_activities: vec![
+                Activity::Idle,
+                Activity::Reading,
+                Activity::Editing,
+                Activity::Testing,
+                Activity::Building,
+                Activity::Committing,
+            ],
+            ..create_test_state()
+        };
+        let expected = [
+            ICON_READING,
+            ICON_EDITING,
+            ICON_TESTING,
+            ICON_BUILDING,
+            ICON_GIT_BRANCH,
+        ]
+        .join(" ");
+        assert_eq!(
+            render_activity_trail_section(&state, &prefs).as_deref(),
+            Some(expected.as_str())
+        );
+
+        let hidden = PersonalityPreferences {
+            show_activity_trail: false,
+            ..prefs
+        };
+        assert!(render_activity_trail_section(&state, &hidden).is_none());
     }
 }
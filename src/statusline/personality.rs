@@ -3,9 +3,10 @@
 //! This module determines Claude's personality based on current activity,
 //! mood state, file types, and usage patterns.
 
+use crate::config::PersonalityPreferences;
 use crate::kaomoji::{
-    get_default_tool_kaomoji, get_file_kaomoji, get_mood_kaomoji, get_pattern_kaomoji,
-    get_time_kaomoji_for, get_tool_kaomoji,
+    BOOTING_UP, Kaomoji, get_default_tool_kaomoji, get_file_kaomoji, get_mood_kaomoji,
+    get_pattern_kaomoji, get_time_kaomoji_for, get_tool_kaomoji,
 };
 use crate::state::{PersonalityModifier, SessionState};
 use chrono::{DateTime, Local};
@@ -13,16 +14,18 @@ use chrono::{DateTime, Local};
 /// Determine the appropriate personality based on context and state
 pub fn determine_personality(
     state: &SessionState,
+    prefs: &PersonalityPreferences,
     tool_name: &str,
     file_path: Option<&str>,
     command: Option<&str>,
 ) -> String {
-    determine_personality_at(state, tool_name, file_path, command, Local::now())
+    determine_personality_at(state, prefs, tool_name, file_path, command, Local::now())
 }
 
 /// Determine personality at a specific time (used for testing)
 pub fn determine_personality_at(
     state: &SessionState,
+    prefs: &PersonalityPreferences,
     tool_name: &str,
     file_path: Option<&str>,
     command: Option<&str>,
@@ -30,11 +33,7 @@ pub fn determine_personality_at(
 ) -> String {
     // Check for frustrated mood first (highest priority)
     if let PersonalityModifier::Frustrated = state.mood.get_personality_modifier() {
-        let kaomoji = get_mood_kaomoji(
-            &state.mood.get_personality_modifier(),
-            state.mood.frustration_level,
-        );
-        return kaomoji.personality();
+        return mood_personality(state, prefs);
     }
 
     // Check tool-specific personalities (only high-priority ones like Bash git commands, Grep)
@@ -48,8 +47,12 @@ pub fn determine_personality_at(
     }
 
     // Check consecutive action patterns (including extreme cases like Code Berserker)
-    if let Some(kaomoji) = get_pattern_kaomoji(state.consecutive_actions) {
-        return kaomoji.personality();
+    if let Some(kaomoji) = get_pattern_kaomoji(
+        state.consecutive_actions,
+        prefs.hyperfocus_threshold,
+        prefs.berserker_threshold,
+    ) {
+        return from_pack(prefs, kaomoji).personality();
     }
 
     // Check for time-based personalities (before defaults)
@@ -59,16 +62,47 @@ pub fn determine_personality_at(
 
     // Check for InTheZone mood (lower priority than pattern personalities)
     if let PersonalityModifier::InTheZone = state.mood.get_personality_modifier() {
-        let kaomoji = get_mood_kaomoji(
-            &state.mood.get_personality_modifier(),
-            state.mood.frustration_level,
-        );
-        return kaomoji.personality();
+        return mood_personality(state, prefs);
     }
 
     // Default tool personalities (lowest priority)
     let kaomoji = get_default_tool_kaomoji(tool_name, state.consecutive_actions);
-    kaomoji.personality()
+    if *kaomoji == BOOTING_UP
+        && let Some(personality) = prefs.default_personality()
+    {
+        return personality.to_string();
+    }
+    // A custom normal face only replaces the normal mood's own face
+    let face = if kaomoji == get_mood_kaomoji(&PersonalityModifier::Normal, 0) {
+        prefs.mood_face(&PersonalityModifier::Normal)
+    } else {
+        None
+    };
+    let kaomoji = from_pack(prefs, kaomoji);
+    match face {
+        Some(face) => format!("{face} {}", kaomoji.description),
+        None => kaomoji.personality(),
+    }
+}
+
+/// Swap a built-in face for the selected kaomoji pack's version, if any
+fn from_pack(prefs: &PersonalityPreferences, kaomoji: &'static Kaomoji) -> &'static Kaomoji {
+    prefs
+        .kaomoji_pack()
+        .map_or(kaomoji, |pack| pack.remap(kaomoji))
+}
+
+/// Mood personality, using the configured face for the mood when one is set
+fn mood_personality(state: &SessionState, prefs: &PersonalityPreferences) -> String {
+    let modifier = state.mood.get_personality_modifier();
+    let kaomoji = from_pack(
+        prefs,
+        get_mood_kaomoji(&modifier, state.mood.frustration_level),
+    );
+    match prefs.mood_face(&modifier) {
+        Some(face) => format!("{face} {}", kaomoji.description),
+        None => kaomoji.personality(),
+    }
 }
 
 #[cfg(test)]
@@ -100,16 +134,30 @@ mod tests {
             git_branch: None,
             git_dirty: None,
             git_dirty_count: None,
+            git_modified_count: None,
+            git_untracked_count: None,
             git_status_checked_at: None,
+            git_stash_count: None,
+            git_stash_checked_at: None,
+            git_detached_head: None,
+            git_operation: None,
             personality: "Test".to_string(),
             previous_personality: None,
             consecutive_actions,
+            total_actions: consecutive_actions,
+            last_updated_at: None,
+            session_started_at: None,
+            celebrate_until: None,
             error_count,
             recent_activities: Vec::new(),
             mood,
         }
     }
 
+    fn default_prefs() -> PersonalityPreferences {
+        PersonalityPreferences::default()
+    }
+
     // Helper to create a test time (10 AM on Tuesday - no time-based personality)
     fn test_time() -> DateTime<Local> {
         Local.with_ymd_and_hms(2024, 1, 2, 10, 0, 0).unwrap()
@@ -120,15 +168,18 @@ mod tests {
         let time = test_time();
 
         let state = create_test_state(5, 0);
-        let personality = determine_personality_at(&state, "Edit", None, None, time);
+        let personality =
+            determine_personality_at(&state, &default_prefs(), "Edit", None, None, time);
         assert_eq!(personality, "(╯°□°)╯︵ ┻━┻ Table Flipper");
 
         let state = create_test_state(3, 0);
-        let personality = determine_personality_at(&state, "Edit", None, None, time);
+        let personality =
+            determine_personality_at(&state, &default_prefs(), "Edit", None, None, time);
         assert_eq!(personality, "(ノಠ益ಠ)ノ Error Warrior");
 
         let state = create_test_state(2, 0);
-        let personality = determine_personality_at(&state, "Edit", None, None, time);
+        let personality =
+            determine_personality_at(&state, &default_prefs(), "Edit", None, None, time);
         assert_eq!(personality, "ლ(╹◡╹ლ) Cowder");
     }
 
@@ -136,14 +187,85 @@ mod tests {
     fn test_git_operations() {
         let state = create_test_state(0, 0);
 
-        let personality = determine_personality(&state, "Bash", None, Some("git status"));
+        let personality =
+            determine_personality(&state, &default_prefs(), "Bash", None, Some("git status"));
         assert_eq!(personality, "┗(▀̿Ĺ̯▀̿ ̿)┓ Git Manager");
 
-        let personality = determine_personality(&state, "Bash", None, Some("git commit -m 'test'"));
+        let personality = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("git commit -m 'test'"),
+        );
         assert_eq!(personality, "┗(▀̿Ĺ̯▀̿ ̿)┓ Git Manager");
 
-        let personality = determine_personality(&state, "Bash", None, Some("ls -la"));
+        let personality =
+            determine_personality(&state, &default_prefs(), "Bash", None, Some("ls -la"));
         assert_ne!(personality, "┗(▀̿Ĺ̯▀̿ ̿)┓ Git Manager");
+
+        let personality =
+            determine_personality(&state, &default_prefs(), "Bash", None, Some("cargo clippy"));
+        assert_eq!(personality, "৻( •̀ ᗜ •́ ৻) Quality Auditor");
+
+        // Tool names inside paths or arguments aren't linter runs
+        let personality = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("cat docs/eslint-migration.md"),
+        );
+        assert_ne!(personality, "৻( •̀ ᗜ •́ ৻) Quality Auditor");
+        let personality = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("npx eslint src"),
+        );
+        assert_eq!(personality, "৻( •̀ ᗜ •́ ৻) Quality Auditor");
+    }
+
+    #[test]
+    fn test_infra_plan_vs_apply() {
+        let state = create_test_state(0, 0);
+
+        let plan = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("terraform -chdir=infra plan"),
+        );
+        assert_eq!(plan, "(￣ー￣)φ Cautious Planner");
+
+        let apply = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("terraform apply -auto-approve"),
+        );
+        assert_eq!(apply, "( ͡ _ ͡°)ﾉ⚲ Deployment Guard");
+
+        let kubectl = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("kubectl apply -f deploy.yaml"),
+        );
+        assert_eq!(kubectl, "( ͡ _ ͡°)ﾉ⚲ Deployment Guard");
+
+        let namespaced = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            None,
+            Some("kubectl -n prod diff -f deploy.yaml"),
+        );
+        assert_eq!(namespaced, "(￣ー￣)φ Cautious Planner");
     }
 
     #[test]
@@ -151,18 +273,47 @@ mod tests {
         let state = create_test_state(0, 0);
 
         // JavaScript files
-        let personality = determine_personality(&state, "Edit", Some("test.js"), None);
+        let personality =
+            determine_personality(&state, &default_prefs(), "Edit", Some("test.js"), None);
         assert_eq!(personality, "(▀̿Ĺ̯▀̿ ̿) JS Master");
 
-        let personality = determine_personality(&state, "Edit", Some("component.tsx"), None);
+        let personality = determine_personality(
+            &state,
+            &default_prefs(),
+            "Edit",
+            Some("component.tsx"),
+            None,
+        );
         assert_eq!(personality, "(✿◠ᴗ◠) UI Developer");
 
         // Documentation
-        let personality = determine_personality(&state, "Edit", Some("README.md"), None);
+        let personality =
+            determine_personality(&state, &default_prefs(), "Edit", Some("README.md"), None);
         assert_eq!(personality, "φ(．．) Documentation Writer");
 
         // Auth files
-        let personality = determine_personality(&state, "Edit", Some("auth.js"), None);
+        let personality =
+            determine_personality(&state, &default_prefs(), "Edit", Some("auth.js"), None);
+        assert_eq!(personality, "ಠ_ಠ Security Analyst");
+    }
+
+    #[test]
+    fn test_language_personalities() {
+        let state = create_test_state(0, 0);
+        let cases = [
+            ("src/main.rs", "(ง •̀_•́)ง Borrow Checker Whisperer"),
+            ("cmd/main.go", "ʕ◔ϖ◔ʔ Gopher"),
+            ("main.py", "∼(ᵔ◡ᵔ)∼ Snake Charmer"),
+        ];
+        for (file, expected) in cases {
+            let personality =
+                determine_personality(&state, &default_prefs(), "Edit", Some(file), None);
+            assert_eq!(personality, expected, "{file}");
+        }
+
+        // More specific file checks still win over the language
+        let personality =
+            determine_personality(&state, &default_prefs(), "Edit", Some("src/auth.rs"), None);
         assert_eq!(personality, "ಠ_ಠ Security Analyst");
     }
 
@@ -170,19 +321,234 @@ mod tests {
     fn test_consecutive_actions() {
         // Hyperfocused coder
         let state = create_test_state(0, 15);
-        let personality = determine_personality(&state, "Edit", None, None);
+        let personality = determine_personality(&state, &default_prefs(), "Edit", None, None);
         assert_eq!(personality, "┌༼◉ل͟◉༽┐ Hyperfocused Coder");
 
         // Code berserker
         let state = create_test_state(0, 25);
-        let personality = determine_personality(&state, "Edit", None, None);
+        let personality = determine_personality(&state, &default_prefs(), "Edit", None, None);
+        assert_eq!(personality, "【╯°□°】╯︵ ┻━┻ Code Berserker");
+    }
+
+    #[test]
+    fn test_custom_pattern_thresholds() {
+        let prefs = PersonalityPreferences {
+            hyperfocus_threshold: 3,
+            berserker_threshold: 6,
+            ..PersonalityPreferences::default()
+        };
+
+        let state = create_test_state(0, 5);
+        let personality = determine_personality(&state, &prefs, "Edit", None, None);
+        assert_eq!(personality, "┌༼◉ل͟◉༽┐ Hyperfocused Coder");
+
+        let state = create_test_state(0, 7);
+        let personality = determine_personality(&state, &prefs, "Edit", None, None);
         assert_eq!(personality, "【╯°□°】╯︵ ┻━┻ Code Berserker");
     }
 
+    #[test]
+    fn test_custom_mood_faces() {
+        let mut mood = crate::state::MoodState::default();
+        while mood.momentum < 10 {
+            mood.update(false);
+        }
+        let state = SessionState {
+            mood,
+            ..create_test_state(0, 0)
+        };
+        let prefs = PersonalityPreferences {
+            in_the_zone_face: Some("(•̀ᴗ•́)و".to_string()),
+            ..PersonalityPreferences::default()
+        };
+
+        let personality =
+            determine_personality_at(&state, &prefs, "OtherTool", None, None, test_time());
+        assert_eq!(personality, "(•̀ᴗ•́)و Hyperfocused Coder");
+
+        // Empty faces fall back to the built-in ones
+        let prefs = PersonalityPreferences {
+            in_the_zone_face: Some("  ".to_string()),
+            ..PersonalityPreferences::default()
+        };
+        let personality =
+            determine_personality_at(&state, &prefs, "OtherTool", None, None, test_time());
+        assert_eq!(personality, "┌༼◉ل͟◉༽┐ Hyperfocused Coder");
+
+        let prefs = PersonalityPreferences {
+            frustrated_face: Some("(>_<)".to_string()),
+            ..PersonalityPreferences::default()
+        };
+        let state = create_test_state(3, 0);
+        let personality = determine_personality_at(&state, &prefs, "Edit", None, None, test_time());
+        assert_eq!(personality, "(>_<) Error Warrior");
+    }
+
+    #[test]
+    fn test_custom_normal_face() {
+        let state = create_test_state(0, 0);
+        let prefs = PersonalityPreferences {
+            normal_face: Some("(o_o)".to_string()),
+            ..PersonalityPreferences::default()
+        };
+
+        // Editing with no other context shows the normal mood's face
+        let personality = determine_personality_at(&state, &prefs, "Edit", None, None, test_time());
+        assert_eq!(
+            personality,
+            format!("(o_o) {}", get_default_tool_kaomoji("Edit", 0).description)
+        );
+
+        // Other default tool faces and the idle face are left alone
+        for tool in ["Read", "Write", "OtherTool"] {
+            let personality =
+                determine_personality_at(&state, &prefs, tool, None, None, test_time());
+            assert_eq!(
+                personality,
+                get_default_tool_kaomoji(tool, 0).personality(),
+                "{tool}"
+            );
+        }
+
+        // Other moods keep their own faces
+        let frustrated = create_test_state(3, 0);
+        let personality =
+            determine_personality_at(&frustrated, &prefs, "Edit", None, None, test_time());
+        assert!(!personality.starts_with("(o_o)"), "{personality}");
+    }
+
+    #[test]
+    fn test_custom_default_personality() {
+        let state = create_test_state(0, 0);
+        let prefs = PersonalityPreferences {
+            default_personality: Some("(-_-) Zen Mode".to_string()),
+            ..PersonalityPreferences::default()
+        };
+
+        // An unrecognized tool on an unrecognized file gets the custom default
+        let personality = determine_personality_at(
+            &state,
+            &prefs,
+            "OtherTool",
+            Some("notes.xyz"),
+            None,
+            test_time(),
+        );
+        assert_eq!(personality, "(-_-) Zen Mode");
+
+        // Recognized tools keep their own personality
+        let personality = determine_personality_at(&state, &prefs, "Read", None, None, test_time());
+        assert_ne!(personality, "(-_-) Zen Mode");
+
+        // Empty falls back to the built-in default
+        let prefs = PersonalityPreferences {
+            default_personality: Some(" ".to_string()),
+            ..PersonalityPreferences::default()
+        };
+        let personality =
+            determine_personality_at(&state, &prefs, "OtherTool", None, None, test_time());
+        assert_eq!(personality, "( ˘ ³˘) Chillin");
+    }
+
+    #[test]
+    fn test_kaomoji_pack_faces() {
+        let cats = PersonalityPreferences {
+            kaomoji_pack: Some("cats".to_string()),
+            ..PersonalityPreferences::default()
+        };
+
+        // Normal: the default Edit face
+        let state = create_test_state(0, 0);
+        let personality = determine_personality_at(&state, &cats, "Edit", None, None, test_time());
+        assert_eq!(personality, "(=^･ω･^=) Code Kitty");
+
+        // In the zone
+        let mut mood = crate::state::MoodState::default();
+        while mood.momentum < 10 {
+            mood.update(false);
+        }
+        let state = SessionState {
+            mood,
+            ..create_test_state(0, 0)
+        };
+        let personality =
+            determine_personality_at(&state, &cats, "OtherTool", None, None, test_time());
+        assert_eq!(personality, "(=◉ω◉=) Laser-Pointer Focus");
+
+        // Tool faces are not part of packs
+        let personality = determine_personality(&state, &cats, "Grep", None, None);
+        assert_eq!(personality, "(つ◉益◉)つ Bug Hunter");
+    }
+
+    #[test]
+    fn test_multiedit_refactor_surgeon() {
+        let state = create_test_state(0, 0);
+        let personality =
+            determine_personality(&state, &default_prefs(), "MultiEdit", Some("main.rs"), None);
+        assert_eq!(personality, "(•̀ᴗ•́)ノ✄ Refactor Surgeon");
+
+        // File-type faces only apply to single edits
+        let personality =
+            determine_personality(&state, &default_prefs(), "MultiEdit", Some("test.js"), None);
+        assert_eq!(personality, "(•̀ᴗ•́)ノ✄ Refactor Surgeon");
+        let personality =
+            determine_personality(&state, &default_prefs(), "Edit", Some("test.js"), None);
+        assert_eq!(personality, "(▀̿Ĺ̯▀̿ ̿) JS Master");
+    }
+
+    #[test]
+    fn test_git_clone_net_retriever() {
+        let state = create_test_state(0, 0);
+        let prefs = default_prefs();
+        let personality = determine_personality(
+            &state,
+            &prefs,
+            "Bash",
+            None,
+            Some("git clone https://github.com/x/y.git"),
+        );
+        assert_eq!(personality, "(っ◔◡◔)っ ⇩ Net Retriever");
+
+        let personality = determine_personality(&state, &prefs, "Bash", None, Some("git status"));
+        assert!(personality.ends_with("Git Manager"));
+
+        // Same parsing as the Fetching activity: quoted text and lookalikes don't count
+        let personality = determine_personality(
+            &state,
+            &prefs,
+            "Bash",
+            None,
+            Some("git commit -m \"git clone notes\""),
+        );
+        assert!(personality.ends_with("Git Manager"));
+        let personality = determine_personality(
+            &state,
+            &prefs,
+            "Bash",
+            None,
+            Some("curl -fsSLO https://example.com/tool.tar.gz"),
+        );
+        assert!(personality.ends_with("Net Retriever"));
+    }
+
+    #[test]
+    fn test_todo_write_task_orchestrator() {
+        let state = create_test_state(0, 0);
+        let personality = determine_personality(&state, &default_prefs(), "TodoWrite", None, None);
+        assert_eq!(personality, "(￣^￣)ゞ Task Orchestrator");
+    }
+
+    #[test]
+    fn test_webfetch_web_surfer() {
+        let state = create_test_state(0, 0);
+        let personality = determine_personality(&state, &default_prefs(), "WebFetch", None, None);
+        assert_eq!(personality, "(~˘▾˘)~ Web Surfer");
+    }
+
     #[test]
     fn test_grep_debugging() {
         let state = create_test_state(0, 0);
-        let personality = determine_personality(&state, "Grep", None, None);
+        let personality = determine_personality(&state, &default_prefs(), "Grep", None, None);
         assert_eq!(personality, "(つ◉益◉)つ Bug Hunter");
     }
 
@@ -190,18 +556,25 @@ mod tests {
     fn test_priority_order() {
         // Error states should override everything
         let state = create_test_state(5, 0);
-        let personality = determine_personality(&state, "Bash", None, Some("git status"));
+        let personality =
+            determine_personality(&state, &default_prefs(), "Bash", None, Some("git status"));
         assert_eq!(personality, "(╯°□°)╯︵ ┻━┻ Table Flipper");
 
         // Git should override file types
         let state = create_test_state(0, 0);
-        let personality =
-            determine_personality(&state, "Bash", Some("test.js"), Some("git status"));
+        let personality = determine_personality(
+            &state,
+            &default_prefs(),
+            "Bash",
+            Some("test.js"),
+            Some("git status"),
+        );
         assert_eq!(personality, "┗(▀̿Ĺ̯▀̿ ̿)┓ Git Manager");
 
         // File types should override tool defaults
         let state = create_test_state(0, 0);
-        let personality = determine_personality(&state, "Edit", Some("README.md"), None);
+        let personality =
+            determine_personality(&state, &default_prefs(), "Edit", Some("README.md"), None);
         assert_eq!(personality, "φ(．．) Documentation Writer");
 
         // Pattern should override time (not easily testable with system time dependency, but conceptually true)
@@ -213,22 +586,50 @@ mod tests {
 
         // Test Night Owl (2 AM)
         let night_time = Local.with_ymd_and_hms(2024, 1, 2, 2, 0, 0).unwrap();
-        let personality = determine_personality_at(&state, "OtherTool", None, None, night_time);
+        let personality = determine_personality_at(
+            &state,
+            &default_prefs(),
+            "OtherTool",
+            None,
+            None,
+            night_time,
+        );
         assert_eq!(personality, "(ʘ,ʘ) Night Owl");
 
         // Test Caffeinated (6 AM)
         let morning_time = Local.with_ymd_and_hms(2024, 1, 2, 6, 0, 0).unwrap();
-        let personality = determine_personality_at(&state, "OtherTool", None, None, morning_time);
+        let personality = determine_personality_at(
+            &state,
+            &default_prefs(),
+            "OtherTool",
+            None,
+            None,
+            morning_time,
+        );
         assert_eq!(personality, "( -_-)旦~ Caffeinated");
 
         // Test TGIFFFFF (Friday 6 PM)
         let friday_evening = Local.with_ymd_and_hms(2024, 1, 5, 18, 0, 0).unwrap(); // Jan 5, 2024 is Friday
-        let personality = determine_personality_at(&state, "OtherTool", None, None, friday_evening);
+        let personality = determine_personality_at(
+            &state,
+            &default_prefs(),
+            "OtherTool",
+            None,
+            None,
+            friday_evening,
+        );
         assert_eq!(personality, "ヽ(⌐■_■)ノ♪♬ TGIFFFFF");
 
         // Test default (10 AM Tuesday - no time-based personality)
         let default_time = test_time();
-        let personality = determine_personality_at(&state, "OtherTool", None, None, default_time);
+        let personality = determine_personality_at(
+            &state,
+            &default_prefs(),
+            "OtherTool",
+            None,
+            None,
+            default_time,
+        );
         assert_eq!(personality, "( ˘ ³˘) Chillin");
     }
 }
@@ -0,0 +1,117 @@
+//! tmux status line output
+//!
+//! Translates the ANSI escape sequences produced by the statusline renderer into
+//! tmux's `#[fg=...]` style syntax so the statusline can be embedded in
+//! `status-right` via `#(claude-code-personalities --statusline --tmux)`.
+//!
+//! tmux mode has no Claude Code input, so it renders from the persisted session
+//! state only: the personality, activity, activity trail, action count, and
+//! debug sections work as usual; git shows the last cached branch and status;
+//! the model section shows a generic name; the directory, update, and context
+//! usage sections are omitted.
+
+use crate::statusline::format::{AnsiSegment, AnsiSegments};
+
+/// Convert ANSI SGR color sequences into tmux style directives.
+///
+/// Literal `#` characters are doubled so tmux does not treat them as format
+/// sequences. Unsupported SGR parameters are dropped.
+#[must_use]
+pub fn ansi_to_tmux(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for segment in AnsiSegments::new(input) {
+        match segment {
+            AnsiSegment::Text(text) => output.push_str(&text.replace('#', "##")),
+            AnsiSegment::Sgr(params) => {
+                let styles = sgr_to_tmux_styles(params);
+                if !styles.is_empty() {
+                    output.push_str(&format!("#[{}]", styles.join(",")));
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Map the parameters of one SGR sequence (e.g. `1;38;2;255;0;0`) to tmux styles
+fn sgr_to_tmux_styles(params: &str) -> Vec<String> {
+    let codes: Vec<u16> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let mut styles = Vec::new();
+    let mut index = 0;
+
+    while index < codes.len() {
+        let code = codes[index];
+        match code {
+            0 => styles.push("default".to_string()),
+            1 => styles.push("bold".to_string()),
+            2 => styles.push("dim".to_string()),
+            3 => styles.push("italics".to_string()),
+            4 => styles.push("underscore".to_string()),
+            7 => styles.push("reverse".to_string()),
+            22 => styles.push("nobold,nodim".to_string()),
+            39 => styles.push("fg=default".to_string()),
+            49 => styles.push("bg=default".to_string()),
+            30..=37 => styles.push(format!("fg=colour{}", code - 30)),
+            40..=47 => styles.push(format!("bg=colour{}", code - 40)),
+            90..=97 => styles.push(format!("fg=colour{}", code - 90 + 8)),
+            100..=107 => styles.push(format!("bg=colour{}", code - 100 + 8)),
+            38 | 48 => {
+                let target = if code == 38 { "fg" } else { "bg" };
+                match codes.get(index + 1) {
+                    Some(5) => {
+                        if let Some(colour) = codes.get(index + 2) {
+                            styles.push(format!("{target}=colour{colour}"));
+                        }
+                        index += 2;
+                    }
+                    Some(2) => {
+                        if let [r, g, b] = codes.get(index + 2..index + 5).unwrap_or_default() {
+                            styles.push(format!("{target}=#{r:02x}{g:02x}{b:02x}"));
+                        }
+                        index += 4;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    styles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truecolor_translation() {
+        let ansi = "\u{1b}[1;38;2;255;121;198mOpus\u{1b}[0m";
+        assert_eq!(ansi_to_tmux(ansi), "#[bold,fg=#ff79c6]Opus#[default]");
+    }
+
+    #[test]
+    fn test_256_and_basic_colors() {
+        assert_eq!(
+            ansi_to_tmux("\u{1b}[38;5;244mdim\u{1b}[39m"),
+            "#[fg=colour244]dim#[fg=default]"
+        );
+        assert_eq!(ansi_to_tmux("\u{1b}[31mred"), "#[fg=colour1]red");
+        assert_eq!(
+            ansi_to_tmux("\u{1b}[48;2;0;0;0mbg\u{1b}[49m"),
+            "#[bg=#000000]bg#[bg=default]"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_and_hash_escaping() {
+        assert_eq!(ansi_to_tmux("issue #42"), "issue ##42");
+        assert_eq!(ansi_to_tmux("plain"), "plain");
+    }
+}
@@ -0,0 +1,161 @@
+//! Cross-session day streak: how many days in a row Claude has been used
+//!
+//! Unlike session state, which lives in the temp directory and is removed when a
+//! session ends, the streak is kept in a small file next to the preferences so it
+//! survives sessions and reboots. It is written at most once a day, on the first
+//! activity of that day, and every read or write is best effort. Writes are atomic,
+//! and a streak file that fails to parse is left alone instead of being reset.
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::atomic_write::write_atomically_blocking;
+use crate::config::PersonalityPreferences;
+
+/// File name of the streak file, stored beside the preferences file
+pub const STREAK_FILE_NAME: &str = "personalities_streak.json";
+
+/// Days in a row with activity, up to the most recent active day
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayStreak {
+    /// Most recent day with activity
+    pub last_active: Option<NaiveDate>,
+    /// Consecutive active days ending on `last_active`
+    pub days: u32,
+}
+
+impl DayStreak {
+    /// Record activity on `today`, returning whether the streak changed.
+    ///
+    /// The day after the last active day extends the streak; any longer gap
+    /// starts a new one. Further activity on the same day changes nothing.
+    pub fn record(&mut self, today: NaiveDate) -> bool {
+        match self.last_active {
+            Some(last) if last == today => return false,
+            Some(last) if last.succ_opt() == Some(today) => self.days += 1,
+            _ => self.days = 1,
+        }
+        self.last_active = Some(today);
+        true
+    }
+
+    /// The streak as of `today`: zero once a whole day has passed without activity
+    #[must_use]
+    pub fn current(&self, today: NaiveDate) -> u32 {
+        match self.last_active {
+            Some(last) if last == today || last.succ_opt() == Some(today) => self.days,
+            _ => 0,
+        }
+    }
+
+    /// Read the streak from `path`, starting fresh if it is missing or unreadable
+    #[must_use]
+    pub fn load(path: &Path) -> Self {
+        Self::read(path).unwrap_or_default()
+    }
+
+    /// Read the streak from `path`, starting fresh only if there is no file yet
+    fn read(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write the streak to `path`
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_atomically_blocking(path, &serde_json::to_string(self)?)
+    }
+}
+
+/// Location of the streak file, beside the preferences file
+#[must_use]
+pub fn streak_path() -> Option<PathBuf> {
+    PersonalityPreferences::get_preferences_path()
+        .ok()
+        .map(|path| path.with_file_name(STREAK_FILE_NAME))
+}
+
+/// Count today as an active day. Failures are ignored.
+pub fn record_activity_today() {
+    if let Some(path) = streak_path() {
+        let _ = record_activity(&path, Local::now().date_naive());
+    }
+}
+
+/// Record activity on `today` in the streak file at `path`, writing only when it
+/// changes. A file that exists but doesn't parse is never overwritten.
+fn record_activity(path: &Path, today: NaiveDate) -> std::io::Result<()> {
+    let mut streak = DayStreak::read(path)?;
+    if streak.record(today) {
+        streak.save(path)?;
+    }
+    Ok(())
+}
+
+/// The current day streak, or zero when there is no streak file
+#[must_use]
+pub fn current_streak() -> u32 {
+    streak_path().map_or(0, |path| {
+        DayStreak::load(&path).current(Local::now().date_naive())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_streak_grows_on_consecutive_days() {
+        let mut streak = DayStreak::default();
+        assert!(streak.record(day(1)));
+        assert!(streak.record(day(2)));
+        // A second activity the same day doesn't count twice
+        assert!(!streak.record(day(2)));
+        assert!(streak.record(day(3)));
+        assert_eq!(streak.days, 3);
+        assert_eq!(streak.current(day(3)), 3);
+        // Still alive the next day, before anything has happened yet
+        assert_eq!(streak.current(day(4)), 3);
+    }
+
+    #[test]
+    fn test_streak_resets_after_a_gap() {
+        let mut streak = DayStreak::default();
+        streak.record(day(1));
+        streak.record(day(2));
+        assert_eq!(streak.current(day(4)), 0);
+
+        streak.record(day(4));
+        assert_eq!(streak.days, 1);
+        assert_eq!(streak.current(day(4)), 1);
+    }
+
+    #[test]
+    fn test_streak_persists_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STREAK_FILE_NAME);
+        assert_eq!(DayStreak::load(&path), DayStreak::default());
+
+        record_activity(&path, day(30)).unwrap();
+        record_activity(&path, day(31)).unwrap();
+        record_activity(&path, day(31)).unwrap();
+
+        let streak = DayStreak::load(&path);
+        assert_eq!(streak.days, 2);
+        assert_eq!(streak.last_active, Some(day(31)));
+
+        // Corrupt files read as no streak, but recording leaves them alone
+        let torn = r#"{"last_active":"2026-10-31","da"#;
+        std::fs::write(&path, torn).unwrap();
+        assert_eq!(DayStreak::load(&path), DayStreak::default());
+        assert!(record_activity(&path, day(31)).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), torn);
+    }
+}
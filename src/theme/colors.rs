@@ -1,7 +1,7 @@
 use colored::{ColoredString, Colorize};
 
 /// Color wrapper for theme support
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Color {
     /// RGB color for regular themes
     Rgb { r: u8, g: u8, b: u8 },
@@ -26,22 +26,79 @@ impl Color {
         Self::Terminal256(index)
     }
 
-    /// Convert terminal 256-color index to RGB values
-    fn terminal_256_to_rgb(index: u8) -> (u8, u8, u8) {
+    /// RGB values of an xterm 256-color palette index
+    pub(crate) fn terminal_256_to_rgb(index: u8) -> (u8, u8, u8) {
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 0, 0),
+            (0, 205, 0),
+            (205, 205, 0),
+            (0, 0, 238),
+            (205, 0, 205),
+            (0, 205, 205),
+            (229, 229, 229),
+            (127, 127, 127),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (92, 92, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+        const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
         match index {
-            32 => (0, 135, 175),    // Teal (Haiku model)
-            69 => (95, 175, 255),   // Bright blue-cyan (activity)
-            82 => (95, 255, 0),     // Bright green (success)
-            121 => (135, 255, 175), // Light purple (Sonnet model)
-            208 => (255, 135, 0),   // Orange (warning)
-            226 => (255, 255, 0),   // Yellow (Opus model)
-            231 => (255, 255, 255), // Bright white (files/directory)
-            234 => (28, 28, 28),    // Dark gray (separators)
-            254 => (228, 228, 228), // Very light gray (personalities)
-            _ => (128, 128, 128),   // Default gray
+            0..=15 => BASIC[usize::from(index)],
+            16..=231 => {
+                let cube = usize::from(index - 16);
+                (
+                    CUBE_LEVELS[cube / 36],
+                    CUBE_LEVELS[(cube / 6) % 6],
+                    CUBE_LEVELS[cube % 6],
+                )
+            }
+            _ => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
         }
     }
 
+    /// RGB components, resolving palette indexes
+    fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Self::Rgb { r, g, b } => (*r, *g, *b),
+            Self::Terminal256(index) => Self::terminal_256_to_rgb(*index),
+        }
+    }
+
+    /// SGR sequence that sets this color as the foreground
+    #[must_use]
+    pub fn foreground_code(&self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("\x1b[38;2;{r};{g};{b}m")
+    }
+
+    /// SGR sequence that sets this color as the background
+    #[must_use]
+    pub fn background_code(&self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("\x1b[48;2;{r};{g};{b}m")
+    }
+
+    /// Blend toward `target` by `t` (0.0 keeps this color, 1.0 gives `target`)
+    #[must_use]
+    pub fn lerp(&self, target: &Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let (r1, g1, b1) = self.rgb();
+        let (r2, g2, b2) = target.rgb();
+        let mix = |from: u8, to: u8| {
+            (f32::from(from) + (f32::from(to) - f32::from(from)) * t).round() as u8
+        };
+        Color::new(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+    }
+
     /// Apply this color to a string
     pub fn apply<T: AsRef<str>>(&self, text: T) -> ColoredString {
         match self {
@@ -63,67 +120,90 @@ impl Color {
             }
         }
     }
+}
 
-    /// Apply this color as foreground with a dimmed version as background
-    pub fn apply_with_dim_background<T: AsRef<str>>(&self, text: T) -> ColoredString {
-        match self {
-            Self::Rgb { r, g, b } => {
-                let bg_r = ((*r as f32) * 0.25) as u8;
-                let bg_g = ((*g as f32) * 0.25) as u8;
-                let bg_b = ((*b as f32) * 0.25) as u8;
-                text.as_ref()
-                    .truecolor(*r, *g, *b)
-                    .on_truecolor(bg_r, bg_g, bg_b)
-            }
-            Self::Terminal256(index) => {
-                let (r, g, b) = Self::terminal_256_to_rgb(*index);
-                let bg_r = ((r as f32) * 0.25) as u8;
-                let bg_g = ((g as f32) * 0.25) as u8;
-                let bg_b = ((b as f32) * 0.25) as u8;
-                text.as_ref()
-                    .truecolor(r, g, b)
-                    .on_truecolor(bg_r, bg_g, bg_b)
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    /// Parse `#RRGGBB` hex or a 256-color palette index (`0`-`255`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = s.trim();
+        if let Some(hex) = spec.strip_prefix('#') {
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!(
+                    "invalid hex color '{spec}': expected # followed by 6 hex digits (0-9, a-f)"
+                ));
             }
+            let value = u32::from_str_radix(hex, 16)
+                .map_err(|e| format!("invalid hex color '{spec}': {e}"))?;
+            return Ok(Self::from_hex(value));
+        }
+
+        match spec.parse::<u32>() {
+            Ok(index) => u8::try_from(index)
+                .map(Self::from_terminal_256)
+                .map_err(|_| format!("256-color index {index} is out of range (0-255)")),
+            Err(_) => Err(format!(
+                "invalid color '{spec}': use #RRGGBB or a 256-color index (0-255)"
+            )),
         }
     }
+}
 
-    /// Apply this color with dim background using background-only reset
-    pub fn apply_with_inverse<T: AsRef<str>>(&self, text: T) -> String {
-        match self {
-            Self::Rgb { r, g, b } => {
-                // Use dim background with bright foreground and background-only reset
-                let bg_r = ((*r as f32) * 0.25) as u8;
-                let bg_g = ((*g as f32) * 0.25) as u8;
-                let bg_b = ((*b as f32) * 0.25) as u8;
-                format!(
-                    "\x1b[48;2;{};{};{};38;2;{};{};{}m{}\x1b[49m",
-                    bg_r,
-                    bg_g,
-                    bg_b,
-                    r,
-                    g,
-                    b,
-                    text.as_ref()
-                )
-            }
-            Self::Terminal256(index) => {
-                let (r, g, b) = Self::terminal_256_to_rgb(*index);
-                let bg_r = ((r as f32) * 0.25) as u8;
-                let bg_g = ((g as f32) * 0.25) as u8;
-                let bg_b = ((b as f32) * 0.25) as u8;
-                format!(
-                    "\x1b[48;2;{};{};{};38;2;{};{};{}m{}\x1b[49m",
-                    bg_r,
-                    bg_g,
-                    bg_b,
-                    r,
-                    g,
-                    b,
-                    text.as_ref()
-                )
-            }
+/// Color roles that can be set in a theme color file, matching [`ThemeColors`] fields
+pub const THEME_COLOR_KEYS: [&str; 14] = [
+    "personality",
+    "activity",
+    "directory",
+    "directory_icon",
+    "file",
+    "error",
+    "warning",
+    "success",
+    "info",
+    "separator",
+    "model_opus",
+    "model_sonnet",
+    "model_haiku",
+    "flow",
+];
+
+/// Check a theme color file and describe every problem found, with line context.
+///
+/// Each non-empty line that isn't a `#` comment must be `role = color`, where the
+/// role is one of [`THEME_COLOR_KEYS`] and the color is `#RRGGBB` or a 256-color
+/// index. Values may be quoted. An empty result means the file is valid.
+#[must_use]
+pub fn validate_theme_file(content: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let mut report = |message: String| {
+            problems.push(format!("line {}: `{text}`: {message}", index + 1));
+        };
+
+        let Some((key, value)) = text.split_once('=') else {
+            report("expected `role = color`".to_string());
+            continue;
+        };
+        let key = key.trim();
+        if !THEME_COLOR_KEYS.contains(&key) {
+            report(format!(
+                "unknown color role '{key}' (expected one of: {})",
+                THEME_COLOR_KEYS.join(", ")
+            ));
+            continue;
+        }
+        if let Err(message) = value.trim().trim_matches('"').parse::<Color>() {
+            report(message);
         }
     }
+
+    problems
 }
 
 /// Complete color scheme for a theme
@@ -132,141 +212,160 @@ pub struct ThemeColors {
     pub personality: Color,
     pub activity: Color,
     pub directory: Color,
+    /// Folder icon in the directory section, usually a muted take on `directory`
+    pub directory_icon: Color,
     pub file: Color,
     pub error: Color,
     pub warning: Color,
     pub success: Color,
     pub info: Color,
+    /// Separators between sections, a dim tone kept apart from the text colors
     pub separator: Color,
     pub model_opus: Color,
     pub model_sonnet: Color,
     pub model_haiku: Color,
+    /// Vivid color the personality shifts toward as momentum builds
+    pub flow: Color,
 }
 
 impl ThemeColors {
     /// Dark theme colors (current default)
     pub fn dark() -> Self {
         Self {
-            personality: Color::new(255, 255, 255), // White
-            activity: Color::new(0, 255, 255),      // Cyan
-            directory: Color::new(85, 170, 255),    // Light blue
-            file: Color::new(255, 255, 170),        // Light yellow
-            error: Color::new(255, 85, 85),         // Red
-            warning: Color::new(255, 184, 108),     // Orange
-            success: Color::new(80, 250, 123),      // Green
-            info: Color::new(139, 233, 253),        // Light cyan
-            separator: Color::new(128, 128, 128),   // Gray
-            model_opus: Color::new(255, 85, 255),   // Magenta
-            model_sonnet: Color::new(85, 255, 255), // Cyan
-            model_haiku: Color::new(85, 255, 85),   // Green
+            personality: Color::new(255, 255, 255),   // White
+            activity: Color::new(0, 255, 255),        // Cyan
+            directory: Color::new(85, 170, 255),      // Light blue
+            directory_icon: Color::new(60, 120, 180), // Muted blue
+            file: Color::new(255, 255, 170),          // Light yellow
+            error: Color::new(255, 85, 85),           // Red
+            warning: Color::new(255, 184, 108),       // Orange
+            success: Color::new(80, 250, 123),        // Green
+            info: Color::new(139, 233, 253),          // Light cyan
+            separator: Color::new(128, 128, 128),     // Gray
+            model_opus: Color::new(255, 85, 255),     // Magenta
+            model_sonnet: Color::new(85, 255, 255),   // Cyan
+            model_haiku: Color::new(85, 255, 85),     // Green
+            flow: Color::new(255, 200, 60),           // Gold
         }
     }
 
     /// Light theme colors
     pub fn light() -> Self {
         Self {
-            personality: Color::new(64, 64, 64),   // Dark gray
-            activity: Color::new(0, 102, 204),     // Blue
-            directory: Color::new(85, 85, 170),    // Dark blue
-            file: Color::new(170, 136, 0),         // Dark yellow
-            error: Color::new(170, 0, 0),          // Dark red
-            warning: Color::new(204, 102, 0),      // Dark orange
-            success: Color::new(0, 136, 0),        // Dark green
-            info: Color::new(0, 136, 170),         // Dark cyan
-            separator: Color::new(170, 170, 170),  // Light gray
-            model_opus: Color::new(170, 0, 170),   // Dark magenta
-            model_sonnet: Color::new(0, 136, 170), // Dark cyan
-            model_haiku: Color::new(0, 136, 0),    // Dark green
+            personality: Color::new(64, 64, 64),       // Dark gray
+            activity: Color::new(0, 102, 204),         // Blue
+            directory: Color::new(85, 85, 170),        // Dark blue
+            directory_icon: Color::new(140, 140, 190), // Muted blue-gray
+            file: Color::new(170, 136, 0),             // Dark yellow
+            error: Color::new(170, 0, 0),              // Dark red
+            warning: Color::new(204, 102, 0),          // Dark orange
+            success: Color::new(0, 136, 0),            // Dark green
+            info: Color::new(0, 136, 170),             // Dark cyan
+            separator: Color::new(170, 170, 170),      // Light gray
+            model_opus: Color::new(170, 0, 170),       // Dark magenta
+            model_sonnet: Color::new(0, 136, 170),     // Dark cyan
+            model_haiku: Color::new(0, 136, 0),        // Dark green
+            flow: Color::new(200, 140, 0),             // Dark gold
         }
     }
 
     /// Solarized Dark theme
     pub fn solarized() -> Self {
         Self {
-            personality: Color::from_hex(0xfdf6e3),  // Base3
-            activity: Color::from_hex(0x2aa198),     // Cyan
-            directory: Color::from_hex(0x268bd2),    // Blue
-            file: Color::from_hex(0xb58900),         // Yellow
-            error: Color::from_hex(0xdc322f),        // Red
-            warning: Color::from_hex(0xcb4b16),      // Orange
-            success: Color::from_hex(0x859900),      // Green
-            info: Color::from_hex(0x2aa198),         // Cyan
-            separator: Color::from_hex(0x586e75),    // Base01
-            model_opus: Color::from_hex(0xd33682),   // Magenta
-            model_sonnet: Color::from_hex(0x2aa198), // Cyan
-            model_haiku: Color::from_hex(0x859900),  // Green
+            personality: Color::from_hex(0xfdf6e3),    // Base3
+            activity: Color::from_hex(0x2aa198),       // Cyan
+            directory: Color::from_hex(0x268bd2),      // Blue
+            directory_icon: Color::from_hex(0x586e75), // Base01
+            file: Color::from_hex(0xb58900),           // Yellow
+            error: Color::from_hex(0xdc322f),          // Red
+            warning: Color::from_hex(0xcb4b16),        // Orange
+            success: Color::from_hex(0x859900),        // Green
+            info: Color::from_hex(0x2aa198),           // Cyan
+            separator: Color::from_hex(0x586e75),      // Base01
+            model_opus: Color::from_hex(0xd33682),     // Magenta
+            model_sonnet: Color::from_hex(0x2aa198),   // Cyan
+            model_haiku: Color::from_hex(0x859900),    // Green
+            flow: Color::from_hex(0xb58900),           // Yellow
         }
     }
 
     /// Dracula theme
     pub fn dracula() -> Self {
         Self {
-            personality: Color::from_hex(0xf8f8f2),  // Foreground
-            activity: Color::from_hex(0x8be9fd),     // Cyan
-            directory: Color::from_hex(0x8be9fd),    // Cyan
-            file: Color::from_hex(0xf1fa8c),         // Yellow
-            error: Color::from_hex(0xff5555),        // Red
-            warning: Color::from_hex(0xffb86c),      // Orange
-            success: Color::from_hex(0x50fa7b),      // Green
-            info: Color::from_hex(0xbd93f9),         // Purple
-            separator: Color::from_hex(0x6272a4),    // Comment
-            model_opus: Color::from_hex(0xff79c6),   // Pink
-            model_sonnet: Color::from_hex(0x8be9fd), // Cyan
-            model_haiku: Color::from_hex(0x50fa7b),  // Green
+            personality: Color::from_hex(0xf8f8f2),    // Foreground
+            activity: Color::from_hex(0x8be9fd),       // Cyan
+            directory: Color::from_hex(0x8be9fd),      // Cyan
+            directory_icon: Color::from_hex(0x6272a4), // Comment
+            file: Color::from_hex(0xf1fa8c),           // Yellow
+            error: Color::from_hex(0xff5555),          // Red
+            warning: Color::from_hex(0xffb86c),        // Orange
+            success: Color::from_hex(0x50fa7b),        // Green
+            info: Color::from_hex(0xbd93f9),           // Purple
+            separator: Color::from_hex(0x6272a4),      // Comment
+            model_opus: Color::from_hex(0xff79c6),     // Pink
+            model_sonnet: Color::from_hex(0x8be9fd),   // Cyan
+            model_haiku: Color::from_hex(0x50fa7b),    // Green
+            flow: Color::from_hex(0xf1fa8c),           // Yellow
         }
     }
 
     /// Nord theme
     pub fn nord() -> Self {
         Self {
-            personality: Color::from_hex(0xeceff4),  // Snow Storm
-            activity: Color::from_hex(0x88c0d0),     // Frost
-            directory: Color::from_hex(0x5e81ac),    // Frost
-            file: Color::from_hex(0xebcb8b),         // Aurora Yellow
-            error: Color::from_hex(0xbf616a),        // Aurora Red
-            warning: Color::from_hex(0xd08770),      // Aurora Orange
-            success: Color::from_hex(0xa3be8c),      // Aurora Green
-            info: Color::from_hex(0x81a1c1),         // Frost
-            separator: Color::from_hex(0x4c566a),    // Polar Night
-            model_opus: Color::from_hex(0xb48ead),   // Aurora Purple
-            model_sonnet: Color::from_hex(0x88c0d0), // Frost
-            model_haiku: Color::from_hex(0xa3be8c),  // Aurora Green
+            personality: Color::from_hex(0xeceff4),    // Snow Storm
+            activity: Color::from_hex(0x88c0d0),       // Frost
+            directory: Color::from_hex(0x5e81ac),      // Frost
+            directory_icon: Color::from_hex(0x4c566a), // Polar night
+            file: Color::from_hex(0xebcb8b),           // Aurora Yellow
+            error: Color::from_hex(0xbf616a),          // Aurora Red
+            warning: Color::from_hex(0xd08770),        // Aurora Orange
+            success: Color::from_hex(0xa3be8c),        // Aurora Green
+            info: Color::from_hex(0x81a1c1),           // Frost
+            separator: Color::from_hex(0x4c566a),      // Polar Night
+            model_opus: Color::from_hex(0xb48ead),     // Aurora Purple
+            model_sonnet: Color::from_hex(0x88c0d0),   // Frost
+            model_haiku: Color::from_hex(0xa3be8c),    // Aurora Green
+            flow: Color::from_hex(0xebcb8b),           // Aurora yellow
         }
     }
 
     /// Gruvbox theme
     pub fn gruvbox() -> Self {
         Self {
-            personality: Color::from_hex(0xfbf1c7),  // Light fg
-            activity: Color::from_hex(0x83a598),     // Blue
-            directory: Color::from_hex(0x83a598),    // Blue
-            file: Color::from_hex(0xd79921),         // Yellow
-            error: Color::from_hex(0xfb4934),        // Red
-            warning: Color::from_hex(0xfe8019),      // Orange
-            success: Color::from_hex(0xb8bb26),      // Green
-            info: Color::from_hex(0x8ec07c),         // Aqua
-            separator: Color::from_hex(0x665c54),    // Gray
-            model_opus: Color::from_hex(0xd3869b),   // Purple
-            model_sonnet: Color::from_hex(0x8ec07c), // Aqua
-            model_haiku: Color::from_hex(0xb8bb26),  // Green
+            personality: Color::from_hex(0xfbf1c7),    // Light fg
+            activity: Color::from_hex(0x83a598),       // Blue
+            directory: Color::from_hex(0x83a598),      // Blue
+            directory_icon: Color::from_hex(0x928374), // Gray
+            file: Color::from_hex(0xd79921),           // Yellow
+            error: Color::from_hex(0xfb4934),          // Red
+            warning: Color::from_hex(0xfe8019),        // Orange
+            success: Color::from_hex(0xb8bb26),        // Green
+            info: Color::from_hex(0x8ec07c),           // Aqua
+            separator: Color::from_hex(0x665c54),      // Gray
+            model_opus: Color::from_hex(0xd3869b),     // Purple
+            model_sonnet: Color::from_hex(0x8ec07c),   // Aqua
+            model_haiku: Color::from_hex(0xb8bb26),    // Green
+            flow: Color::from_hex(0xfabd2f),           // Bright yellow
         }
     }
 
     /// High contrast theme
     pub fn high_contrast() -> Self {
         Self {
-            personality: Color::new(255, 255, 255), // Pure white
-            activity: Color::new(0, 255, 255),      // Bright cyan
-            directory: Color::new(85, 170, 255),    // Light blue
-            file: Color::new(255, 255, 0),          // Pure yellow
-            error: Color::new(255, 0, 0),           // Pure red
-            warning: Color::new(255, 165, 0),       // Pure orange
-            success: Color::new(0, 255, 0),         // Pure green
-            info: Color::new(0, 191, 255),          // Bright blue
-            separator: Color::new(192, 192, 192),   // Light gray
-            model_opus: Color::new(255, 0, 255),    // Pure magenta
-            model_sonnet: Color::new(0, 255, 255),  // Pure cyan
-            model_haiku: Color::new(0, 255, 0),     // Pure green
+            personality: Color::new(255, 255, 255),   // Pure white
+            activity: Color::new(0, 255, 255),        // Bright cyan
+            directory: Color::new(85, 170, 255),      // Light blue
+            directory_icon: Color::new(85, 170, 255), // Light blue, kept vivid
+            file: Color::new(255, 255, 0),            // Pure yellow
+            error: Color::new(255, 0, 0),             // Pure red
+            warning: Color::new(255, 165, 0),         // Pure orange
+            success: Color::new(0, 255, 0),           // Pure green
+            info: Color::new(0, 191, 255),            // Bright blue
+            separator: Color::new(192, 192, 192),     // Light gray
+            model_opus: Color::new(255, 0, 255),      // Pure magenta
+            model_sonnet: Color::new(0, 255, 255),    // Pure cyan
+            model_haiku: Color::new(0, 255, 0),       // Pure green
+            flow: Color::new(255, 215, 0),            // Gold
         }
     }
 
@@ -276,6 +375,7 @@ impl ThemeColors {
             personality: Color::from_terminal_256(231), // Bright white
             activity: Color::from_terminal_256(69),     // Bright blue-cyan
             directory: Color::from_terminal_256(231),   // Bright white
+            directory_icon: Color::from_terminal_256(244), // Mid gray
             file: Color::from_terminal_256(231),        // Bright white
             error: Color::from_terminal_256(197),       // Bright red/pink
             warning: Color::from_terminal_256(208),     // Orange warning
@@ -285,6 +385,7 @@ impl ThemeColors {
             model_opus: Color::from_terminal_256(226),  // Yellow
             model_sonnet: Color::from_terminal_256(121), // Light purple
             model_haiku: Color::from_terminal_256(32),  // Teal
+            flow: Color::from_terminal_256(220),        // Gold
         }
     }
 }
@@ -317,6 +418,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_color_spec_parsing() {
+        assert!(matches!(
+            "#ff8040".parse::<Color>(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 64
+            })
+        ));
+        assert!(matches!(
+            "208".parse::<Color>(),
+            Ok(Color::Terminal256(208))
+        ));
+        assert!("300".parse::<Color>().unwrap_err().contains("out of range"));
+        assert!("#fff".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_valid_theme_file() {
+        let content = "# My theme\npersonality = #ffffff\nactivity = \"69\"\n\nseparator = 234\n";
+        assert!(validate_theme_file(content).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_hex_in_theme_file() {
+        let content = "personality = #ffffff\nerror = #GG0000\n";
+        let problems = validate_theme_file(content);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("line 2: `error = #GG0000`"));
+        assert!(problems[0].contains("invalid hex color '#GG0000'"));
+    }
+
+    #[test]
+    fn test_theme_file_reports_unknown_roles_and_bad_lines() {
+        let problems = validate_theme_file("backgrund = #000000\nwarning 208\n");
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("unknown color role 'backgrund'"));
+        assert!(problems[1].contains("expected `role = color`"));
+    }
+
     #[test]
     fn test_theme_colors_dark() {
         let colors = ThemeColors::dark();
@@ -339,6 +481,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_terminal_256_palette() {
+        assert_eq!(Color::terminal_256_to_rgb(15), (255, 255, 255));
+        assert_eq!(Color::terminal_256_to_rgb(197), (255, 0, 95));
+        assert_eq!(Color::terminal_256_to_rgb(226), (255, 255, 0));
+        assert_eq!(Color::terminal_256_to_rgb(244), (128, 128, 128));
+        assert_eq!(Color::terminal_256_to_rgb(255), (238, 238, 238));
+    }
+
     #[test]
     fn test_default_terminal_theme() {
         let colors = ThemeColors::default_terminal();
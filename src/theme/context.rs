@@ -1,13 +1,9 @@
 /// Simplified color selection for the Default theme
 ///
 /// This module provides consistent coloring with a single color per section.
+use crate::icons::model_family;
 use crate::theme::colors::Color;
 
-/// Get color for personalities in Default theme - always returns 231 (bright white)
-pub fn get_personality_color_256(_personality: &str) -> u8 {
-    231 // Consistent bright white for all personalities
-}
-
 /// Create a consistent color for personality (always 231 - bright white)
 pub fn get_context_aware_personality_color(_personality: &str) -> Color {
     Color::from_terminal_256(231)
@@ -15,14 +11,11 @@ pub fn get_context_aware_personality_color(_personality: &str) -> Color {
 
 /// Get model-specific color for the Default theme
 pub fn get_model_color_256(model_name: &str) -> u8 {
-    if model_name.to_lowercase().contains("opus") {
-        226 // Yellow
-    } else if model_name.to_lowercase().contains("sonnet") {
-        121 // Light purple
-    } else if model_name.to_lowercase().contains("haiku") {
-        32 // Teal
-    } else {
-        254 // Default to neutral color
+    match model_family(model_name) {
+        Some("Opus") => 226,   // Yellow
+        Some("Sonnet") => 121, // Light purple
+        Some("Haiku") => 32,   // Teal
+        _ => 254,              // Default to neutral color
     }
 }
 
@@ -38,23 +31,24 @@ mod tests {
 
     #[test]
     fn test_personality_color_mapping() {
-        // All personalities now return the same color (231 - bright white)
-        assert_eq!(get_personality_color_256("┗(▀̿Ĺ̯▀̿ ̿)┓ Git Manager"), 231);
-        assert_eq!(
-            get_personality_color_256("φ(．．) Documentation Writer"),
-            231
-        );
-        assert_eq!(
-            get_personality_color_256("(╯°□°)╯︵ ┻━┻ Table Flipper"),
-            231
-        );
-        assert_eq!(get_personality_color_256("(ノಠ益ಠ)ノ Error Warrior"), 231);
-        assert_eq!(get_personality_color_256("(つ◉益◉)つ Bug Hunter"), 231);
-        assert_eq!(get_personality_color_256("Search Maestro"), 231);
-        assert_eq!(get_personality_color_256("ლ(╹◡╹ლ) Cowder"), 231);
-        assert_eq!(get_personality_color_256("JS Master"), 231);
-        assert_eq!(get_personality_color_256("Chillin"), 231);
-        assert_eq!(get_personality_color_256("Editor User"), 231);
+        // All personalities return the same color (231 - bright white)
+        for personality in [
+            "┗(▀̿Ĺ̯▀̿ ̿)┓ Git Manager",
+            "φ(．．) Documentation Writer",
+            "(╯°□°)╯︵ ┻━┻ Table Flipper",
+            "(ノಠ益ಠ)ノ Error Warrior",
+            "(つ◉益◉)つ Bug Hunter",
+            "Search Maestro",
+            "ლ(╹◡╹ლ) Cowder",
+            "JS Master",
+            "Chillin",
+            "Editor User",
+        ] {
+            assert_eq!(
+                get_context_aware_personality_color(personality),
+                Color::from_terminal_256(231)
+            );
+        }
     }
 
     #[test]
@@ -66,5 +60,9 @@ mod tests {
         assert_eq!(get_model_color_256("Haiku"), 32);
         assert_eq!(get_model_color_256("Claude-3-Haiku"), 32);
         assert_eq!(get_model_color_256("GPT-4"), 254); // Unknown model
+        assert_eq!(get_model_color_256("claude-opus-4-20250101"), 226);
+        assert_eq!(get_model_color_256("opus4.5"), 226);
+        assert_eq!(get_model_color_256("claude-4-opus-thinking"), 226);
+        assert_eq!(get_model_color_256("opus-then-haiku"), 32);
     }
 }
@@ -7,8 +7,9 @@ pub mod presets;
 pub use colors::ThemeColors;
 
 /// Built-in theme options
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum Theme {
+    #[default]
     Default, // New terminal-colors theme
     Dark,
     Light,
@@ -19,12 +20,6 @@ pub enum Theme {
     HighContrast,
 }
 
-impl Default for Theme {
-    fn default() -> Self {
-        Self::Default // Use the new Default theme
-    }
-}
-
 impl Theme {
     /// Get all available themes
     pub fn all() -> Vec<Theme> {
@@ -40,6 +35,20 @@ impl Theme {
         ]
     }
 
+    /// Name used to pick the theme on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::Solarized => "solarized",
+            Theme::Dracula => "dracula",
+            Theme::Nord => "nord",
+            Theme::Gruvbox => "gruvbox",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
     /// Get theme display name
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -102,7 +111,13 @@ impl std::str::FromStr for Theme {
             "nord" => Ok(Theme::Nord),
             "gruvbox" | "gruvbox-dark" => Ok(Theme::Gruvbox),
             "high-contrast" | "highcontrast" => Ok(Theme::HighContrast),
-            _ => Err(format!("Unknown theme: {s}")),
+            _ => {
+                let valid: Vec<&str> = Self::all().iter().map(Theme::name).collect();
+                Err(format!(
+                    "Unknown theme: {s} (valid themes: {})",
+                    valid.join(", ")
+                ))
+            }
         }
     }
 }
@@ -121,6 +136,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_theme_names_round_trip() {
+        for theme in Theme::all() {
+            assert_eq!(theme.name().parse::<Theme>().unwrap(), theme);
+        }
+
+        let error = "neon".parse::<Theme>().unwrap_err();
+        assert!(error.contains("Unknown theme: neon"));
+        assert!(error.contains("dracula"));
+        assert!(error.contains("high-contrast"));
+    }
+
     #[test]
     fn test_theme_display() {
         assert_eq!(Theme::Dark.to_string(), "Dark");
@@ -139,7 +166,6 @@ mod tests {
                 }
                 crate::theme::colors::Color::Terminal256(_) => {
                     // Terminal256 colors are always valid
-                    assert!(true);
                 }
             }
         }
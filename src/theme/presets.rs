@@ -1,7 +1,26 @@
 use super::Theme;
-use crate::state::SessionState;
+use super::colors::{Color, ThemeColors};
+use crate::icons::model_family;
+use crate::state::{PersonalityModifier, SessionState};
 use crate::theme::context::{get_context_aware_model_color, get_context_aware_personality_color};
 
+/// Momentum just below the in-the-zone range; the flow shift starts after it
+const FLOW_START_MOMENTUM: u8 = 7;
+/// Momentum at which the personality reaches the full flow color
+const MAX_MOMENTUM: u8 = 10;
+
+/// Picks one color out of a theme's palette
+type PaletteColor = fn(&ThemeColors) -> Color;
+
+/// Conventional branch prefixes (the part before the first `/`) and the theme
+/// color each type of branch is shown in
+const BRANCH_TYPE_COLORS: [(&str, PaletteColor); 4] = [
+    ("feat", |colors| colors.success.clone()),
+    ("feature", |colors| colors.success.clone()),
+    ("fix", |colors| colors.warning.clone()),
+    ("hotfix", |colors| colors.error.clone()),
+];
+
 /// Helper functions for applying theme colors consistently
 impl Theme {
     /// Apply personality color with bold formatting
@@ -22,12 +41,33 @@ impl Theme {
         colors.directory.apply(text).to_string()
     }
 
+    /// Color used for the folder icon in the directory section
+    pub fn directory_icon_color(&self) -> Color {
+        self.colors().directory_icon
+    }
+
+    /// Apply the directory icon color, so the folder icon can be styled apart from the name
+    pub fn apply_directory_icon(&self, text: &str) -> String {
+        self.directory_icon_color().apply(text).to_string()
+    }
+
     /// Apply file color
     pub fn apply_file(&self, text: &str) -> String {
         let colors = self.colors();
         colors.file.apply(text).to_string()
     }
 
+    /// Color for a branch name: by its conventional prefix (`feat/`, `fix/`,
+    /// `hotfix/`) when `by_type` is set, otherwise the file color
+    pub fn branch_color(&self, branch: &str, by_type: bool) -> Color {
+        let colors = self.colors();
+        let prefix = branch.split_once('/').map(|(prefix, _)| prefix);
+        BRANCH_TYPE_COLORS
+            .iter()
+            .find(|(name, _)| by_type && prefix.is_some_and(|p| p.eq_ignore_ascii_case(name)))
+            .map_or_else(|| colors.file.clone(), |(_, color)| color(&colors))
+    }
+
     /// Apply error color
     pub fn apply_error(&self, text: &str) -> String {
         let colors = self.colors();
@@ -61,31 +101,34 @@ impl Theme {
     /// Apply model-specific color
     pub fn apply_model_color(&self, text: &str, model_name: &str) -> String {
         let colors = self.colors();
-        let color = if model_name.to_lowercase().contains("opus") {
-            &colors.model_opus
-        } else if model_name.to_lowercase().contains("sonnet") {
-            &colors.model_sonnet
-        } else if model_name.to_lowercase().contains("haiku") {
-            &colors.model_haiku
-        } else {
-            &colors.personality
+        let color = match model_family(model_name) {
+            Some("Opus") => &colors.model_opus,
+            Some("Sonnet") => &colors.model_sonnet,
+            Some("Haiku") => &colors.model_haiku,
+            _ => &colors.personality,
         };
         color.apply(text).to_string()
     }
 
     /// Apply personality color with context awareness for Default theme
     pub fn apply_personality_with_context(&self, text: &str, state: &SessionState) -> String {
-        match self {
-            Theme::Default => {
-                // Use context-aware coloring with bold for Default theme
-                let color = get_context_aware_personality_color(&state.personality);
-                color.apply_bold(text).to_string()
-            }
-            _ => {
-                // Use standard personality color for other themes
-                self.apply_personality(text)
-            }
+        self.personality_color(state).apply_bold(text).to_string()
+    }
+
+    /// Personality color for a session, shifting toward the theme's flow color
+    /// as momentum climbs from 8 to 10 while in the zone
+    pub fn personality_color(&self, state: &SessionState) -> Color {
+        let base = match self {
+            // Use context-aware coloring for Default theme
+            Theme::Default => get_context_aware_personality_color(&state.personality),
+            _ => self.colors().personality,
+        };
+        if state.mood.get_personality_modifier() != PersonalityModifier::InTheZone {
+            return base;
         }
+        let progress = f32::from(state.mood.momentum.saturating_sub(FLOW_START_MOMENTUM))
+            / f32::from(MAX_MOMENTUM - FLOW_START_MOMENTUM);
+        base.lerp(&self.colors().flow, progress)
     }
 
     /// Apply model color with context awareness for Default theme
@@ -122,6 +165,90 @@ mod tests {
         assert!(model.contains("Opus"));
     }
 
+    #[test]
+    fn test_directory_icon_color_differs_from_name() {
+        for theme in [Theme::Dark, Theme::Nord, Theme::Default] {
+            let icon = theme.directory_icon_color().apply("\u{f07b}");
+            let name = theme.colors().directory.apply("project");
+            assert!(icon.fgcolor.is_some());
+            assert_ne!(icon.fgcolor, name.fgcolor, "{theme}");
+        }
+    }
+
+    #[test]
+    fn test_separator_color_is_distinct_from_text() {
+        let dark = Theme::Dark.colors();
+        assert_ne!(dark.separator, dark.personality);
+
+        for theme in Theme::all() {
+            let colors = theme.colors();
+            assert!(
+                colors.separator.apply("|").fgcolor.is_some(),
+                "{theme} has no separator color"
+            );
+            for text in [&colors.personality, &colors.activity, &colors.file] {
+                assert_ne!(&colors.separator, text, "{theme}");
+            }
+            assert!(theme.apply_separator("|").contains('|'));
+        }
+    }
+
+    #[test]
+    fn test_momentum_shifts_personality_toward_flow() {
+        use crate::state::MoodState;
+
+        let with_mood = |frustration_level, momentum| SessionState {
+            mood: MoodState {
+                frustration_level,
+                momentum,
+                last_error_time: None,
+            },
+            ..Default::default()
+        };
+
+        for theme in Theme::all() {
+            let calm = theme.personality_color(&with_mood(0, 0));
+            let building = theme.personality_color(&with_mood(0, 9));
+            let flowing = theme.personality_color(&with_mood(0, 10));
+            let frustrated = theme.personality_color(&with_mood(10, 0));
+
+            assert_ne!(flowing, calm, "{theme}");
+            assert_ne!(flowing, frustrated, "{theme}");
+            assert_ne!(building, flowing, "{theme}");
+            assert_eq!(flowing, calm.lerp(&theme.colors().flow, 1.0), "{theme}");
+            // No shift before the zone
+            assert_eq!(theme.personality_color(&with_mood(0, 7)), calm, "{theme}");
+        }
+    }
+
+    #[test]
+    fn test_branch_color_by_type() {
+        for theme in Theme::all() {
+            let plain = theme.branch_color("main", true);
+            let feature = theme.branch_color("feat/x", true);
+            let hotfix = theme.branch_color("hotfix/y", true);
+
+            assert_eq!(plain, theme.colors().file, "{theme}");
+            assert_ne!(feature, plain, "{theme}");
+            assert_ne!(hotfix, plain, "{theme}");
+            assert_ne!(feature, hotfix, "{theme}");
+            assert_eq!(theme.branch_color("Fix/z", true), theme.colors().warning);
+            // Unknown prefixes and a disabled option keep the file color
+            assert_eq!(theme.branch_color("chore/deps", true), plain, "{theme}");
+            assert_eq!(theme.branch_color("feat/x", false), plain, "{theme}");
+        }
+    }
+
+    #[test]
+    fn test_color_lerp() {
+        let black = Color::new(0, 0, 0);
+        let white = Color::from_terminal_256(231);
+        assert_eq!(black.lerp(&white, 0.0), black);
+        assert_eq!(black.lerp(&white, 1.0), Color::new(255, 255, 255));
+        assert_eq!(black.lerp(&white, 0.5), Color::new(128, 128, 128));
+        assert_eq!(black.lerp(&white, 2.0), Color::new(255, 255, 255));
+    }
+
     #[test]
     fn test_model_color_matching() {
         let theme = Theme::Dark;
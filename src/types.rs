@@ -45,6 +45,14 @@ pub enum Activity {
     Deploying,
     /// Version control operations
     Committing,
+    /// Running linters and formatters
+    QualityChecking,
+    /// Fetching web pages
+    Browsing,
+    /// Downloading: cloning repositories, `curl`/`wget` downloads, fetch-only package commands
+    Fetching,
+    /// Planning and organizing work, e.g. updating the todo list
+    Planning,
 }
 
 impl Display for Activity {
@@ -70,6 +78,10 @@ impl Display for Activity {
             Activity::Documenting => "Documenting",
             Activity::Deploying => "Deploying",
             Activity::Committing => "Committing",
+            Activity::QualityChecking => "Quality Checking",
+            Activity::Browsing => "Browsing",
+            Activity::Fetching => "Fetching",
+            Activity::Planning => "Planning",
         };
         write!(f, "{s}")
     }
@@ -100,12 +112,81 @@ impl FromStr for Activity {
             "documenting" => Activity::Documenting,
             "deploying" => Activity::Deploying,
             "committing" => Activity::Committing,
+            "qualitychecking" | "quality-checking" | "quality checking" => {
+                Activity::QualityChecking
+            }
+            "browsing" => Activity::Browsing,
+            "fetching" => Activity::Fetching,
+            "planning" => Activity::Planning,
             _ => Activity::Working,
         })
     }
 }
 
 impl Activity {
+    /// A short present-progressive phrase describing the activity, e.g. `Running tests`
+    #[must_use]
+    pub fn phrase(&self) -> &'static str {
+        match self {
+            Activity::Editing => "Editing files",
+            Activity::Coding => "Writing code",
+            Activity::Configuring => "Tweaking config",
+            Activity::Navigating => "Looking around",
+            Activity::Writing => "Writing files",
+            Activity::Executing => "Running commands",
+            Activity::Reading => "Reading code",
+            Activity::Searching => "Searching the code",
+            Activity::Debugging => "Chasing a bug",
+            Activity::Testing => "Running tests",
+            Activity::Reviewing => "Reviewing changes",
+            Activity::Thinking => "Thinking it over",
+            Activity::Building => "Building the project",
+            Activity::Installing => "Installing packages",
+            Activity::Idle => "Taking a break",
+            Activity::Working => "Getting things done",
+            Activity::Refactoring => "Refactoring code",
+            Activity::Documenting => "Writing docs",
+            Activity::Deploying => "Shipping it",
+            Activity::Committing => "Committing changes",
+            Activity::QualityChecking => "Checking quality",
+            Activity::Browsing => "Browsing the web",
+            Activity::Fetching => "Downloading files",
+            Activity::Planning => "Planning the work",
+        }
+    }
+
+    /// Get all activities - convenience method for tests
+    #[cfg(test)]
+    #[must_use]
+    pub fn all() -> Vec<Activity> {
+        vec![
+            Activity::Editing,
+            Activity::Coding,
+            Activity::Configuring,
+            Activity::Navigating,
+            Activity::Writing,
+            Activity::Executing,
+            Activity::Reading,
+            Activity::Searching,
+            Activity::Debugging,
+            Activity::Testing,
+            Activity::Reviewing,
+            Activity::Thinking,
+            Activity::Building,
+            Activity::Installing,
+            Activity::Idle,
+            Activity::Working,
+            Activity::Refactoring,
+            Activity::Documenting,
+            Activity::Deploying,
+            Activity::Committing,
+            Activity::QualityChecking,
+            Activity::Browsing,
+            Activity::Fetching,
+            Activity::Planning,
+        ]
+    }
+
     /// Convert from string (case-insensitive) - convenience method for tests
     #[cfg(test)]
     #[must_use]
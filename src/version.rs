@@ -1,11 +1,17 @@
 use anyhow::{Context, Result, anyhow};
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const GITHUB_REPO: &str = "Mehdi-Hp/claude-code-personalities";
 const VERSION_CACHE_DURATION: Duration = Duration::from_secs(60 * 60); // 1 hour
 
+/// Number of attempts made for a GitHub API request before giving up
+const FETCH_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubles on each subsequent retry (200ms, 400ms)
+const FETCH_INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+
 /// Current version from Cargo.toml (set at compile time)
 pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -27,6 +33,13 @@ pub struct GitHubAsset {
     pub size: u64,
 }
 
+/// Name of the release binary built for `platform_target`, e.g.
+/// `claude-code-personalities-linux-x86_64` for `linux-x86_64`
+#[must_use]
+pub fn platform_asset_name(platform_target: &str) -> String {
+    format!("claude-code-personalities-{platform_target}")
+}
+
 /// Response from GitHub's compare API endpoint
 #[derive(Debug, Clone, Deserialize)]
 struct GitHubCompareResponse {
@@ -60,6 +73,69 @@ impl VersionCache {
     }
 }
 
+/// A failed fetch attempt, tagged with whether retrying could help
+#[derive(Debug)]
+struct FetchError {
+    error: anyhow::Error,
+    retryable: bool,
+}
+
+impl FetchError {
+    /// Transient failure (network error, 5xx) worth retrying
+    fn transient(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            retryable: true,
+        }
+    }
+
+    /// Permanent failure (404, rate limit, bad payload) that retrying won't fix
+    fn permanent(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            retryable: false,
+        }
+    }
+
+    /// Classify a non-success HTTP status. Only server errors are retried;
+    /// 404s and rate-limit responses (403/429) fail immediately.
+    fn from_status(status: reqwest::StatusCode, context: &str) -> Self {
+        let error = anyhow!("{context}: HTTP {status}");
+        if status.is_server_error() {
+            Self::transient(error)
+        } else {
+            Self::permanent(error)
+        }
+    }
+}
+
+/// Run `fetch` up to `attempts` times, sleeping with exponential backoff between
+/// retryable failures. Permanent failures are returned immediately.
+async fn fetch_with_retry<T, F, Fut>(
+    attempts: u32,
+    initial_backoff: Duration,
+    mut fetch: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, FetchError>>,
+{
+    let mut backoff = initial_backoff;
+    let mut attempt = 1;
+
+    loop {
+        match fetch().await {
+            Ok(value) => return Ok(value),
+            Err(failure) if failure.retryable && attempt < attempts => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(failure) => return Err(failure.error),
+        }
+    }
+}
+
 pub struct VersionManager {
     cache_path: std::path::PathBuf,
 }
@@ -229,7 +305,7 @@ impl VersionManager {
         release: &'a GitHubRelease,
         platform_target: &str,
     ) -> Option<&'a GitHubAsset> {
-        let expected_name = format!("claude-code-personalities-{platform_target}");
+        let expected_name = platform_asset_name(platform_target);
         release
             .assets
             .iter()
@@ -243,11 +319,21 @@ impl VersionManager {
             .with_context(|| format!("Failed to parse version from tag: {tag}"))
     }
 
-    /// Fetch latest release from GitHub API
+    /// Fetch latest release from GitHub API, retrying transient failures
     async fn fetch_latest_release(&self) -> Result<GitHubRelease> {
+        let client = reqwest::Client::new();
+        fetch_with_retry(FETCH_ATTEMPTS, FETCH_INITIAL_BACKOFF, || {
+            Self::fetch_latest_release_once(&client)
+        })
+        .await
+    }
+
+    /// Make a single request for the latest release
+    async fn fetch_latest_release_once(
+        client: &reqwest::Client,
+    ) -> std::result::Result<GitHubRelease, FetchError> {
         let url = format!("https://api.github.com/repos/{GITHUB_REPO}/releases/latest");
 
-        let client = reqwest::Client::new();
         let response = client
             .get(&url)
             .header(
@@ -257,19 +343,24 @@ impl VersionManager {
             .header("Accept", "application/vnd.github.v3+json")
             .send()
             .await
-            .with_context(|| format!("Failed to fetch latest release from GitHub: {url}"))?;
+            .with_context(|| format!("Failed to fetch latest release from GitHub: {url}"))
+            .map_err(FetchError::transient)?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("GitHub API error: HTTP {}", response.status()));
+            return Err(FetchError::from_status(
+                response.status(),
+                "GitHub API error",
+            ));
         }
 
         let release: GitHubRelease = response
             .json()
             .await
-            .with_context(|| "Failed to parse GitHub API response")?;
+            .with_context(|| "Failed to parse GitHub API response")
+            .map_err(FetchError::permanent)?;
 
         if release.draft {
-            return Err(anyhow!("Latest release is a draft"));
+            return Err(FetchError::permanent(anyhow!("Latest release is a draft")));
         }
 
         Ok(release)
@@ -572,6 +663,46 @@ mod tests {
         assert!(asset.is_none());
     }
 
+    #[test]
+    fn test_platform_asset_selection_per_platform() {
+        let vm = VersionManager::new().unwrap();
+        let targets = [
+            "macos-x86_64",
+            "macos-aarch64",
+            "linux-x86_64",
+            "linux-aarch64",
+        ];
+
+        // Every binary ships with a `.sha256` file that must never be picked instead
+        let assets = targets
+            .iter()
+            .flat_map(|target| {
+                let name = platform_asset_name(target);
+                [format!("{name}.sha256"), name]
+            })
+            .map(|name| GitHubAsset {
+                browser_download_url: format!("https://example.com/{name}"),
+                name,
+                size: 1024,
+            })
+            .collect();
+        let release = GitHubRelease {
+            tag_name: "v1.0.0".to_string(),
+            name: None,
+            body: None,
+            published_at: None,
+            assets,
+            prerelease: false,
+            draft: false,
+        };
+
+        for target in targets {
+            let asset = vm.find_platform_asset(&release, target).unwrap();
+            assert_eq!(asset.name, format!("claude-code-personalities-{target}"));
+        }
+        assert!(vm.find_platform_asset(&release, "linux-riscv64").is_none());
+    }
+
     #[test]
     fn test_version_cache_expiry() {
         let now = SystemTime::now()
@@ -645,6 +776,60 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_fetch_with_retry_recovers_from_transient_failures() {
+        let mut calls = 0;
+        let result = fetch_with_retry(3, Duration::from_millis(1), || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt < 3 {
+                    Err(FetchError::transient(anyhow!("connection reset")))
+                } else {
+                    Ok("v1.2.3")
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "v1.2.3");
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_with_retry_gives_up_after_attempts() {
+        let mut calls = 0;
+        let result: Result<()> = fetch_with_retry(3, Duration::from_millis(1), || {
+            calls += 1;
+            async { Err(FetchError::transient(anyhow!("connection reset"))) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_with_retry_does_not_retry_permanent_failures() {
+        for status in [
+            reqwest::StatusCode::NOT_FOUND,
+            reqwest::StatusCode::FORBIDDEN,
+        ] {
+            let mut calls = 0;
+            let result: Result<()> = fetch_with_retry(3, Duration::from_millis(1), || {
+                calls += 1;
+                async move { Err(FetchError::from_status(status, "GitHub API error")) }
+            })
+            .await;
+
+            assert!(result.unwrap_err().to_string().contains(status.as_str()));
+            assert_eq!(calls, 1, "HTTP {status} should not be retried");
+        }
+
+        let failure = FetchError::from_status(reqwest::StatusCode::BAD_GATEWAY, "GitHub API error");
+        assert!(failure.retryable);
+    }
+
     #[test]
     fn test_is_generic_changelog() {
         // Generic changelogs
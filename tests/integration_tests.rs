@@ -189,6 +189,36 @@ fn test_cli_help() {
     );
 }
 
+#[test]
+fn test_config_theme_set() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("personalities_config.json");
+
+    let output = Command::new("cargo")
+        .env("CLAUDE_PERSONALITIES_CONFIG", &config_path)
+        .args(["run", "--", "config", "theme", "set", "dracula"])
+        .output()
+        .expect("Failed to run config theme set");
+    assert!(output.status.success());
+
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(saved["theme"], "Dracula");
+
+    // Unknown names fail and leave the saved theme alone
+    let output = Command::new("cargo")
+        .env("CLAUDE_PERSONALITIES_CONFIG", &config_path)
+        .args(["run", "--", "config", "theme", "set", "neon"])
+        .output()
+        .expect("Failed to run config theme set");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("valid themes"));
+
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(saved["theme"], "Dracula");
+}
+
 #[test]
 fn test_invalid_json_input() {
     let invalid_json = "not json at all";